@@ -0,0 +1,49 @@
+use structopt::StructOpt;
+
+/// Path of the core used by default in the generated crates
+pub const CORE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../oito_core");
+
+/// Translate a Chip-8 ROM into a Rust crate
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    /// The ROM file to translate
+    pub file: String,
+    /// Directory where the crate will be generated
+    #[structopt(long = "out", short = "o", default_value = "out")]
+    pub out: String,
+    /// Name of the generated crate, by default it's derived from the ROM file name
+    #[structopt(long = "name", short = "n")]
+    pub name: Option<String>,
+    /// Path to the oito_core crate used by the generated crate
+    #[structopt(long = "core", short = "c", raw(default_value = "CORE_PATH"))]
+    pub core: String,
+}
+
+/// Builds a valid crate name out of the ROM file name
+pub fn crate_name(file_name: &str) -> String {
+    let stem = file_name.split('.').next().unwrap_or_default();
+    let name: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => name,
+        _ => format!("rom_{name}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn crate_name() {
+        assert_eq!("pong", super::crate_name("Pong.ch8"));
+        assert_eq!("space_invaders", super::crate_name("Space Invaders.ch8"));
+        assert_eq!("rom_15puzzle", super::crate_name("15puzzle.ch8"));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use oito_core::{instruction::Instruction, Address, OpCode};
+
+/// Address where the ROMs are loaded
+pub const STARTING_ADDRESS: Address = 0x200;
+/// Size in memory of each instruction
+pub const INSTRUCTION_SIZE: Address = 2;
+
+/// Basic block of the ROM: instructions run one after another without any jump between them
+#[derive(Debug, PartialEq)]
+pub struct Block {
+    /// Address of the first instruction of the block
+    pub start: Address,
+    /// Instructions of the block paired with their address
+    pub instructions: Vec<(Address, Instruction)>,
+}
+
+impl Block {
+    /// Returns the address following the last instruction of the block
+    pub fn end(&self) -> Address {
+        self.start + self.instructions.len() as Address * INSTRUCTION_SIZE
+    }
+
+    /// Returns true if the last instruction of the block sets the Program Counter by itself
+    pub fn is_terminated(&self) -> bool {
+        match self.instructions.last() {
+            Some((_, instruction)) => instruction.is_terminator(),
+            None => false,
+        }
+    }
+}
+
+/// Control flow graph of a ROM recovered statically from its entry point
+#[derive(Debug)]
+pub struct Cfg {
+    /// Basic blocks indexed by their starting address
+    pub blocks: BTreeMap<Address, Block>,
+}
+
+impl Cfg {
+    /// Recovers the control flow graph following every statically known path of the ROM.
+    /// Indirect jumps can't be followed, their targets will be left to the interpreter.
+    pub fn recover(rom: &[u8]) -> Self {
+        let mut code = BTreeMap::new();
+        let mut leaders = BTreeSet::from([STARTING_ADDRESS]);
+        let mut pending = vec![STARTING_ADDRESS];
+
+        while let Some(address) = pending.pop() {
+            if code.contains_key(&address) {
+                continue;
+            }
+            let instruction = match decode(rom, address) {
+                Some(instruction) => instruction,
+                None => continue,
+            };
+            code.insert(address, instruction);
+
+            let successors = successors(address, &instruction);
+            if ends_block(&instruction) {
+                leaders.extend(successors.iter());
+            }
+            pending.extend(successors);
+        }
+
+        let blocks = leaders
+            .iter()
+            .filter(|leader| code.contains_key(leader))
+            .map(|leader| (*leader, build_block(*leader, &code, &leaders)))
+            .collect();
+        Self { blocks }
+    }
+}
+
+/// Decodes the instruction stored in the address if it belongs to the ROM and it's valid
+fn decode(rom: &[u8], address: Address) -> Option<Instruction> {
+    let offset = address.checked_sub(STARTING_ADDRESS)? as usize;
+    let bytes = rom.get(offset..offset + 2)?;
+    let opcode = ((bytes[0] as OpCode) << 8) | bytes[1] as OpCode;
+    Instruction::try_from(opcode).ok()
+}
+
+/// Returns true if the instruction must be the last one of its block. These are the ones
+/// changing the control flow, and the ones writing into memory, as they could overwrite the
+/// code that follows.
+fn ends_block(instruction: &Instruction) -> bool {
+    use Instruction::*;
+    instruction.is_terminator() || matches!(instruction, LDrm(_) | LDvm(_))
+}
+
+/// Returns the addresses that can be run after the specified instruction
+fn successors(address: Address, instruction: &Instruction) -> Vec<Address> {
+    use Instruction::*;
+    let next = address + INSTRUCTION_SIZE;
+    match instruction {
+        SYS(target) | JP(target) => vec![*target],
+        CALL(target) => vec![*target, next],
+        RET | EXIT | JPr(_) => vec![],
+        SErb { .. } | SNErb { .. } | SErr { .. } | SNErr { .. } | SKP(_) | SKNP(_) => {
+            vec![next, next + INSTRUCTION_SIZE]
+        }
+        LDkr(_) => vec![address, next],
+        _ => vec![next],
+    }
+}
+
+/// Groups the instructions starting at the leader until the end of the block
+fn build_block(
+    start: Address,
+    code: &BTreeMap<Address, Instruction>,
+    leaders: &BTreeSet<Address>,
+) -> Block {
+    let mut instructions = Vec::new();
+    let mut address = start;
+    while let Some(instruction) = code.get(&address) {
+        instructions.push((address, *instruction));
+        address += INSTRUCTION_SIZE;
+        if ends_block(instruction) || leaders.contains(&address) {
+            break;
+        }
+    }
+    Block {
+        start,
+        instructions,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::instruction::Instruction;
+
+    use super::Cfg;
+
+    #[test]
+    fn recover() {
+        let rom = [
+            0x60, 0x00, // 0x200: V0 = 0
+            0x70, 0x01, // 0x202: V0 += 1
+            0x30, 0x10, // 0x204: skip if V0 == 0x10
+            0x12, 0x02, // 0x206: goto 0x202
+            0x22, 0x0C, // 0x208: call 0x20C
+            0x12, 0x0A, // 0x20A: goto 0x20A
+            0x00, 0xEE, // 0x20C: return
+            0xFF, 0xFF, // 0x20E: data
+        ];
+
+        let cfg = Cfg::recover(&rom);
+        let starts: Vec<_> = cfg.blocks.keys().copied().collect();
+        assert_eq!(vec![0x200, 0x202, 0x206, 0x208, 0x20A, 0x20C], starts);
+
+        let entry = &cfg.blocks[&0x200];
+        assert_eq!(1, entry.instructions.len());
+        assert!(!entry.is_terminated());
+        assert_eq!(0x202, entry.end());
+
+        let skip = &cfg.blocks[&0x202];
+        assert_eq!(
+            vec![
+                (0x202, Instruction::ADDbr { x: 0, byte: 0x01 }),
+                (0x204, Instruction::SErb { x: 0, byte: 0x10 })
+            ],
+            skip.instructions
+        );
+        assert!(skip.is_terminated());
+    }
+
+    #[test]
+    fn recover_writes() {
+        let rom = [
+            0xA2, 0x06, // 0x200: I = 0x206
+            0xF0, 0x55, // 0x202: store V0 at I
+            0x60, 0x01, // 0x204: V0 = 1
+            0x00, 0xEE, // 0x206: return
+        ];
+
+        let cfg = Cfg::recover(&rom);
+        assert_eq!(2, cfg.blocks[&0x200].instructions.len());
+        assert_eq!(2, cfg.blocks[&0x204].instructions.len());
+    }
+
+    #[test]
+    fn recover_indirect_jump() {
+        let rom = [
+            0xB2, 0x10, // 0x200: goto V0 + 0x210
+            0x60, 0x01, // 0x202: unreachable
+        ];
+
+        let cfg = Cfg::recover(&rom);
+        assert_eq!(1, cfg.blocks.len());
+        assert!(cfg.blocks[&0x200].is_terminated());
+    }
+
+    #[test]
+    fn recover_wait_key() {
+        let rom = [
+            0x60, 0x01, // 0x200: V0 = 1
+            0xF1, 0x0A, // 0x202: V1 = key()
+            0x12, 0x04, // 0x204: goto 0x204
+        ];
+
+        let cfg = Cfg::recover(&rom);
+        let starts: Vec<_> = cfg.blocks.keys().copied().collect();
+        assert_eq!(vec![0x200, 0x202, 0x204], starts);
+    }
+}
@@ -0,0 +1,350 @@
+use std::fmt::Write;
+
+use oito_core::{instruction::Instruction, Address};
+
+use crate::cfg::{Block, Cfg, INSTRUCTION_SIZE, STARTING_ADDRESS};
+
+/// Number of bytes written in each line of the embedded ROM
+const BYTES_PER_LINE: usize = 16;
+
+/// Generates the source of a Rust module running the ROM natively over the OitoCore runtime
+pub fn module(name: &str, rom: &[u8], cfg: &Cfg) -> String {
+    let mut src = String::new();
+    writeln!(
+        src,
+        "//! Native translation of the `{name}` Chip-8 ROM. Generated by oito's aot, do not edit."
+    )
+    .unwrap();
+    writeln!(src).unwrap();
+    writeln!(src, "use oito_core::core::runtime::Runtime;").unwrap();
+    writeln!(src, "use oito_core::exception::Exception;").unwrap();
+    writeln!(src).unwrap();
+
+    write_rom(&mut src, rom);
+    write_entry_points(&mut src, cfg);
+    for block in cfg.blocks.values() {
+        write_block(&mut src, rom, block);
+    }
+    src
+}
+
+/// Generates the manifest of the crate holding the translated ROM
+pub fn manifest(name: &str, core_path: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+description = "Native translation of a Chip-8 ROM running over OitoCore"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+oito_core = {{ path = "{core_path}" }}
+"#
+    )
+}
+
+/// Writes the ROM content as a constant, it's still loaded for the interpreter fallback
+fn write_rom(src: &mut String, rom: &[u8]) {
+    writeln!(src, "/// Content of the translated ROM").unwrap();
+    writeln!(src, "pub const ROM: [u8; {}] = [", rom.len()).unwrap();
+    for line in rom.chunks(BYTES_PER_LINE) {
+        writeln!(src, "    {},", byte_list(line)).unwrap();
+    }
+    writeln!(src, "];").unwrap();
+    writeln!(src).unwrap();
+}
+
+/// Writes the public functions to build the runtime and run the translated code
+fn write_entry_points(src: &mut String, cfg: &Cfg) {
+    src.push_str(
+        "/// Returns a runtime with the ROM loaded and ready to run
+pub fn runtime() -> Runtime {
+    let mut rt = Runtime::new();
+    rt.load(&ROM);
+    rt
+}
+
+/// Runs at least the specified number of instructions, always finishing the running block.
+/// The code that wasn't translated, or that was overwritten, runs in the interpreter.
+pub fn run(rt: &mut Runtime, cycles: usize) -> Result<(), Exception> {
+    let mut cycles = cycles;
+    while cycles > 0 && !rt.status().is_stopped() {
+        let ran = match rt.pc() {
+",
+    );
+    for start in cfg.blocks.keys() {
+        writeln!(src, "            {start:#05X} => block_{start:03x}(rt)?,").unwrap();
+    }
+    src.push_str(
+        "            _ => 0,
+        };
+        if ran == 0 {
+            rt.step()?;
+        }
+        cycles = cycles.saturating_sub(ran.max(1));
+    }
+    Ok(())
+}
+",
+    );
+}
+
+/// Writes the function running natively the basic block
+fn write_block(src: &mut String, rom: &[u8], block: &Block) {
+    let offset = (block.start - STARTING_ADDRESS) as usize;
+    let code = &rom[offset..offset + (block.end() - block.start) as usize];
+
+    writeln!(src).unwrap();
+    writeln!(
+        src,
+        "fn block_{:03x}(rt: &mut Runtime) -> Result<usize, Exception> {{",
+        block.start
+    )
+    .unwrap();
+    writeln!(
+        src,
+        "    if !rt.verify({:#05X}, &[{}]) {{",
+        block.start,
+        byte_list(code)
+    )
+    .unwrap();
+    writeln!(src, "        return Ok(0);").unwrap();
+    writeln!(src, "    }}").unwrap();
+    for (address, instruction) in &block.instructions {
+        writeln!(src, "    // {address:#05X}: {instruction:?}").unwrap();
+        for line in translate(*address, instruction) {
+            writeln!(src, "    {line}").unwrap();
+        }
+    }
+    if !block.is_terminated() {
+        writeln!(src, "    rt.jump({:#05X});", block.end()).unwrap();
+    }
+    writeln!(src, "    Ok({})", block.instructions.len()).unwrap();
+    writeln!(src, "}}").unwrap();
+}
+
+/// Translates the instruction into the Rust statements performing it
+fn translate(address: Address, instruction: &Instruction) -> Vec<String> {
+    use Instruction::*;
+    let next = address + INSTRUCTION_SIZE;
+    let skip = next + INSTRUCTION_SIZE;
+    let branch = |condition: String| {
+        vec![format!(
+            "rt.jump(if {condition} {{ {skip:#05X} }} else {{ {next:#05X} }});"
+        )]
+    };
+
+    match *instruction {
+        CLS => vec!["rt.cls();".to_string()],
+        RET => vec!["rt.ret()?;".to_string()],
+        EXIT => vec![format!("rt.exit({address:#05X});")],
+        SYS(target) | JP(target) => vec![format!("rt.jump({target:#05X});")],
+        CALL(target) => vec![format!("rt.call({next:#05X}, {target:#05X})?;")],
+        SErb { x, byte } => branch(format!("rt.v({x}) == {byte:#04X}")),
+        SNErb { x, byte } => branch(format!("rt.v({x}) != {byte:#04X}")),
+        SErr { x, y } => branch(format!("rt.v({x}) == rt.v({y})")),
+        SNErr { x, y } => branch(format!("rt.v({x}) != rt.v({y})")),
+        LDbr { x, byte } => vec![format!("rt.set_v({x}, {byte:#04X});")],
+        ADDbr { x, byte } => vec![format!(
+            "rt.set_v({x}, rt.v({x}).wrapping_add({byte:#04X}));"
+        )],
+        LDrr { x, y } => vec![format!("rt.set_v({x}, rt.v({y}));")],
+        OR { x, y } => vec![format!("rt.set_v({x}, rt.v({x}) | rt.v({y}));")],
+        AND { x, y } => vec![format!("rt.set_v({x}, rt.v({x}) & rt.v({y}));")],
+        XOR { x, y } => vec![format!("rt.set_v({x}, rt.v({x}) ^ rt.v({y}));")],
+        ADDrr { x, y } => vec![
+            format!("let (result, carry) = rt.v({x}).overflowing_add(rt.v({y}));"),
+            format!("rt.set_v({x}, result);"),
+            "rt.set_flag(carry as u8);".to_string(),
+        ],
+        SUB { x, y } => vec![
+            format!("let (result, borrow) = rt.v({x}).overflowing_sub(rt.v({y}));"),
+            format!("rt.set_v({x}, result);"),
+            "rt.set_flag(!borrow as u8);".to_string(),
+        ],
+        SUBN { x, y } => vec![
+            format!("let (result, borrow) = rt.v({y}).overflowing_sub(rt.v({x}));"),
+            format!("rt.set_v({x}, result);"),
+            "rt.set_flag(borrow as u8);".to_string(),
+        ],
+        SHR(x) => vec![
+            format!("let value = rt.v({x});"),
+            "rt.set_flag(value & 0x01);".to_string(),
+            format!("rt.set_v({x}, value >> 1);"),
+        ],
+        SHL(x) => vec![
+            format!("let value = rt.v({x});"),
+            "rt.set_flag(value >> 7);".to_string(),
+            format!("rt.set_v({x}, value << 1);"),
+        ],
+        LDi(target) => vec![format!("rt.set_i({target:#05X});")],
+        JPr(target) => vec![format!("rt.jump(rt.v(0) as u16 + {target:#05X});")],
+        RND { x, byte } => vec![
+            "let value = rt.random();".to_string(),
+            format!("rt.set_v({x}, {byte:#04X} & value);"),
+        ],
+        DRW { x, y, n } => vec![format!("rt.draw({x}, {y}, {n})?;")],
+        SKP(x) => branch(format!("rt.key({x})")),
+        SKNP(x) => branch(format!("!rt.key({x})")),
+        LDdr(x) => vec![format!("rt.set_v({x}, rt.dt());")],
+        LDkr(x) => vec![format!(
+            "rt.jump(if rt.wait_key({x}) {{ {next:#05X} }} else {{ {address:#05X} }});"
+        )],
+        LDrd(x) => vec![format!("rt.set_dt(rt.v({x}));")],
+        LDrs(x) => vec![format!("rt.set_st(rt.v({x}));")],
+        ADDri(x) => vec![format!("rt.set_i(rt.i().wrapping_add(rt.v({x}) as u16));")],
+        LDmi(x) => vec![format!("rt.font({x});")],
+        LDhi(x) => vec![format!("rt.big_font({x});")],
+        LDrm(x) => vec![format!("rt.bcd({x})?;")],
+        LDvm(x) => vec![format!("rt.store({x})?;")],
+        LDmv(x) => vec![format!("rt.restore({x})?;")],
+    }
+}
+
+/// Formats the bytes as a list of hexadecimal literals
+fn byte_list(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:#04X}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path, process::Command};
+
+    use oito_core::instruction::Instruction;
+
+    use crate::{args::CORE_PATH, cfg::Cfg};
+
+    /// Target checked along the host one when it's installed
+    const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+    const ROM: [u8; 6] = [
+        0x60, 0x05, // 0x200: V0 = 5
+        0x80, 0x04, // 0x202: V0 += V0
+        0x12, 0x04, // 0x204: goto 0x204
+    ];
+
+    #[test]
+    fn module() {
+        let src = super::module("test.ch8", &ROM, &Cfg::recover(&ROM));
+
+        assert!(
+            src.contains("pub const ROM: [u8; 6] = [\n    0x60, 0x05, 0x80, 0x04, 0x12, 0x04,\n];")
+        );
+        assert!(src.contains("            0x200 => block_200(rt)?,\n"));
+        assert!(src.contains("            0x204 => block_204(rt)?,\n"));
+        assert!(src.contains("fn block_200(rt: &mut Runtime) -> Result<usize, Exception> {\n"));
+        assert!(src.contains("    if !rt.verify(0x200, &[0x60, 0x05, 0x80, 0x04]) {\n"));
+        assert!(src.contains("    rt.jump(0x204);\n    Ok(2)\n}"));
+    }
+
+    #[test]
+    fn manifest() {
+        let manifest = super::manifest("pong", "../oito_core");
+
+        assert!(manifest.contains("name = \"pong\""));
+        assert!(manifest.contains("oito_core = { path = \"../oito_core\" }"));
+    }
+
+    #[test]
+    fn translate() {
+        assert_eq!(
+            vec!["rt.jump(if rt.v(1) == 0x0A { 0x306 } else { 0x304 });"],
+            super::translate(0x302, &Instruction::SErb { x: 1, byte: 0x0A })
+        );
+        assert_eq!(
+            vec!["rt.exit(0x302);"],
+            super::translate(0x302, &Instruction::EXIT)
+        );
+        assert_eq!(
+            vec!["rt.call(0x304, 0x400)?;"],
+            super::translate(0x302, &Instruction::CALL(0x400))
+        );
+        assert_eq!(
+            vec!["rt.jump(if rt.wait_key(3) { 0x304 } else { 0x302 });"],
+            super::translate(0x302, &Instruction::LDkr(3))
+        );
+        assert_eq!(
+            vec![
+                "let (result, borrow) = rt.v(2).overflowing_sub(rt.v(1));",
+                "rt.set_v(1, result);",
+                "rt.set_flag(borrow as u8);"
+            ],
+            super::translate(0x302, &Instruction::SUBN { x: 1, y: 2 })
+        );
+    }
+
+    #[test]
+    fn generated_crate_compiles() {
+        let rom = include_bytes!("../../rom_loader/test/test_opcode.ch8");
+        let dir = env::temp_dir().join(format!("oito_aot_{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            super::manifest("test_opcode", CORE_PATH),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("src").join("lib.rs"),
+            super::module("test_opcode.ch8", rom, &Cfg::recover(rom)),
+        )
+        .unwrap();
+        // reuses the resolution of this crate, so the check doesn't need to reach the registry
+        fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock"),
+            dir.join("Cargo.lock"),
+        )
+        .unwrap();
+
+        let host = cargo_check(&dir, None);
+        let wasm = wasm_installed().then(|| cargo_check(&dir, Some(WASM_TARGET)));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(host, "the generated crate doesn't compile for the host");
+        assert!(
+            wasm.unwrap_or(true),
+            "the generated crate doesn't compile for {WASM_TARGET}"
+        );
+    }
+
+    /// Runs `cargo check` on the crate, sharing the build directory of this one
+    fn cargo_check(dir: &Path, target: Option<&str>) -> bool {
+        let mut cargo = Command::new(env!("CARGO"));
+        cargo
+            .arg("check")
+            .arg("--manifest-path")
+            .arg(dir.join("Cargo.toml"))
+            .env(
+                "CARGO_TARGET_DIR",
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("target")
+                    .join("generated"),
+            );
+        if let Some(target) = target {
+            cargo.args(["--target", target]);
+        }
+        cargo.status().unwrap().success()
+    }
+
+    /// Checks if the standard library of the wasm target is in the sysroot
+    fn wasm_installed() -> bool {
+        Command::new("rustc")
+            .args(["--print", "sysroot"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+            .is_ok_and(|sysroot| {
+                Path::new(&sysroot)
+                    .join("lib/rustlib")
+                    .join(WASM_TARGET)
+                    .exists()
+            })
+    }
+
+    #[test]
+    fn byte_list() {
+        assert_eq!("0x00, 0xAB", super::byte_list(&[0x00, 0xAB]));
+    }
+}
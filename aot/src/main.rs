@@ -0,0 +1,41 @@
+use cfg::Cfg;
+use rom_loader::{desktop::FilePathLoader, RomLoader};
+use std::{error::Error, fs, path::Path};
+use structopt::StructOpt;
+
+mod args;
+mod cfg;
+mod codegen;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = args::Args::from_args();
+
+    let loader = FilePathLoader::new(&args.file);
+    let rom = loader.rom();
+    let file_name = Path::new(&args.file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let crate_name = args.name.unwrap_or_else(|| args::crate_name(&file_name));
+
+    let cfg = Cfg::recover(rom);
+    let translated: usize = cfg.blocks.values().map(|b| b.instructions.len()).sum();
+
+    let out = Path::new(&args.out);
+    fs::create_dir_all(out.join("src"))?;
+    fs::write(
+        out.join("Cargo.toml"),
+        codegen::manifest(&crate_name, &args.core),
+    )?;
+    fs::write(
+        out.join("src").join("lib.rs"),
+        codegen::module(&file_name, rom, &cfg),
+    )?;
+
+    println!(
+        "{file_name}: {} blocks with {translated} instructions translated into {}",
+        cfg.blocks.len(),
+        out.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use std::env;
+
+/// Generates the C header of the API in the build directory, a test checks that `include/oito.h` matches it
+fn main() {
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out = env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&dir)
+        .expect("the API is exportable to C")
+        .write_to_file(format!("{out}/oito.h"));
+}
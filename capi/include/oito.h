@@ -0,0 +1,136 @@
+/* C API of the OitoCore Chip-8 emulator. Generated by cbindgen from the capi crate, do not edit. */
+
+#ifndef OITO_H
+#define OITO_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Width of the screen in pixels
+#define OITO_SCREEN_WIDTH 64
+
+// Height of the screen in pixels
+#define OITO_SCREEN_HEIGHT 32
+
+// Result of the fallible functions, with a variant for each exception of the emulator
+typedef enum OitoError {
+  // Successful call
+  OITO_ERROR_OK = 0,
+  // A pointer argument was null
+  OITO_ERROR_NULL_POINTER = 1,
+  // An argument was out of its range, like a key over 0xF
+  OITO_ERROR_INVALID_ARGUMENT = 2,
+  // Access to an address out of the memory
+  OITO_ERROR_SEGMENTATION_FAULT = 3,
+  // Call with the stack full
+  OITO_ERROR_STACK_OVERFLOW = 4,
+  // Return with the stack empty
+  OITO_ERROR_STACK_UNDERFLOW = 5,
+  // Write into a protected memory region
+  OITO_ERROR_PROTECTED_WRITE = 6,
+  // Unknown instruction
+  OITO_ERROR_WRONG_OP_CODE = 7,
+  // Saved state not valid
+  OITO_ERROR_INVALID_STATE = 8,
+} OitoError;
+
+// Emulator behind the handles of the API
+typedef struct Oito Oito;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns a new emulator with the default settings, to destroy with `oito_free`
+struct Oito *oito_new(void);
+
+// Returns a new emulator whose random numbers are generated from the seed, to destroy with `oito_free`
+struct Oito *oito_new_seeded(uint64_t seed);
+
+// Destroys the emulator. Null is ignored.
+//
+// # Safety
+// The emulator must have been returned by `oito_new` and not freed yet.
+void oito_free(struct Oito *oito);
+
+// Loads the ROM of the length in the emulator
+//
+// # Safety
+// The emulator must be alive and the ROM must point to `len` readable bytes.
+enum OitoError oito_load(struct Oito *oito, const uint8_t *rom, size_t len);
+
+// Runs an instruction
+//
+// # Safety
+// The emulator must be alive.
+enum OitoError oito_tick(struct Oito *oito);
+
+// Decreases the timers, to call once per frame
+//
+// # Safety
+// The emulator must be alive.
+enum OitoError oito_frame_tick(struct Oito *oito);
+
+// Returns the frame buffer, `OITO_SCREEN_WIDTH * OITO_SCREEN_HEIGHT` pixels row by row, true if drawn.
+// Null if the emulator is null.
+//
+// # Safety
+// The emulator must be alive. The buffer is unpacked from the rows on each call,
+// and it's valid until the next call to this function with the same emulator.
+const bool *oito_frame_buffer(const struct Oito *oito);
+
+// Returns the frame packed in `OITO_SCREEN_HEIGHT` rows, the leftmost pixel as the most significant bit.
+// Null if the emulator is null.
+//
+// # Safety
+// The emulator must be alive. The rows are valid until the next call changing the emulator.
+const uint64_t *oito_frame_rows(const struct Oito *oito);
+
+// Presses the key of the value, from 0x0 to 0xF
+//
+// # Safety
+// The emulator must be alive.
+enum OitoError oito_key_press(struct Oito *oito, uint8_t key);
+
+// Releases the key of the value, from 0x0 to 0xF
+//
+// # Safety
+// The emulator must be alive.
+enum OitoError oito_key_release(struct Oito *oito, uint8_t key);
+
+// Returns true if the sound is playing, false if it's not or the emulator is null
+//
+// # Safety
+// The emulator must be alive.
+bool oito_sound(const struct Oito *oito);
+
+// Returns true if the emulator won't run more instructions, because the ROM halted or a limit was reached
+//
+// # Safety
+// The emulator must be alive.
+bool oito_stopped(const struct Oito *oito);
+
+// Returns the number of instructions run
+//
+// # Safety
+// The emulator must be alive.
+uint64_t oito_instructions(const struct Oito *oito);
+
+// Returns the description of the last exception raised by the emulator, empty if there was none.
+// Null if the emulator is null.
+//
+// # Safety
+// The emulator must be alive. The description is valid until the next call changing the emulator.
+const char *oito_last_error(const struct Oito *oito);
+
+// Returns the static description of the error code, or "unknown" if it isn't an `OitoError`
+const char *oito_error_name(int error);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* OITO_H */
@@ -0,0 +1,383 @@
+//! C API of the OitoCore. The emulators are handled through opaque pointers created with `oito_new`
+//! and destroyed with `oito_free`, and the fallible functions return an `OitoError` code.
+
+use std::{
+    cell::Cell,
+    ffi::{c_char, c_int, CString},
+    ptr, slice,
+};
+
+use oito_core::{config::Config, core::OitoCore, exception::Exception, key::Key};
+
+/// Width of the screen in pixels
+pub const OITO_SCREEN_WIDTH: usize = 64;
+/// Height of the screen in pixels
+pub const OITO_SCREEN_HEIGHT: usize = 32;
+
+/// Result of the fallible functions, with a variant for each exception of the emulator
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OitoError {
+    /// Successful call
+    Ok = 0,
+    /// A pointer argument was null
+    NullPointer = 1,
+    /// An argument was out of its range, like a key over 0xF
+    InvalidArgument = 2,
+    /// Access to an address out of the memory
+    SegmentationFault = 3,
+    /// Call with the stack full
+    StackOverflow = 4,
+    /// Return with the stack empty
+    StackUnderflow = 5,
+    /// Write into a protected memory region
+    ProtectedWrite = 6,
+    /// Unknown instruction
+    WrongOpCode = 7,
+    /// Saved state not valid
+    InvalidState = 8,
+}
+
+impl From<&Exception> for OitoError {
+    fn from(exception: &Exception) -> Self {
+        match exception {
+            Exception::SegmentationFault(_) => OitoError::SegmentationFault,
+            Exception::StackOverflow => OitoError::StackOverflow,
+            Exception::StackUnderflow => OitoError::StackUnderflow,
+            Exception::ProtectedWrite(_) => OitoError::ProtectedWrite,
+            Exception::WrongOpCode(_) => OitoError::WrongOpCode,
+            Exception::InvalidState => OitoError::InvalidState,
+        }
+    }
+}
+
+/// Emulator behind the handles of the API
+pub struct Oito {
+    core: OitoCore,
+    /// Description of the last exception, kept alive for `oito_last_error`
+    error: CString,
+    /// Pixels of the frame unpacked by the last `oito_frame_buffer`, kept alive for the caller
+    pixels: Cell<[bool; OITO_SCREEN_WIDTH * OITO_SCREEN_HEIGHT]>,
+}
+
+impl Oito {
+    /// Returns the result of the call, keeping the description of the exception if it failed
+    fn check(&mut self, result: Result<(), Exception>) -> OitoError {
+        match result {
+            Ok(()) => OitoError::Ok,
+            Err(exception) => {
+                let code = OitoError::from(&exception);
+                self.error = CString::new(exception.to_string()).unwrap_or_default();
+                code
+            }
+        }
+    }
+}
+
+/// Returns a new emulator with the default settings, to destroy with `oito_free`
+#[no_mangle]
+pub extern "C" fn oito_new() -> *mut Oito {
+    with_config(Config::default())
+}
+
+/// Returns a new emulator whose random numbers are generated from the seed, to destroy with `oito_free`
+#[no_mangle]
+pub extern "C" fn oito_new_seeded(seed: u64) -> *mut Oito {
+    with_config(Config {
+        seed: Some(seed),
+        ..Default::default()
+    })
+}
+
+/// Destroys the emulator. Null is ignored.
+///
+/// # Safety
+/// The emulator must have been returned by `oito_new` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn oito_free(oito: *mut Oito) {
+    if !oito.is_null() {
+        drop(Box::from_raw(oito));
+    }
+}
+
+/// Loads the ROM of the length in the emulator
+///
+/// # Safety
+/// The emulator must be alive and the ROM must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn oito_load(oito: *mut Oito, rom: *const u8, len: usize) -> OitoError {
+    let Some(oito) = oito.as_mut() else {
+        return OitoError::NullPointer;
+    };
+    if rom.is_null() {
+        return OitoError::NullPointer;
+    }
+    oito.core.load(slice::from_raw_parts(rom, len));
+    OitoError::Ok
+}
+
+/// Runs an instruction
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_tick(oito: *mut Oito) -> OitoError {
+    match oito.as_mut() {
+        Some(oito) => {
+            let result = oito.core.tick();
+            oito.check(result)
+        }
+        None => OitoError::NullPointer,
+    }
+}
+
+/// Decreases the timers, to call once per frame
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_frame_tick(oito: *mut Oito) -> OitoError {
+    match oito.as_mut() {
+        Some(oito) => {
+            oito.core.frame_tick();
+            OitoError::Ok
+        }
+        None => OitoError::NullPointer,
+    }
+}
+
+/// Returns the frame buffer, `OITO_SCREEN_WIDTH * OITO_SCREEN_HEIGHT` pixels row by row, true if drawn.
+/// Null if the emulator is null.
+///
+/// # Safety
+/// The emulator must be alive. The buffer is unpacked from the rows on each call,
+/// and it's valid until the next call to this function with the same emulator.
+#[no_mangle]
+pub unsafe extern "C" fn oito_frame_buffer(oito: *const Oito) -> *const bool {
+    oito.as_ref().map_or(ptr::null(), |oito| {
+        let mut pixels = [false; OITO_SCREEN_WIDTH * OITO_SCREEN_HEIGHT];
+        for (pixel, lit) in pixels.iter_mut().zip(oito.core.frame_buffer()) {
+            *pixel = lit;
+        }
+        oito.pixels.set(pixels);
+        oito.pixels.as_ptr().cast()
+    })
+}
+
+/// Returns the frame packed in `OITO_SCREEN_HEIGHT` rows, the leftmost pixel as the most significant bit.
+/// Null if the emulator is null.
+///
+/// # Safety
+/// The emulator must be alive. The rows are valid until the next call changing the emulator.
+#[no_mangle]
+pub unsafe extern "C" fn oito_frame_rows(oito: *const Oito) -> *const u64 {
+    oito.as_ref()
+        .map_or(ptr::null(), |oito| oito.core.frame_rows().as_ptr())
+}
+
+/// Presses the key of the value, from 0x0 to 0xF
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_key_press(oito: *mut Oito, key: u8) -> OitoError {
+    with_key(oito, key, OitoCore::key_press)
+}
+
+/// Releases the key of the value, from 0x0 to 0xF
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_key_release(oito: *mut Oito, key: u8) -> OitoError {
+    with_key(oito, key, OitoCore::key_release)
+}
+
+/// Returns true if the sound is playing, false if it's not or the emulator is null
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_sound(oito: *const Oito) -> bool {
+    oito.as_ref().is_some_and(|oito| oito.core.sound())
+}
+
+/// Returns true if the emulator won't run more instructions, because the ROM halted or a limit was reached
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_stopped(oito: *const Oito) -> bool {
+    oito.as_ref()
+        .is_some_and(|oito| oito.core.status().is_stopped())
+}
+
+/// Returns the number of instructions run
+///
+/// # Safety
+/// The emulator must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn oito_instructions(oito: *const Oito) -> u64 {
+    oito.as_ref().map_or(0, |oito| oito.core.instructions())
+}
+
+/// Returns the description of the last exception raised by the emulator, empty if there was none.
+/// Null if the emulator is null.
+///
+/// # Safety
+/// The emulator must be alive. The description is valid until the next call changing the emulator.
+#[no_mangle]
+pub unsafe extern "C" fn oito_last_error(oito: *const Oito) -> *const c_char {
+    oito.as_ref()
+        .map_or(ptr::null(), |oito| oito.error.as_ptr())
+}
+
+/// Returns the static description of the error code, or "unknown" if it isn't an `OitoError`
+#[no_mangle]
+pub extern "C" fn oito_error_name(error: c_int) -> *const c_char {
+    // C callers can pass any integer, so it's not read as an `OitoError` until it's known to be one
+    let name: &'static [u8] = match error {
+        0 => b"ok\0",
+        1 => b"null pointer\0",
+        2 => b"invalid argument\0",
+        3 => b"segmentation fault\0",
+        4 => b"stack overflow\0",
+        5 => b"stack underflow\0",
+        6 => b"protected write\0",
+        7 => b"wrong opcode\0",
+        8 => b"invalid state\0",
+        _ => b"unknown\0",
+    };
+    name.as_ptr() as *const c_char
+}
+
+/// Returns the handle of a new emulator with the settings
+fn with_config(config: Config) -> *mut Oito {
+    Box::into_raw(Box::new(Oito {
+        core: OitoCore::with_config(config),
+        error: CString::default(),
+        pixels: Cell::new([false; OITO_SCREEN_WIDTH * OITO_SCREEN_HEIGHT]),
+    }))
+}
+
+/// Applies the action to the key of the value
+unsafe fn with_key(oito: *mut Oito, key: u8, action: fn(&mut OitoCore, Key)) -> OitoError {
+    let Some(oito) = oito.as_mut() else {
+        return OitoError::NullPointer;
+    };
+    match Key::try_from(key) {
+        Ok(key) => {
+            action(&mut oito.core, key);
+            OitoError::Ok
+        }
+        Err(_) => OitoError::InvalidArgument,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn lifecycle() {
+        // LD V0, 0x07; LD ST, V0; RET
+        let rom = [0x60, 0x07, 0xF0, 0x18, 0x00, 0xEE];
+        unsafe {
+            let oito = oito_new();
+            assert_eq!(OitoError::Ok, oito_load(oito, rom.as_ptr(), rom.len()));
+            assert_eq!(OitoError::Ok, oito_tick(oito));
+            assert!(!oito_sound(oito));
+            assert_eq!(OitoError::Ok, oito_tick(oito));
+            assert!(oito_sound(oito));
+            assert_eq!(2, oito_instructions(oito));
+
+            assert_eq!(OitoError::StackUnderflow, oito_tick(oito));
+            assert_eq!(
+                "Stack underflow",
+                CStr::from_ptr(oito_last_error(oito)).to_str().unwrap()
+            );
+            oito_free(oito);
+        }
+    }
+
+    #[test]
+    fn reload() {
+        let halting = [0x60, 0x07, 0x12, 0x02];
+        let rom = [0x61, 0x03, 0x12, 0x02];
+        unsafe {
+            let oito = oito_new();
+            assert_eq!(
+                OitoError::Ok,
+                oito_load(oito, halting.as_ptr(), halting.len())
+            );
+            assert_eq!(OitoError::Ok, oito_tick(oito));
+            assert_eq!(OitoError::Ok, oito_tick(oito));
+            assert!(oito_stopped(oito));
+
+            assert_eq!(OitoError::Ok, oito_load(oito, rom.as_ptr(), rom.len()));
+            assert!(!oito_stopped(oito));
+            assert_eq!(OitoError::Ok, oito_tick(oito));
+            assert_eq!(1, oito_instructions(oito));
+            assert_eq!(3, (*oito).core.registers().v[1]);
+            oito_free(oito);
+        }
+    }
+
+    #[test]
+    fn keys() {
+        unsafe {
+            let oito = oito_new_seeded(1);
+            assert_eq!(OitoError::Ok, oito_key_press(oito, 0xF));
+            assert_eq!(0x8000, (*oito).core.keys());
+            assert_eq!(OitoError::Ok, oito_key_release(oito, 0xF));
+            assert_eq!(OitoError::InvalidArgument, oito_key_press(oito, 0x10));
+            oito_free(oito);
+        }
+    }
+
+    #[test]
+    fn screen() {
+        assert_eq!(oito_core::SCREEN_WIDTH, OITO_SCREEN_WIDTH);
+        assert_eq!(oito_core::SCREEN_HEIGHT, OITO_SCREEN_HEIGHT);
+    }
+
+    #[test]
+    fn null() {
+        unsafe {
+            assert_eq!(OitoError::NullPointer, oito_tick(ptr::null_mut()));
+            let oito = oito_new();
+            assert_eq!(OitoError::NullPointer, oito_load(oito, ptr::null(), 0));
+            oito_free(oito);
+            assert!(oito_frame_buffer(ptr::null()).is_null());
+            oito_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn error_name() {
+        let name = |error| {
+            unsafe { CStr::from_ptr(oito_error_name(error)) }
+                .to_str()
+                .unwrap()
+        };
+        assert_eq!("ok", name(OitoError::Ok as c_int));
+        assert_eq!("wrong opcode", name(OitoError::WrongOpCode as c_int));
+        assert_eq!("invalid state", name(OitoError::InvalidState as c_int));
+        assert_eq!("unknown", name(9));
+        assert_eq!("unknown", name(-1));
+    }
+
+    #[test]
+    fn header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/oito.h"));
+        let header =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/include/oito.h"));
+        assert!(
+            header.is_ok_and(|header| header == generated),
+            "include/oito.h is outdated, replace it with {}/oito.h",
+            env!("OUT_DIR")
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Compiles the C program of `tests/embed.c` against the generated header and the static library,
+//! and runs it. The compiler is `cc` unless the `CC` environment variable picks another one.
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn embed() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The tests are built in target/<profile>/deps, next to the libraries of the crate
+    let exe = env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    let program = deps.join("embed");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest.join("tests/embed.c"))
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg(deps.join("liboito.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-Wall", "-Werror", "-o"])
+        .arg(&program)
+        .status()
+        .expect("a C compiler is installed");
+    assert!(status.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!("ok\n", String::from_utf8_lossy(&output.stdout));
+}
@@ -0,0 +1,77 @@
+/* Embeds the emulator through the C API, exiting with the number of failed checks */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "oito.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                  \
+    do {                                                                  \
+        if (!(condition)) {                                               \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); \
+            failures++;                                                   \
+        }                                                                 \
+    } while (0)
+
+/* LD V0, 0x07; LD F, V0; DRW V0, V0, 5; LD V1, 0x05; LD ST, V1; JP 0x20A */
+static const uint8_t DIGIT[] = {0x60, 0x07, 0xF0, 0x29, 0xD0, 0x05,
+                                0x61, 0x05, 0xF1, 0x18, 0x12, 0x0A};
+
+/* RET */
+static const uint8_t UNDERFLOW[] = {0x00, 0xEE};
+
+static void run(void) {
+    Oito *oito = oito_new();
+    CHECK(oito != NULL);
+    CHECK(oito_load(oito, DIGIT, sizeof(DIGIT)) == OITO_ERROR_OK);
+
+    for (int frame = 0; frame < 2 && !oito_stopped(oito); frame++) {
+        for (int tick = 0; tick < 10 && !oito_stopped(oito); tick++) {
+            CHECK(oito_tick(oito) == OITO_ERROR_OK);
+        }
+        CHECK(oito_frame_tick(oito) == OITO_ERROR_OK);
+    }
+    CHECK(oito_stopped(oito));
+    CHECK(oito_instructions(oito) == 6);
+    CHECK(oito_sound(oito));
+
+    /* The digit 7 is drawn at (7, 7) */
+    const bool *buffer = oito_frame_buffer(oito);
+    CHECK(buffer[7 * OITO_SCREEN_WIDTH + 7]);
+    CHECK(!buffer[7 * OITO_SCREEN_WIDTH + 6]);
+    const uint64_t *rows = oito_frame_rows(oito);
+    CHECK(rows[7] == (uint64_t)0xF0 << (OITO_SCREEN_WIDTH - 8 - 7));
+    CHECK(rows[OITO_SCREEN_HEIGHT - 1] == 0);
+
+    CHECK(strcmp(oito_last_error(oito), "") == 0);
+    oito_free(oito);
+}
+
+static void errors(void) {
+    Oito *oito = oito_new_seeded(8);
+    CHECK(oito_load(oito, UNDERFLOW, sizeof(UNDERFLOW)) == OITO_ERROR_OK);
+    OitoError error = oito_tick(oito);
+    CHECK(error == OITO_ERROR_STACK_UNDERFLOW);
+    CHECK(strcmp(oito_error_name(error), "stack underflow") == 0);
+    CHECK(strcmp(oito_last_error(oito), "Stack underflow") == 0);
+
+    CHECK(oito_key_press(oito, 0xA) == OITO_ERROR_OK);
+    CHECK(oito_key_release(oito, 0xA) == OITO_ERROR_OK);
+    CHECK(oito_key_press(oito, 0x10) == OITO_ERROR_INVALID_ARGUMENT);
+    oito_free(oito);
+
+    CHECK(oito_tick(NULL) == OITO_ERROR_NULL_POINTER);
+    CHECK(oito_frame_buffer(NULL) == NULL);
+    oito_free(NULL);
+}
+
+int main(void) {
+    run();
+    errors();
+    if (failures == 0) {
+        puts("ok");
+    }
+    return failures;
+}
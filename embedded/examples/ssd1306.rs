@@ -0,0 +1,192 @@
+//! Oito running on a 128x64 monochrome display, like the SSD1306 ones, and a hexadecimal keypad.
+//! The display and the keypad are mocks kept in memory, so the example runs anywhere;
+//! on a board they would be replaced by the driver of the display and the pins of the keypad.
+//!
+//! The ROM waits for a key and draws its digit, the example presses the key 5 for a few frames
+//! and prints the display. Run it with `cargo run --example ssd1306`.
+
+use std::{convert::Infallible, fmt::Display};
+
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+use oito_core::{config::Config, core::OitoCore};
+use oito_embedded::{
+    display::FrameDrawer,
+    keypad::{Keypad, MatrixKeypad, VIP_LAYOUT},
+};
+
+/// Width of the display
+const WIDTH: usize = 128;
+/// Height of the display
+const HEIGHT: usize = 64;
+/// Rows of each page of the memory of the display
+const PAGE_HEIGHT: usize = 8;
+/// Instructions run on each frame
+const TICKS_PER_FRAME: usize = 10;
+/// Frames the example runs
+const FRAMES: usize = 10;
+/// Frames when the key 5 is held down
+const HELD: std::ops::Range<usize> = 2..6;
+
+/// ROM waiting for a key and drawing its digit in the middle of the screen until the key is released
+#[rustfmt::skip]
+const ROM: [u8; 18] = [
+    0xF0, 0x0A, // LD V0, K
+    0x00, 0xE0, // CLS
+    0xF0, 0x29, // LD F, V0
+    0x61, 0x1E, // LD V1, 30
+    0x62, 0x0D, // LD V2, 13
+    0xD1, 0x25, // DRW V1, V2, 5
+    0xE0, 0xA1, // SKNP V0
+    0x12, 0x0C, // JP 0x20C
+    0x12, 0x00, // JP 0x200
+];
+
+/// In-memory display laid out like the SSD1306 ones:
+/// eight pages of eight rows, with a byte for each column of a page and the top row as the least significant bit
+struct Ssd1306 {
+    buffer: [u8; WIDTH * HEIGHT / PAGE_HEIGHT],
+}
+
+impl Ssd1306 {
+    fn new() -> Self {
+        Self {
+            buffer: [0; WIDTH * HEIGHT / PAGE_HEIGHT],
+        }
+    }
+
+    /// Returns true if the pixel is lit
+    fn pixel(&self, x: usize, y: usize) -> bool {
+        self.buffer[y / PAGE_HEIGHT * WIDTH + x] & (1 << (y % PAGE_HEIGHT)) != 0
+    }
+}
+
+impl OriginDimensions for Ssd1306 {
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl DrawTarget for Ssd1306 {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+                continue;
+            };
+            if x >= WIDTH || y >= HEIGHT {
+                continue;
+            }
+            let byte = &mut self.buffer[y / PAGE_HEIGHT * WIDTH + x];
+            let bit = 1 << (y % PAGE_HEIGHT);
+            match color {
+                BinaryColor::On => *byte |= bit,
+                BinaryColor::Off => *byte &= !bit,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for Ssd1306 {
+    /// Formats the display with `#` for the lit pixels and `.` for the rest
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                write!(f, "{}", if self.pixel(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// In-memory keypad, with the pressed columns of each row
+#[derive(Default)]
+struct Membrane {
+    rows: [u8; 4],
+}
+
+impl MatrixKeypad for Membrane {
+    type Error = Infallible;
+
+    fn scan(&mut self, row: usize) -> Result<u8, Self::Error> {
+        Ok(self.rows[row])
+    }
+}
+
+/// Runs the ROM for the frames, holding the key 5 of the keypad down in the frames specified
+fn run(frames: usize, held: std::ops::Range<usize>) -> Ssd1306 {
+    let mut oito = OitoCore::with_config(Config {
+        // there's no entropy to take the random numbers from
+        seed: Some(0x0170),
+        ..Default::default()
+    });
+    oito.load(&ROM);
+
+    let mut display = Ssd1306::new();
+    let drawer = FrameDrawer::fit(display.size(), BinaryColor::On, BinaryColor::Off);
+    let mut keypad = Keypad::new(Membrane::default(), VIP_LAYOUT);
+    drawer.draw(oito.frame_rows(), &mut display).unwrap();
+
+    for frame in 0..frames {
+        // the key 5 is in the second row and second column of the keypad
+        keypad.matrix().rows[1] = if held.contains(&frame) { 0b0010 } else { 0 };
+        oito.set_keys(keypad.keys().unwrap());
+        for _ in 0..TICKS_PER_FRAME {
+            oito.tick().unwrap();
+        }
+        oito.frame_tick();
+        let damage = oito.take_damage();
+        drawer
+            .draw_damage(oito.frame_rows(), damage, &mut display)
+            .unwrap();
+    }
+    display
+}
+
+fn main() {
+    print!("{}", run(FRAMES, HELD));
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run, FRAMES, HEIGHT, HELD, WIDTH};
+
+    /// Sprite of the digit 5 in the font
+    const FIVE: [u8; 5] = [0xF0, 0x80, 0xF0, 0x10, 0xF0];
+
+    /// Returns the number of lit pixels of the display
+    fn lit(display: &super::Ssd1306) -> usize {
+        (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .filter(|(x, y)| display.pixel(*x, *y))
+            .count()
+    }
+
+    #[test]
+    fn draws_pressed_key() {
+        let display = run(FRAMES, HELD);
+        // the sprite is drawn at (30, 13) of the frame, twice as big
+        for (row, byte) in FIVE.iter().enumerate() {
+            for column in 0..8 {
+                let expected = byte & (0x80 >> column) != 0;
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let (x, y) = (60 + column * 2 + dx, 26 + row * 2 + dy);
+                    assert_eq!(expected, display.pixel(x, y), "pixel at ({x}, {y})");
+                }
+            }
+        }
+        let bits: u32 = FIVE.iter().map(|byte| byte.count_ones()).sum();
+        assert_eq!(bits as usize * 4, lit(&display));
+    }
+
+    #[test]
+    fn waits_for_key() {
+        assert_eq!(0, lit(&run(FRAMES, 0..0)));
+    }
+}
@@ -0,0 +1,186 @@
+use embedded_graphics::{pixelcolor::PixelColor, prelude::*, primitives::Rectangle};
+use oito_core::{damage::Damage, Row, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// Draws the frames of the emulator into any embedded-graphics display,
+/// enlarging each pixel of the frame into a square of pixels of the display
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameDrawer<C> {
+    /// Position in the display of the top left corner of the frame
+    origin: Point,
+    /// Side in pixels of the display of each pixel of the frame
+    scale: u32,
+    /// Color of the lit pixels
+    on: C,
+    /// Color of the unlit pixels
+    off: C,
+}
+
+impl<C: PixelColor> FrameDrawer<C> {
+    /// Returns a new drawer placing the frame at the origin, with each pixel enlarged to the scale.
+    /// A scale of zero is taken as one.
+    pub fn new(origin: Point, scale: u32, on: C, off: C) -> Self {
+        Self {
+            origin,
+            scale: scale.max(1),
+            on,
+            off,
+        }
+    }
+
+    /// Returns a new drawer enlarging the frame as much as possible inside a display of the size,
+    /// centered on it. A 128x64 display, like the SSD1306 ones, fits the frame twice as big.
+    pub fn fit(size: Size, on: C, off: C) -> Self {
+        let scale = (size.width / SCREEN_WIDTH as u32)
+            .min(size.height / SCREEN_HEIGHT as u32)
+            .max(1);
+        let frame = Size::new(SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32) * scale;
+        let origin = Point::new(
+            size.width.saturating_sub(frame.width) as i32 / 2,
+            size.height.saturating_sub(frame.height) as i32 / 2,
+        );
+        Self::new(origin, scale, on, off)
+    }
+
+    /// Returns the area of the display covered by the frame
+    pub fn area(&self) -> Rectangle {
+        let size = Size::new(SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32) * self.scale;
+        Rectangle::new(self.origin, size)
+    }
+
+    /// Draws the whole frame, given as the rows of [frame_rows](oito_core::core::OitoCore::frame_rows)
+    pub fn draw<D>(&self, rows: &[Row], target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_damage(rows, Damage::FULL, target)
+    }
+
+    /// Draws only the region of the frame changed, given as the damage of
+    /// [take_damage](oito_core::core::OitoCore::take_damage). Nothing is drawn without changes.
+    pub fn draw_damage<D>(
+        &self,
+        rows: &[Row],
+        damage: Damage,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        // the rectangle around the changed pixels is filled with the enlarged pixels of the frame
+        let Some(bounds) = damage.bounds() else {
+            return Ok(());
+        };
+        let scale = self.scale as usize;
+        let area = Rectangle::new(
+            self.origin + Point::new((bounds.x * scale) as i32, (bounds.y * scale) as i32),
+            Size::new(
+                (bounds.width * scale) as u32,
+                (bounds.height * scale) as u32,
+            ),
+        );
+        let colors = area.points().map(|point| {
+            let x = (point.x - self.origin.x) as usize / scale;
+            let y = (point.y - self.origin.y) as usize / scale;
+            let lit = rows[y] & (1 << (SCREEN_WIDTH - 1 - x)) != 0;
+            if lit {
+                self.on
+            } else {
+                self.off
+            }
+        });
+        target.fill_contiguous(&area, colors)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*};
+    use oito_core::{core::OitoCore, SCREEN_HEIGHT};
+
+    use super::FrameDrawer;
+
+    /// Rows with the top left pixel and the bottom right one lit
+    fn corners() -> [u64; SCREEN_HEIGHT] {
+        let mut rows = [0; SCREEN_HEIGHT];
+        rows[0] = 1 << 63;
+        rows[SCREEN_HEIGHT - 1] = 1;
+        rows
+    }
+
+    #[test]
+    fn fit() {
+        let drawer = FrameDrawer::fit(Size::new(128, 64), BinaryColor::On, BinaryColor::Off);
+        assert_eq!(Point::zero(), drawer.area().top_left);
+        assert_eq!(Size::new(128, 64), drawer.area().size);
+
+        let drawer = FrameDrawer::fit(Size::new(160, 80), BinaryColor::On, BinaryColor::Off);
+        assert_eq!(Point::new(16, 8), drawer.area().top_left);
+        assert_eq!(Size::new(128, 64), drawer.area().size);
+
+        let drawer = FrameDrawer::fit(Size::new(32, 32), BinaryColor::On, BinaryColor::Off);
+        assert_eq!(Size::new(64, 32), drawer.area().size);
+    }
+
+    #[test]
+    fn draw() {
+        let drawer = FrameDrawer::new(Point::new(0, 16), 1, BinaryColor::On, BinaryColor::Off);
+        let mut display = MockDisplay::new();
+        drawer.draw(&corners(), &mut display).unwrap();
+
+        assert_eq!(Some(BinaryColor::On), display.get_pixel(Point::new(0, 16)));
+        assert_eq!(Some(BinaryColor::Off), display.get_pixel(Point::new(1, 16)));
+        assert_eq!(Some(BinaryColor::On), display.get_pixel(Point::new(63, 47)));
+        assert_eq!(None, display.get_pixel(Point::new(0, 15)));
+        assert_eq!(None, display.get_pixel(Point::new(0, 48)));
+    }
+
+    #[test]
+    fn draw_scaled() {
+        let drawer = FrameDrawer::new(Point::zero(), 2, BinaryColor::On, BinaryColor::Off);
+        let mut display = MockDisplay::new();
+        display.set_allow_out_of_bounds_drawing(true);
+        drawer.draw(&corners(), &mut display).unwrap();
+
+        for point in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            assert_eq!(Some(BinaryColor::On), display.get_pixel(point.into()));
+        }
+        assert_eq!(Some(BinaryColor::Off), display.get_pixel(Point::new(2, 0)));
+        assert_eq!(Some(BinaryColor::Off), display.get_pixel(Point::new(0, 2)));
+    }
+
+    #[test]
+    fn draw_damage() {
+        let mut oito = OitoCore::new();
+        // I = sprite of 0, draw it at (8, 4)
+        oito.load(&[0xF0, 0x29, 0x60, 0x08, 0x61, 0x04, 0xD0, 0x15]);
+        // the whole screen is damaged by the load, it's left for the first draw of the ROM
+        oito.take_damage();
+        for _ in 0..4 {
+            oito.tick().unwrap();
+        }
+        let drawer = FrameDrawer::new(Point::zero(), 1, BinaryColor::On, BinaryColor::Off);
+        let mut display = MockDisplay::new();
+        let damage = oito.take_damage();
+        drawer
+            .draw_damage(oito.frame_rows(), damage, &mut display)
+            .unwrap();
+
+        display.assert_pattern(&[
+            "            ", //
+            "            ", //
+            "            ", //
+            "            ", //
+            "        ####", //
+            "        #..#", //
+            "        #..#", //
+            "        #..#", //
+            "        ####", //
+        ]);
+
+        // nothing changed, drawing any pixel again would make the mock panic
+        let damage = oito.take_damage();
+        drawer
+            .draw_damage(oito.frame_rows(), damage, &mut display)
+            .unwrap();
+    }
+}
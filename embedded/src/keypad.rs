@@ -0,0 +1,134 @@
+use oito_core::key::Key;
+
+/// Number of rows and of columns of the keypads
+pub const KEYPAD_SIZE: usize = 4;
+
+/// Keys at each row and column of a keypad
+pub type Layout = [[Key; KEYPAD_SIZE]; KEYPAD_SIZE];
+
+/// Hexadecimal keypad of the COSMAC VIP, the one the original Chip-8 programs were written for
+pub const VIP_LAYOUT: Layout = [
+    [Key::One, Key::Two, Key::Three, Key::C],
+    [Key::Four, Key::Five, Key::Six, Key::D],
+    [Key::Seven, Key::Eight, Key::Nine, Key::E],
+    [Key::A, Key::Zero, Key::B, Key::F],
+];
+
+/// Common membrane keypad of the microcontroller kits, labelled `123A`, `456B`, `789C` and `*0#D`.
+/// The letters of the last column are the keys `A` to `D`, `*` is the key `E` and `#` the key `F`.
+pub const MEMBRANE_LAYOUT: Layout = [
+    [Key::One, Key::Two, Key::Three, Key::A],
+    [Key::Four, Key::Five, Key::Six, Key::B],
+    [Key::Seven, Key::Eight, Key::Nine, Key::C],
+    [Key::E, Key::Zero, Key::F, Key::D],
+];
+
+/// Keypad wired as a matrix of four rows and four columns, scanned one row at a time
+pub trait MatrixKeypad {
+    /// Error of the pins of the keypad
+    type Error;
+
+    /// Drives the row and returns its pressed columns as a mask, bit N is set if column N is pressed
+    fn scan(&mut self, row: usize) -> Result<u8, Self::Error>;
+}
+
+/// Reader of the Chip-8 keys from a matrix keypad with the specified layout
+#[derive(Debug)]
+pub struct Keypad<K> {
+    /// Matrix of the keypad
+    matrix: K,
+    /// Keys at each row and column
+    layout: Layout,
+}
+
+impl<K: MatrixKeypad> Keypad<K> {
+    /// Returns a new reader of the matrix keypad with the keys laid out as specified
+    pub fn new(matrix: K, layout: Layout) -> Self {
+        Self { matrix, layout }
+    }
+
+    /// Scans the keypad and returns the state of every key as a mask, bit N is set if the key of value N
+    /// is pressed. It's the mask expected by [set_keys](oito_core::core::OitoCore::set_keys).
+    pub fn keys(&mut self) -> Result<u16, K::Error> {
+        let mut mask = 0;
+        for (row, keys) in self.layout.iter().enumerate() {
+            let columns = self.matrix.scan(row)?;
+            for (column, key) in keys.iter().enumerate() {
+                if columns & (1 << column) != 0 {
+                    mask |= key.mask();
+                }
+            }
+        }
+        Ok(mask)
+    }
+
+    /// Returns the matrix of the keypad
+    pub fn matrix(&mut self) -> &mut K {
+        &mut self.matrix
+    }
+
+    /// Returns the matrix of the keypad, consuming the reader
+    pub fn into_matrix(self) -> K {
+        self.matrix
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::key::Key;
+
+    use super::{Keypad, MatrixKeypad, MEMBRANE_LAYOUT, VIP_LAYOUT};
+
+    /// Matrix with the pressed columns of each row
+    struct Matrix([u8; 4]);
+
+    impl MatrixKeypad for Matrix {
+        type Error = ();
+
+        fn scan(&mut self, row: usize) -> Result<u8, Self::Error> {
+            Ok(self.0[row])
+        }
+    }
+
+    /// Matrix with the pins disconnected
+    struct Broken;
+
+    impl MatrixKeypad for Broken {
+        type Error = &'static str;
+
+        fn scan(&mut self, _: usize) -> Result<u8, Self::Error> {
+            Err("disconnected")
+        }
+    }
+
+    #[test]
+    fn layouts() {
+        for layout in [VIP_LAYOUT, MEMBRANE_LAYOUT] {
+            let mask = layout
+                .iter()
+                .flatten()
+                .fold(0, |mask, key| mask | key.mask());
+            assert_eq!(0xFFFF, mask);
+        }
+    }
+
+    #[test]
+    fn keys() {
+        let mut keypad = Keypad::new(Matrix([0b0001, 0, 0, 0b1010]), VIP_LAYOUT);
+        assert_eq!(
+            Ok(Key::One.mask() | Key::Zero.mask() | Key::F.mask()),
+            keypad.keys()
+        );
+
+        keypad.matrix().0 = [0b0010, 0, 0, 0];
+        assert_eq!(Ok(Key::Two.mask()), keypad.keys());
+
+        let mut keypad = Keypad::new(Matrix([0, 0, 0, 0b0101]), MEMBRANE_LAYOUT);
+        assert_eq!(Ok(Key::E.mask() | Key::F.mask()), keypad.keys());
+    }
+
+    #[test]
+    fn error() {
+        assert_eq!(Err("disconnected"), Keypad::new(Broken, VIP_LAYOUT).keys());
+    }
+}
@@ -0,0 +1,7 @@
+//! Embedded front-end of the Oito emulator: draws the frames into any embedded-graphics display
+//! and reads the keys from matrix keypads. It's `no_std` and doesn't allocate.
+
+#![no_std]
+
+pub mod display;
+pub mod keypad;
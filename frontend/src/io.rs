@@ -0,0 +1,37 @@
+use oito_core::{core::OitoCore, key::Key};
+
+/// Destination of the frames of the emulator, like a window or a canvas
+pub trait VideoSink {
+    /// Draws the current frame of the emulator.
+    /// The emulator is mutable to allow taking the damage and redrawing only the changed region.
+    fn draw(&mut self, oito: &mut OitoCore);
+}
+
+/// Player of the beep of the emulator
+pub trait AudioSink {
+    /// Starts or keeps playing the beep while it's on, and stops it when it's off
+    fn beep(&mut self, on: bool);
+}
+
+/// Source of the input of the player, like a keyboard or a gamepad
+pub trait InputSource {
+    /// Returns the inputs received since the last poll
+    fn poll(&mut self) -> Vec<Input>;
+}
+
+/// Commands that the player can send to the runner
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    /// Press of a key of the keypad
+    Press(Key),
+    /// Release of a key of the keypad
+    Release(Key),
+    /// Pauses the emulator, or resumes it if it's paused
+    Pause,
+    /// Runs a single instruction, only while paused
+    Step,
+    /// Restarts the ROM from the beginning
+    Reset,
+    /// Stops the emulator
+    Quit,
+}
@@ -0,0 +1,227 @@
+use std::str::FromStr;
+
+use oito_core::{
+    action::{Action, ActionMap},
+    key::Key,
+};
+
+use crate::io::Input;
+
+/// Keys of the host keyboard used by the emulator, named by their position in a QWERTY layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PhysicalKey {
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Q,
+    W,
+    E,
+    R,
+    A,
+    S,
+    D,
+    F,
+    Z,
+    X,
+    C,
+    V,
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Enter,
+    Escape,
+    F5,
+    F6,
+    F7,
+}
+
+impl FromStr for PhysicalKey {
+    type Err = String;
+
+    /// Parses the key from its code in the web `KeyboardEvent`, like `KeyQ`, `Digit1` or `ArrowUp`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use PhysicalKey::*;
+        match s {
+            "Digit1" => Ok(Digit1),
+            "Digit2" => Ok(Digit2),
+            "Digit3" => Ok(Digit3),
+            "Digit4" => Ok(Digit4),
+            "KeyQ" => Ok(Q),
+            "KeyW" => Ok(W),
+            "KeyE" => Ok(E),
+            "KeyR" => Ok(R),
+            "KeyA" => Ok(A),
+            "KeyS" => Ok(S),
+            "KeyD" => Ok(D),
+            "KeyF" => Ok(F),
+            "KeyZ" => Ok(Z),
+            "KeyX" => Ok(X),
+            "KeyC" => Ok(C),
+            "KeyV" => Ok(V),
+            "ArrowUp" => Ok(Up),
+            "ArrowDown" => Ok(Down),
+            "ArrowLeft" => Ok(Left),
+            "ArrowRight" => Ok(Right),
+            "Space" => Ok(Space),
+            "Enter" => Ok(Enter),
+            "Escape" => Ok(Escape),
+            "F5" => Ok(F5),
+            "F6" => Ok(F6),
+            "F7" => Ok(F7),
+            _ => Err(format!("unmapped key: {s}")),
+        }
+    }
+}
+
+/// Translation of the host keys into inputs of the runner.
+///
+/// The four left columns of the keyboard are the keypad, the arrows move and the space bar (or enter) fires
+/// through the actions of the ROM, escape quits, F5 resets, F6 pauses and F7 steps.
+/// ```text
+/// +---+---+---+---+         +---+---+---+---+
+/// | 1 | 2 | 3 | 4 |         | 1 | 2 | 3 | C |
+/// +---+---+---+---+         +---+---+---+---+
+/// | Q | W | E | R |         | 4 | 5 | 6 | D |
+/// +---+---+---+---+   -->   +---+---+---+---+
+/// | A | S | D | F |         | 7 | 8 | 9 | E |
+/// +---+---+---+---+         +---+---+---+---+
+/// | Z | X | C | V |         | A | 0 | B | F |
+/// +---+---+---+---+         +---+---+---+---+
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Keymap {
+    /// Keys pressed by the actions in the current ROM
+    actions: ActionMap,
+}
+
+impl Keymap {
+    pub fn new(actions: ActionMap) -> Self {
+        Self { actions }
+    }
+
+    /// Binds the actions to the keys of the current ROM
+    pub fn set_actions(&mut self, actions: ActionMap) {
+        self.actions = actions;
+    }
+
+    /// Returns the input of the press or release of the host key, if it's mapped.
+    /// The controls of the runner only react to the presses.
+    pub fn input(&self, key: PhysicalKey, pressed: bool) -> Option<Input> {
+        let control = match key {
+            PhysicalKey::Escape => Some(Input::Quit),
+            PhysicalKey::F5 => Some(Input::Reset),
+            PhysicalKey::F6 => Some(Input::Pause),
+            PhysicalKey::F7 => Some(Input::Step),
+            _ => None,
+        };
+        if control.is_some() {
+            return control.filter(|_| pressed);
+        }
+        let key = keypad(key).or_else(|| action(key).map(|action| self.actions.key(action)))?;
+        Some(if pressed {
+            Input::Press(key)
+        } else {
+            Input::Release(key)
+        })
+    }
+}
+
+/// Returns the key of the keypad in the position of the host key
+fn keypad(key: PhysicalKey) -> Option<Key> {
+    use PhysicalKey::*;
+    match key {
+        Digit1 => Some(Key::One),
+        Digit2 => Some(Key::Two),
+        Digit3 => Some(Key::Three),
+        Digit4 => Some(Key::C),
+        Q => Some(Key::Four),
+        W => Some(Key::Five),
+        E => Some(Key::Six),
+        R => Some(Key::D),
+        A => Some(Key::Seven),
+        S => Some(Key::Eight),
+        D => Some(Key::Nine),
+        F => Some(Key::E),
+        Z => Some(Key::A),
+        X => Some(Key::Zero),
+        C => Some(Key::B),
+        V => Some(Key::F),
+        _ => None,
+    }
+}
+
+/// Returns the action bound to the host key
+fn action(key: PhysicalKey) -> Option<Action> {
+    use PhysicalKey::*;
+    match key {
+        Up => Some(Action::Up),
+        Down => Some(Action::Down),
+        Left => Some(Action::Left),
+        Right => Some(Action::Right),
+        Space | Enter => Some(Action::Fire),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::{
+        action::{Action, ActionMap},
+        key::Key,
+    };
+
+    use crate::io::Input;
+
+    use super::{Keymap, PhysicalKey};
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(PhysicalKey::Digit4), "Digit4".parse());
+        assert_eq!(Ok(PhysicalKey::Q), "KeyQ".parse());
+        assert_eq!(Ok(PhysicalKey::Left), "ArrowLeft".parse());
+        assert!("KeyP".parse::<PhysicalKey>().is_err());
+    }
+
+    #[test]
+    fn keypad() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            Some(Input::Press(Key::C)),
+            keymap.input(PhysicalKey::Digit4, true)
+        );
+        assert_eq!(
+            Some(Input::Release(Key::Zero)),
+            keymap.input(PhysicalKey::X, false)
+        );
+    }
+
+    #[test]
+    fn actions() {
+        let mut actions = ActionMap::default();
+        actions.set(Action::Fire, Key::A);
+        let mut keymap = Keymap::default();
+
+        assert_eq!(
+            Some(Input::Press(Key::Two)),
+            keymap.input(PhysicalKey::Up, true)
+        );
+        keymap.set_actions(actions);
+        assert_eq!(
+            Some(Input::Release(Key::A)),
+            keymap.input(PhysicalKey::Space, false)
+        );
+    }
+
+    #[test]
+    fn controls() {
+        let keymap = Keymap::default();
+
+        assert_eq!(Some(Input::Quit), keymap.input(PhysicalKey::Escape, true));
+        assert_eq!(Some(Input::Pause), keymap.input(PhysicalKey::F6, true));
+        assert_eq!(None, keymap.input(PhysicalKey::F6, false));
+    }
+}
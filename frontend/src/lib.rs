@@ -0,0 +1,3 @@
+pub mod io;
+pub mod keymap;
+pub mod runner;
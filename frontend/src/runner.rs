@@ -0,0 +1,301 @@
+use std::time::{Duration, Instant};
+
+use oito_core::{
+    config::Config,
+    core::{OitoCore, Status},
+    exception::Exception,
+};
+
+use crate::io::{AudioSink, Input, InputSource, VideoSink};
+
+/// Instructions run per frame by default
+pub const TICKS_PER_FRAME: usize = 10;
+/// Frames per second of the Chip8
+pub const FRAME_RATE: u32 = 60;
+
+/// Driver of an [OitoCore] shared by the front-ends.
+/// It runs the frames of the emulator and handles the pause, the steps and the resets,
+/// leaving to the front-ends only the implementation of the sinks and the input source.
+#[derive(Debug)]
+pub struct Runner {
+    /// Emulator running the ROM
+    oito: OitoCore,
+    /// Settings used to rebuild the emulator in the resets
+    config: Config,
+    /// ROM being run
+    rom: Vec<u8>,
+    /// Instructions run in each frame
+    ticks: usize,
+    /// True while the emulator is paused
+    paused: bool,
+}
+
+impl Runner {
+    /// Returns a new runner of the ROM in an emulator built with the settings
+    pub fn new(config: Config, rom: &[u8]) -> Self {
+        let mut oito = OitoCore::with_config(config.clone());
+        oito.load(rom);
+        Self {
+            oito,
+            config,
+            rom: rom.to_vec(),
+            ticks: TICKS_PER_FRAME,
+            paused: false,
+        }
+    }
+
+    /// Returns the emulator
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Returns the emulator mutably, to feed it keys or inspect it
+    pub fn oito_mut(&mut self) -> &mut OitoCore {
+        &mut self.oito
+    }
+
+    /// Sets the number of instructions run in each frame
+    pub fn set_ticks(&mut self, ticks: usize) {
+        self.ticks = ticks;
+    }
+
+    /// Returns true if the emulator is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops running instructions in the frames
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Restarts running instructions in the frames
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Pauses the emulator if it's running and resumes it if it's paused
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Runs a single instruction, ignoring the breakpoints. Does nothing unless the emulator is paused.
+    pub fn step(&mut self) -> Result<(), Exception> {
+        if self.paused {
+            self.oito.step()?;
+        }
+        Ok(())
+    }
+
+    /// Restarts the ROM in a new emulator built with the same settings
+    pub fn reset(&mut self) {
+        self.oito = OitoCore::with_config(self.config.clone());
+        self.oito.load(&self.rom);
+    }
+
+    /// Replaces the ROM and restarts the emulator to run it
+    pub fn load(&mut self, rom: &[u8]) {
+        self.rom = rom.to_vec();
+        self.reset();
+    }
+
+    /// Applies the input. Returns false if the input asks to quit.
+    pub fn handle(&mut self, input: Input) -> Result<bool, Exception> {
+        match input {
+            Input::Press(key) => self.oito.key_press(key),
+            Input::Release(key) => self.oito.key_release(key),
+            Input::Pause => self.toggle_pause(),
+            Input::Step => self.step()?,
+            Input::Reset => self.reset(),
+            Input::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Runs a frame, unless paused, and sends the resulting image and sound to the sinks.
+    /// Reaching a breakpoint or watchpoint pauses the emulator.
+    pub fn frame(
+        &mut self,
+        video: &mut impl VideoSink,
+        audio: &mut impl AudioSink,
+    ) -> Result<(), Exception> {
+        if !self.paused {
+            self.oito.run(self.ticks as u64)?;
+            if let Status::Break(_) = self.oito.status() {
+                self.paused = true;
+            }
+            self.oito.frame_tick();
+        }
+        audio.beep(!self.paused && self.oito.sound());
+        video.draw(&mut self.oito);
+        Ok(())
+    }
+
+    /// Runs the frames at the Chip8 rate until the input source asks to quit
+    pub fn run(
+        &mut self,
+        video: &mut impl VideoSink,
+        audio: &mut impl AudioSink,
+        input: &mut impl InputSource,
+    ) -> Result<(), Exception> {
+        let mut pacer = Pacer::new(FRAME_RATE);
+        loop {
+            for input in input.poll() {
+                if !self.handle(input)? {
+                    audio.beep(false);
+                    return Ok(());
+                }
+            }
+            self.frame(video, audio)?;
+            pacer.wait();
+        }
+    }
+}
+
+/// Keeper of a fixed rate of frames, sleeping the remaining time of each one
+#[derive(Debug)]
+pub struct Pacer {
+    /// Duration of each frame
+    period: Duration,
+    /// Moment when the next frame should start
+    next: Instant,
+}
+
+impl Pacer {
+    /// Returns a pacer of the specified frames per second
+    pub fn new(rate: u32) -> Self {
+        Self {
+            period: Duration::from_secs(1) / rate,
+            next: Instant::now(),
+        }
+    }
+
+    /// Sleeps until the start of the next frame.
+    /// When running late the next frame starts right away, without trying to catch up.
+    pub fn wait(&mut self) {
+        self.next += self.period;
+        let now = Instant::now();
+        match self.next.checked_duration_since(now) {
+            Some(remaining) => std::thread::sleep(remaining),
+            None => self.next = now,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::{config::Config, core::OitoCore, damage::Damage, key::Key};
+
+    use crate::io::{AudioSink, Input, VideoSink};
+
+    use super::Runner;
+
+    /// Infinite loop of additions to V0: ADD V0, 1; JP 0x200
+    const ROM: [u8; 4] = [0x70, 0x01, 0x12, 0x00];
+
+    #[derive(Default)]
+    struct Sinks {
+        frames: usize,
+        beeping: bool,
+    }
+
+    impl VideoSink for Sinks {
+        fn draw(&mut self, _: &mut OitoCore) {
+            self.frames += 1;
+        }
+    }
+
+    impl AudioSink for Sinks {
+        fn beep(&mut self, on: bool) {
+            self.beeping = on;
+        }
+    }
+
+    fn frame(runner: &mut Runner) -> Sinks {
+        let mut video = Sinks::default();
+        let mut audio = Sinks::default();
+        runner.frame(&mut video, &mut audio).unwrap();
+        assert_eq!(1, video.frames);
+        audio
+    }
+
+    #[test]
+    fn frame_ticks() {
+        let mut runner = Runner::new(Config::default(), &ROM);
+        runner.set_ticks(4);
+
+        frame(&mut runner);
+        assert_eq!(4, runner.oito().instructions());
+        assert_eq!(2, runner.oito().registers().v[0]);
+    }
+
+    #[test]
+    fn pause() {
+        let mut runner = Runner::new(Config::default(), &ROM);
+        runner.step().unwrap();
+        assert_eq!(0, runner.oito().instructions());
+
+        runner.handle(Input::Pause).unwrap();
+        assert!(runner.is_paused());
+        frame(&mut runner);
+        assert_eq!(0, runner.oito().instructions());
+        runner.handle(Input::Step).unwrap();
+        assert_eq!(1, runner.oito().instructions());
+
+        runner.resume();
+        frame(&mut runner);
+        assert_eq!(11, runner.oito().instructions());
+    }
+
+    #[test]
+    fn breakpoint() {
+        let mut runner = Runner::new(Config::default(), &ROM);
+        runner.oito_mut().add_breakpoint(0x202);
+
+        frame(&mut runner);
+        assert!(runner.is_paused());
+        assert_eq!(1, runner.oito().instructions());
+        // Resumes until the breakpoint is reached again
+        runner.resume();
+        frame(&mut runner);
+        assert!(runner.is_paused());
+        assert_eq!(3, runner.oito().instructions());
+    }
+
+    #[test]
+    fn reset() {
+        let mut runner = Runner::new(Config::default(), &ROM);
+        frame(&mut runner);
+        assert!(runner.handle(Input::Reset).unwrap());
+        assert_eq!(0, runner.oito().instructions());
+        assert_eq!(0, runner.oito().registers().v[0]);
+        // The sinks drawing only the damage repaint the whole screen after the reset
+        assert_eq!(Damage::FULL, runner.oito_mut().take_damage());
+
+        runner.load(&[0x60, 0x08, 0x12, 0x02]);
+        assert_eq!(Damage::FULL, runner.oito_mut().take_damage());
+        frame(&mut runner);
+        assert_eq!(8, runner.oito().registers().v[0]);
+    }
+
+    #[test]
+    fn handle() {
+        let mut runner = Runner::new(Config::default(), &ROM);
+
+        assert!(runner.handle(Input::Press(Key::Five)).unwrap());
+        assert_eq!(0x0020, runner.oito().keys());
+        assert!(runner.handle(Input::Release(Key::Five)).unwrap());
+        assert_eq!(0x0000, runner.oito().keys());
+        assert!(!runner.handle(Input::Quit).unwrap());
+    }
+
+    #[test]
+    fn sound() {
+        // LD V0, 0x10; LD ST, V0; JP 0x204
+        let mut runner = Runner::new(Config::default(), &[0x60, 0x10, 0xF0, 0x18, 0x12, 0x04]);
+        assert!(frame(&mut runner).beeping);
+
+        runner.pause();
+        assert!(!frame(&mut runner).beeping);
+    }
+}
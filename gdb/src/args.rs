@@ -0,0 +1,14 @@
+use structopt::StructOpt;
+
+/// Debug a Chip-8 ROM with GDB through its Remote Serial Protocol
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    /// The ROM file to debug
+    pub file: String,
+    /// Port of the loopback interface listening to the client
+    #[structopt(long = "port", short = "p", default_value = "1234")]
+    pub port: u16,
+    /// Fx0A waits for the key to be released, like the COSMAC VIP
+    #[structopt(long = "wait-release")]
+    pub wait_release: bool,
+}
@@ -0,0 +1,31 @@
+use gdb::{server, target::Target};
+use oito_core::config::{Config, Quirks};
+use std::{
+    error::Error,
+    fs,
+    net::{Ipv4Addr, TcpListener},
+};
+use structopt::StructOpt;
+
+mod args;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = args::Args::from_args();
+
+    let mut target = Target::new(
+        Config {
+            quirks: Quirks {
+                wait_release: args.wait_release,
+            },
+            ..Default::default()
+        },
+        &fs::read(&args.file)?,
+    );
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, args.port))?;
+    println!("Waiting for gdb on {}", listener.local_addr()?);
+    let (stream, client) = listener.accept()?;
+    println!("Debugging {} from {client}", args.file);
+    server::serve(&mut target, stream)?;
+    Ok(())
+}
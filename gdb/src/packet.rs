@@ -0,0 +1,191 @@
+use std::io::{self, ErrorKind, Read, Write};
+
+/// Byte sent by the client to interrupt the execution
+pub const INTERRUPT: u8 = 0x03;
+
+/// Messages received from the client
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    /// Command packet, already acknowledged
+    Packet(String),
+    /// Request to stop the running target
+    Interrupt,
+}
+
+/// Returns the checksum of the packet data, the sum of its bytes modulo 256
+pub fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Returns the framed packet of the data, `$data#checksum`, escaping the reserved characters
+pub fn encode(data: &str) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for byte in data.bytes() {
+        match byte {
+            b'#' | b'$' | b'}' | b'*' => escaped.extend([b'}', byte ^ 0x20]),
+            _ => escaped.push(byte),
+        }
+    }
+    let mut packet = Vec::with_capacity(escaped.len() + 4);
+    packet.push(b'$');
+    packet.extend(&escaped);
+    packet.extend(format!("#{:02x}", checksum(&escaped)).bytes());
+    packet
+}
+
+/// Reader and writer of the packets of a connection
+pub struct Connection<S: Read + Write> {
+    stream: S,
+}
+
+impl<S: Read + Write> Connection<S> {
+    pub fn new(stream: S) -> Self {
+        Self { stream }
+    }
+
+    /// Returns the stream of the connection
+    pub fn stream(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Sends the data as a packet, without waiting for the acknowledgement
+    pub fn send(&mut self, data: &str) -> io::Result<()> {
+        self.stream.write_all(&encode(data))?;
+        self.stream.flush()
+    }
+
+    /// Returns the next message of the client, acknowledging the valid packets and asking again for the corrupted ones.
+    /// Returns none when the client closes the connection.
+    pub fn receive(&mut self) -> io::Result<Option<Message>> {
+        loop {
+            let Some(byte) = self.byte()? else {
+                return Ok(None);
+            };
+            match byte {
+                INTERRUPT => return Ok(Some(Message::Interrupt)),
+                b'$' => {}
+                // Acknowledgements and noise between packets
+                _ => continue,
+            }
+            let mut data = Vec::new();
+            loop {
+                match self.byte()? {
+                    Some(b'#') => break,
+                    Some(b'}') => {
+                        let escaped = self.byte()?.ok_or(ErrorKind::UnexpectedEof)?;
+                        data.push(b'}');
+                        data.push(escaped);
+                    }
+                    Some(byte) => data.push(byte),
+                    None => return Ok(None),
+                }
+            }
+            let mut sum = [0; 2];
+            self.stream.read_exact(&mut sum)?;
+            let expected = std::str::from_utf8(&sum)
+                .ok()
+                .and_then(|sum| u8::from_str_radix(sum, 16).ok());
+            if expected != Some(checksum(&data)) {
+                self.stream.write_all(b"-")?;
+                continue;
+            }
+            self.stream.write_all(b"+")?;
+            self.stream.flush()?;
+            return Ok(Some(Message::Packet(unescape(&data))));
+        }
+    }
+
+    /// Returns the next byte of the stream, none if it's closed
+    fn byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        match self.stream.read(&mut byte) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(byte[0])),
+            Err(e) if e.kind() == ErrorKind::Interrupted => self.byte(),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Returns the data of the packet without the escapes
+fn unescape(data: &[u8]) -> String {
+    let mut unescaped = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'}' => unescaped.extend(bytes.next().map(|byte| byte ^ 0x20)),
+            _ => unescaped.push(*byte),
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::{checksum, encode, Connection, Message};
+
+    /// Stream reading from the input and writing into the output
+    struct Stream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl std::io::Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl std::io::Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn connection(input: &[u8]) -> Connection<Stream> {
+        Connection::new(Stream {
+            input: Cursor::new(input.to_vec()),
+            output: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn encode_packet() {
+        assert_eq!(0x9a, checksum(b"OK"));
+        assert_eq!(b"$OK#9a".to_vec(), encode("OK"));
+        assert_eq!(b"$a}\x03#e1".to_vec(), encode("a#"));
+    }
+
+    #[test]
+    fn receive() {
+        let mut connection = connection(b"+$g#67$m200,2#00$m200,2#5d\x03");
+
+        assert_eq!(
+            Some(Message::Packet("g".into())),
+            connection.receive().unwrap()
+        );
+        // The corrupted packet is rejected
+        assert_eq!(
+            Some(Message::Packet("m200,2".into())),
+            connection.receive().unwrap()
+        );
+        assert_eq!(b"+-+".to_vec(), connection.stream().output);
+        assert_eq!(Some(Message::Interrupt), connection.receive().unwrap());
+        assert_eq!(None, connection.receive().unwrap());
+    }
+
+    #[test]
+    fn unescape() {
+        let mut connection = connection(b"$X}\x03#d8");
+        assert_eq!(
+            Some(Message::Packet("X#".into())),
+            connection.receive().unwrap()
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+};
+
+use crate::{
+    packet::{Connection, Message, INTERRUPT},
+    target::{Action, Target},
+};
+
+/// Stream able to check without blocking if the client has sent an interruption
+pub trait Interruptible: Read + Write {
+    /// Returns true if the client asked to interrupt the execution
+    fn interrupted(&mut self) -> io::Result<bool>;
+}
+
+impl Interruptible for TcpStream {
+    fn interrupted(&mut self) -> io::Result<bool> {
+        self.set_nonblocking(true)?;
+        let mut byte = [0];
+        let read = self.peek(&mut byte);
+        self.set_nonblocking(false)?;
+        match read {
+            Ok(1) if byte[0] == INTERRUPT => self.read_exact(&mut byte).map(|_| true),
+            Ok(_) => Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Serves the packets of the client until it kills the target, detaches or closes the connection
+pub fn serve<S: Interruptible>(target: &mut Target, stream: S) -> io::Result<()> {
+    let mut connection = Connection::new(stream);
+    while let Some(message) = connection.receive()? {
+        let packet = match message {
+            Message::Packet(packet) => packet,
+            // Nothing is running when a packet is awaited
+            Message::Interrupt => continue,
+        };
+        match target.handle(&packet) {
+            Action::Reply(reply) => connection.send(&reply)?,
+            Action::Resume(resume) => {
+                let mut error = None;
+                let reply = target.resume(resume, || {
+                    connection.stream().interrupted().unwrap_or_else(|e| {
+                        error = Some(e);
+                        true
+                    })
+                });
+                if let Some(e) = error {
+                    return Err(e);
+                }
+                connection.send(&reply)?;
+            }
+            Action::Kill => break,
+            Action::Detach(reply) => {
+                connection.send(&reply)?;
+                break;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,462 @@
+use oito_core::{
+    config::Config,
+    core::{OitoCore, Register, Status},
+    Address, Byte,
+};
+
+/// Instructions run between two frame ticks of the timers
+const TICKS_PER_FRAME: usize = 10;
+/// Instructions run between two checks of an interruption of the client
+const TICKS_PER_POLL: usize = 1000;
+
+/// Stop reply after a trap: breakpoints, watchpoints, steps and interruptions
+const TRAP: &str = "S05";
+/// Stop reply after an exception of the emulator
+const ILLEGAL: &str = "S04";
+/// Reply of the invalid requests
+const ERROR: &str = "E01";
+/// Reply of the successful requests without content
+const OK: &str = "OK";
+
+/// Number of the registers in the `p` and `P` packets
+const I: usize = 16;
+const PC: usize = 17;
+const SP: usize = 18;
+const DT: usize = 19;
+const ST: usize = 20;
+
+/// Description of the Chip-8 registers sent to the client, in the order of the `g` packet
+pub const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+  <feature name="org.oito.chip8">
+    <reg name="v0" bitsize="8" regnum="0"/>
+    <reg name="v1" bitsize="8"/>
+    <reg name="v2" bitsize="8"/>
+    <reg name="v3" bitsize="8"/>
+    <reg name="v4" bitsize="8"/>
+    <reg name="v5" bitsize="8"/>
+    <reg name="v6" bitsize="8"/>
+    <reg name="v7" bitsize="8"/>
+    <reg name="v8" bitsize="8"/>
+    <reg name="v9" bitsize="8"/>
+    <reg name="va" bitsize="8"/>
+    <reg name="vb" bitsize="8"/>
+    <reg name="vc" bitsize="8"/>
+    <reg name="vd" bitsize="8"/>
+    <reg name="ve" bitsize="8"/>
+    <reg name="vf" bitsize="8"/>
+    <reg name="i" bitsize="16" type="data_ptr"/>
+    <reg name="pc" bitsize="16" type="code_ptr"/>
+    <reg name="sp" bitsize="8"/>
+    <reg name="dt" bitsize="8"/>
+    <reg name="st" bitsize="8"/>
+  </feature>
+</target>
+"#;
+
+/// What the server must do after a packet
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Send the reply to the client
+    Reply(String),
+    /// Run the emulator and send the stop reply once it stops
+    Resume(Resume),
+    /// Close the connection without reply
+    Kill,
+    /// Send the reply and close the connection
+    Detach(String),
+}
+
+/// Ways to resume the execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resume {
+    /// Run a single instruction
+    Step,
+    /// Run until a breakpoint, a watchpoint, an exception or an interruption
+    Continue,
+}
+
+/// Emulator debugged through the packets of the GDB Remote Serial Protocol
+#[derive(Debug)]
+pub struct Target {
+    /// Emulator running the ROM
+    oito: OitoCore,
+    /// Instructions run since the last frame tick
+    ticks: usize,
+}
+
+impl Target {
+    /// Returns a new target running the ROM in an emulator built with the settings
+    pub fn new(config: Config, rom: &[u8]) -> Self {
+        let mut oito = OitoCore::with_config(config);
+        oito.load(rom);
+        Self { oito, ticks: 0 }
+    }
+
+    /// Returns the emulator
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Returns the action requested by the packet, replying with an empty packet the unsupported ones
+    pub fn handle(&mut self, packet: &str) -> Action {
+        let reply = |reply: Option<String>| Action::Reply(reply.unwrap_or_else(|| ERROR.into()));
+        let Some(kind) = packet.chars().next() else {
+            return Action::Reply(String::new());
+        };
+        let args = &packet[kind.len_utf8()..];
+        match kind {
+            '?' => Action::Reply(TRAP.into()),
+            'g' => Action::Reply(self.read_registers()),
+            'G' => reply(self.write_registers(args)),
+            'p' => reply(self.read_register(args)),
+            'P' => reply(self.write_register(args)),
+            'm' => reply(self.read_memory(args)),
+            'M' => reply(self.write_memory(args)),
+            'Z' | 'z' => match self.breakpoint(kind == 'Z', args) {
+                Some(Some(())) => Action::Reply(OK.into()),
+                Some(None) => Action::Reply(ERROR.into()),
+                None => Action::Reply(String::new()),
+            },
+            's' | 'c' => {
+                if !args.is_empty() {
+                    match parse_hex(args) {
+                        Some(address) => self.oito.set_register(Register::Pc, address),
+                        None => return Action::Reply(ERROR.into()),
+                    }
+                }
+                Action::Resume(match kind {
+                    's' => Resume::Step,
+                    _ => Resume::Continue,
+                })
+            }
+            'H' => Action::Reply(OK.into()),
+            'k' => Action::Kill,
+            'D' => Action::Detach(OK.into()),
+            'q' => Action::Reply(self.query(args)),
+            _ => Action::Reply(String::new()),
+        }
+    }
+
+    /// Runs the emulator and returns the stop reply.
+    /// When continuing, the interrupted function is checked periodically to stop on the requests of the client.
+    pub fn resume(&mut self, resume: Resume, mut interrupted: impl FnMut() -> bool) -> String {
+        if resume == Resume::Step {
+            let executed = self.oito.instructions();
+            return match self.oito.step() {
+                Ok(()) => {
+                    self.count(executed);
+                    TRAP.into()
+                }
+                Err(_) => ILLEGAL.into(),
+            };
+        }
+        for tick in 1.. {
+            let executed = self.oito.instructions();
+            if self.oito.tick().is_err() {
+                return ILLEGAL.into();
+            }
+            self.count(executed);
+            match self.oito.status() {
+                Status::Break(_) => return TRAP.into(),
+                status if status.is_stopped() => return TRAP.into(),
+                _ => {}
+            }
+            if tick % TICKS_PER_POLL == 0 && interrupted() {
+                break;
+            }
+        }
+        TRAP.into()
+    }
+
+    /// Counts the instruction if the tick ran one since the previous count of `executed` instructions,
+    /// ticking the timers at the end of each frame. The ticks stopping at a breakpoint don't count.
+    fn count(&mut self, executed: u64) {
+        if self.oito.instructions() == executed {
+            return;
+        }
+        self.ticks += 1;
+        if self.ticks == TICKS_PER_FRAME {
+            self.ticks = 0;
+            self.oito.frame_tick();
+        }
+    }
+
+    /// Returns the content of every register, with the sixteen bits ones in little endian
+    fn read_registers(&self) -> String {
+        (0..=ST).map(|n| self.register(n).unwrap()).collect()
+    }
+
+    /// Writes every register with the same layout of the read. The stack pointer can't be written.
+    fn write_registers(&mut self, args: &str) -> Option<String> {
+        let mut bytes = decode_hex(args)?.into_iter();
+        let mut values = Vec::with_capacity(ST + 1);
+        for n in 0..=ST {
+            let value = match n {
+                I | PC => u16::from_le_bytes([bytes.next()?, bytes.next()?]),
+                _ => bytes.next()? as u16,
+            };
+            values.push(value);
+        }
+        for (n, value) in values.into_iter().enumerate() {
+            if let Some(register) = register(n) {
+                self.oito.set_register(register, value);
+            }
+        }
+        Some(OK.into())
+    }
+
+    /// Returns the content of the register of the number
+    fn read_register(&self, args: &str) -> Option<String> {
+        self.register(parse_hex(args)? as usize)
+    }
+
+    /// Writes the content, `n=value`, in the register of the number
+    fn write_register(&mut self, args: &str) -> Option<String> {
+        let (n, value) = args.split_once('=')?;
+        let n = parse_hex(n)? as usize;
+        let bytes = decode_hex(value)?;
+        let value = match bytes.as_slice() {
+            [byte] => *byte as u16,
+            [low, high] => u16::from_le_bytes([*low, *high]),
+            _ => return None,
+        };
+        if n != SP {
+            self.oito.set_register(register(n)?, value);
+        }
+        Some(OK.into())
+    }
+
+    /// Returns the content of the register of the number encoded in hexadecimal
+    fn register(&self, n: usize) -> Option<String> {
+        let registers = self.oito.registers();
+        let bytes = match n {
+            0..=15 => vec![registers.v[n]],
+            I => registers.i.to_le_bytes().to_vec(),
+            PC => registers.pc.to_le_bytes().to_vec(),
+            SP => vec![registers.sp as Byte],
+            DT => vec![registers.dt],
+            ST => vec![registers.st],
+            _ => return None,
+        };
+        Some(encode_hex(&bytes))
+    }
+
+    /// Returns the content of the memory of the request `address,length`
+    fn read_memory(&self, args: &str) -> Option<String> {
+        let (address, len) = args.split_once(',')?;
+        let content = self
+            .oito
+            .memory(parse_hex(address)?, parse_hex(len)? as usize)
+            .ok()?;
+        Some(encode_hex(content))
+    }
+
+    /// Writes the content of the request `address,length:content` in memory
+    fn write_memory(&mut self, args: &str) -> Option<String> {
+        let (range, content) = args.split_once(':')?;
+        let (address, len) = range.split_once(',')?;
+        let content = decode_hex(content)?;
+        if content.len() != parse_hex(len)? as usize {
+            return None;
+        }
+        self.oito.poke(parse_hex(address)?, &content).ok()?;
+        Some(OK.into())
+    }
+
+    /// Inserts or removes the point of the request `type,address,kind`.
+    /// Returns none if the type is not supported, and an empty result if the request is invalid.
+    fn breakpoint(&mut self, insert: bool, args: &str) -> Option<Option<()>> {
+        let mut fields = args.split(',');
+        let kind = fields.next()?;
+        let Some(address) = fields.next().and_then(parse_hex) else {
+            return Some(None);
+        };
+        match (kind, insert) {
+            ("0", true) => self.oito.add_breakpoint(address),
+            ("0", false) => {
+                self.oito.remove_breakpoint(address);
+            }
+            ("2", true) => self.oito.add_watchpoint(address),
+            ("2", false) => {
+                self.oito.remove_watchpoint(address);
+            }
+            _ => return None,
+        }
+        Some(Some(()))
+    }
+
+    /// Returns the reply of the general queries
+    fn query(&self, args: &str) -> String {
+        if args.starts_with("Supported") {
+            "PacketSize=1000;qXfer:features:read+".into()
+        } else if let Some(range) = args.strip_prefix("Xfer:features:read:target.xml:") {
+            read_chunk(TARGET_XML, range).unwrap_or_else(|| ERROR.into())
+        } else if args == "Attached" {
+            "1".into()
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Returns the register of the number, none for the stack pointer and the unknown numbers
+fn register(n: usize) -> Option<Register> {
+    match n {
+        0..=15 => Some(Register::V(n as u8)),
+        I => Some(Register::I),
+        PC => Some(Register::Pc),
+        DT => Some(Register::Dt),
+        ST => Some(Register::St),
+        _ => None,
+    }
+}
+
+/// Returns the chunk of the document of the request `offset,length`,
+/// starting with `m` if there is more to read and with `l` if it's the last one
+fn read_chunk(document: &str, range: &str) -> Option<String> {
+    let (offset, len) = range.split_once(',')?;
+    let offset = (parse_hex(offset)? as usize).min(document.len());
+    let end = offset.saturating_add(parse_hex(len)? as usize);
+    let chunk = document.get(offset..end.min(document.len()))?;
+    let mark = if end < document.len() { 'm' } else { 'l' };
+    Some(format!("{mark}{chunk}"))
+}
+
+/// Parses a hexadecimal number
+fn parse_hex(s: &str) -> Option<Address> {
+    Address::from_str_radix(s, 16).ok()
+}
+
+/// Returns the bytes encoded as pairs of hexadecimal digits
+fn encode_hex(bytes: &[Byte]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Returns the bytes of the pairs of hexadecimal digits
+fn decode_hex(s: &str) -> Option<Vec<Byte>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| Byte::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::config::Config;
+
+    use super::{read_chunk, Action, Resume, Target};
+
+    /// LD V0, 0x07; LD I, 0x300; LD [I], V0; JP 0x200
+    const ROM: [u8; 8] = [0x60, 0x07, 0xA3, 0x00, 0xF0, 0x55, 0x12, 0x00];
+
+    fn reply(target: &mut Target, packet: &str) -> String {
+        match target.handle(packet) {
+            Action::Reply(reply) => reply,
+            action => panic!("unexpected action: {action:?}"),
+        }
+    }
+
+    #[test]
+    fn registers() {
+        let mut target = Target::new(Config::default(), &ROM);
+        assert_eq!(
+            format!("{}{}{}", "00".repeat(16), "0000", "0002000000"),
+            reply(&mut target, "g")
+        );
+
+        assert_eq!("OK", reply(&mut target, "P3=2a"));
+        assert_eq!("2a", reply(&mut target, "p3"));
+        assert_eq!("OK", reply(&mut target, "P10=0003"));
+        assert_eq!(0x300, target.oito().registers().i);
+        assert_eq!("E01", reply(&mut target, "p15"));
+        assert_eq!("E01", reply(&mut target, "P3=zz"));
+
+        let registers = format!("{}{}{}", "01".repeat(16), "3412", "0402090807");
+        assert_eq!("OK", reply(&mut target, &format!("G{registers}")));
+        let state = target.oito().registers();
+        assert_eq!([1; 16], state.v);
+        assert_eq!(
+            (0x1234, 0x204, 0, 8, 7),
+            (state.i, state.pc, state.sp, state.dt, state.st)
+        );
+        assert_eq!("E01", reply(&mut target, "G0102"));
+    }
+
+    #[test]
+    fn memory() {
+        let mut target = Target::new(Config::default(), &ROM);
+        assert_eq!("6007a300", reply(&mut target, "m200,4"));
+        assert_eq!("OK", reply(&mut target, "M300,2:beef"));
+        assert_eq!("beef", reply(&mut target, "m300,2"));
+        assert_eq!("E01", reply(&mut target, "mfff,2"));
+        assert_eq!("E01", reply(&mut target, "M300,2:be"));
+    }
+
+    #[test]
+    fn resume() {
+        let mut target = Target::new(Config::default(), &ROM);
+        assert_eq!(Action::Resume(Resume::Step), target.handle("s"));
+        assert_eq!("S05", target.resume(Resume::Step, || false));
+        assert_eq!(0x202, target.oito().registers().pc);
+
+        assert_eq!("OK", reply(&mut target, "Z0,206,2"));
+        assert_eq!("S05", target.resume(Resume::Continue, || false));
+        assert_eq!(0x206, target.oito().registers().pc);
+        assert_eq!("OK", reply(&mut target, "z0,206,2"));
+
+        assert_eq!("OK", reply(&mut target, "Z2,300,1"));
+        assert_eq!("OK", reply(&mut target, "M300,1:00"));
+        assert_eq!(Action::Resume(Resume::Continue), target.handle("c200"));
+        assert_eq!("S05", target.resume(Resume::Continue, || false));
+        assert_eq!(0x206, target.oito().registers().pc);
+        assert_eq!("", reply(&mut target, "Z1,200,2"));
+    }
+
+    #[test]
+    fn interrupt() {
+        let mut target = Target::new(Config::default(), &[0x70, 0x01, 0x12, 0x00]);
+        let mut polls = 0;
+        assert_eq!(
+            "S05",
+            target.resume(Resume::Continue, || {
+                polls += 1;
+                polls == 3
+            })
+        );
+        assert_eq!(3000, target.oito().instructions());
+    }
+
+    #[test]
+    fn count() {
+        let mut target = Target::new(Config::default(), &[0x70, 0x01, 0x12, 0x02]);
+        assert_eq!("S05", target.resume(Resume::Continue, || false));
+        assert_eq!(2, target.ticks);
+        // Halted targets don't run anything else
+        assert_eq!("S05", target.resume(Resume::Step, || false));
+        assert_eq!(2, target.ticks);
+    }
+
+    #[test]
+    fn queries() {
+        let mut target = Target::new(Config::default(), &ROM);
+        assert!(reply(&mut target, "qSupported:multiprocess+").contains("qXfer:features:read+"));
+        assert_eq!("1", reply(&mut target, "qAttached"));
+        assert_eq!("", reply(&mut target, "vCont?"));
+        assert_eq!("S05", reply(&mut target, "?"));
+        assert_eq!(Action::Kill, target.handle("k"));
+        assert_eq!(Action::Detach("OK".into()), target.handle("D"));
+    }
+
+    #[test]
+    fn chunks() {
+        assert_eq!(Some("mabc".into()), read_chunk("abcdef", "0,3"));
+        assert_eq!(Some("ldef".into()), read_chunk("abcdef", "3,10"));
+        assert_eq!(Some("l".into()), read_chunk("abcdef", "6,3"));
+        assert_eq!(None, read_chunk("abcdef", "x,3"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Scripted session of a client speaking the Remote Serial Protocol with the server,
+//! replacing gdb to check the stub over a real loopback connection.
+
+use std::{
+    io::{Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use gdb::{
+    packet::{checksum, encode},
+    server,
+    target::{Target, TARGET_XML},
+};
+use oito_core::config::Config;
+
+/// LD V0, 0x07; LD I, 0x300; LD [I], V0; ADD V1, 1; JP 0x206
+const ROM: [u8; 10] = [0x60, 0x07, 0xA3, 0x00, 0xF0, 0x55, 0x71, 0x01, 0x12, 0x06];
+
+/// Client sending the packets and checking the acknowledgements
+struct Client {
+    stream: TcpStream,
+}
+
+impl Client {
+    /// Sends the packet and returns the reply, acknowledging it
+    fn request(&mut self, packet: &str) -> String {
+        self.stream.write_all(&encode(packet)).unwrap();
+        assert_eq!(b'+', self.byte(), "packet {packet} not acknowledged");
+        self.reply()
+    }
+
+    /// Returns the next packet sent by the server, acknowledging it
+    fn reply(&mut self) -> String {
+        assert_eq!(b'$', self.byte());
+        let mut data = Vec::new();
+        loop {
+            match self.byte() {
+                b'#' => break,
+                byte => data.push(byte),
+            }
+        }
+        let sum = [self.byte(), self.byte()];
+        let sum = u8::from_str_radix(std::str::from_utf8(&sum).unwrap(), 16).unwrap();
+        assert_eq!(checksum(&data), sum);
+        self.stream.write_all(b"+").unwrap();
+        String::from_utf8(data).unwrap()
+    }
+
+    fn byte(&mut self) -> u8 {
+        let mut byte = [0];
+        self.stream.read_exact(&mut byte).unwrap();
+        byte[0]
+    }
+}
+
+/// Starts a server of the ROM in another thread and returns a client connected to it
+fn connect(rom: &[u8]) -> (Client, JoinHandle<Target>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let mut target = Target::new(Config::default(), rom);
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        server::serve(&mut target, stream).unwrap();
+        target
+    });
+    let stream = TcpStream::connect(address).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    (Client { stream }, server)
+}
+
+#[test]
+fn session() {
+    let (mut client, server) = connect(&ROM);
+
+    assert!(client
+        .request("qSupported:multiprocess+;xmlRegisters=i386")
+        .contains("qXfer:features:read+"));
+    assert_eq!("S05", client.request("?"));
+
+    // Target description read in chunks
+    let mut xml = String::new();
+    loop {
+        let chunk = client.request(&format!(
+            "qXfer:features:read:target.xml:{:x},80",
+            xml.len()
+        ));
+        xml.push_str(&chunk[1..]);
+        if chunk.starts_with('l') {
+            break;
+        }
+    }
+    assert_eq!(TARGET_XML, xml);
+    for register in ["v0", "vf", "i", "pc", "sp", "dt", "st"] {
+        assert!(xml.contains(&format!("name=\"{register}\"")));
+    }
+
+    // Registers
+    let registers = client.request("g");
+    assert_eq!(46, registers.len());
+    assert_eq!("0002", &registers[36..40]);
+
+    // Single step
+    assert_eq!("S05", client.request("s"));
+    assert_eq!("07", client.request("p0"));
+    assert_eq!("0202", client.request("p11"));
+
+    // Software breakpoint
+    assert_eq!("OK", client.request("Z0,208,2"));
+    assert_eq!("S05", client.request("c"));
+    assert_eq!("0802", client.request("p11"));
+    assert_eq!("01", client.request("p1"));
+    assert_eq!("07", client.request("m300,1"));
+    // Continuing from the breakpoint stops at it again after the loop
+    assert_eq!("S05", client.request("c"));
+    assert_eq!("02", client.request("p1"));
+    assert_eq!("OK", client.request("z0,208,2"));
+
+    // Memory and register writes
+    assert_eq!("OK", client.request("M300,3:0a0b0c"));
+    assert_eq!("0a0b0c", client.request("m300,3"));
+    assert_eq!("E01", client.request("mfff,4"));
+    assert_eq!("OK", client.request("P1=f0"));
+    assert_eq!("f0", client.request("p1"));
+
+    assert_eq!("OK", client.request("D"));
+    let target = server.join().unwrap();
+    assert_eq!(0xF0, target.oito().registers().v[1]);
+}
+
+#[test]
+fn interrupt() {
+    let (mut client, server) = connect(&ROM);
+
+    client.stream.write_all(&encode("c")).unwrap();
+    assert_eq!(b'+', client.byte());
+    thread::sleep(Duration::from_millis(50));
+    client.stream.write_all(&[0x03]).unwrap();
+    assert_eq!("S05", client.reply());
+    assert!(client.request("p1") != "00");
+
+    client.stream.write_all(&encode("k")).unwrap();
+    assert_eq!(b'+', client.byte());
+    let target = server.join().unwrap();
+    assert!(target.oito().instructions() > 0);
+}
@@ -0,0 +1,68 @@
+use oito_core::{config::Config, exception::Exception};
+use rayon::prelude::*;
+
+use crate::env::{Env, Observation, Spec, Step};
+
+/// Group of environments stepped in parallel, one action for each one
+#[derive(Debug)]
+pub struct Batch {
+    envs: Vec<Env>,
+}
+
+impl Batch {
+    /// Returns a batch of the environments
+    pub fn new(envs: Vec<Env>) -> Self {
+        Self { envs }
+    }
+
+    /// Returns a batch of copies of the same environment
+    pub fn with_copies(copies: usize, config: Config, rom: &[u8], spec: Spec) -> Self {
+        Self::new(
+            (0..copies)
+                .map(|_| Env::new(config.clone(), rom, spec.clone()))
+                .collect(),
+        )
+    }
+
+    /// Returns the number of environments
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    /// Returns true if there are no environments
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    /// Returns the environments
+    pub fn envs(&self) -> &[Env] {
+        &self.envs
+    }
+
+    /// Starts a new episode in every environment with its seed and returns the first observations.
+    /// Panics unless there is a seed for each environment.
+    pub fn reset(&mut self, seeds: &[u64]) -> Vec<Observation> {
+        assert_eq!(self.envs.len(), seeds.len(), "one seed per environment");
+        self.envs
+            .par_iter_mut()
+            .zip(seeds)
+            .map(|(env, seed)| env.reset(*seed))
+            .collect()
+    }
+
+    /// Starts a new episode in the environment of the index
+    pub fn reset_one(&mut self, index: usize, seed: u64) -> Observation {
+        self.envs[index].reset(seed)
+    }
+
+    /// Steps every environment with its action, in parallel, and returns the results in the same order.
+    /// Panics unless there is an action for each environment.
+    pub fn step(&mut self, actions: &[u16]) -> Vec<Result<Step, Exception>> {
+        assert_eq!(self.envs.len(), actions.len(), "one action per environment");
+        self.envs
+            .par_iter_mut()
+            .zip(actions)
+            .map(|(env, action)| env.step(*action))
+            .collect()
+    }
+}
@@ -0,0 +1,187 @@
+use oito_core::{
+    config::Config,
+    core::{OitoCore, Status},
+    exception::Exception,
+    Row, SCREEN_HEIGHT,
+};
+
+use crate::expr::{Expr, Snapshot};
+
+/// Packed frame buffer, one row per line with the leftmost pixel as the most significant bit
+pub type Observation = [Row; SCREEN_HEIGHT];
+
+/// Definition of the task of the agents in a game
+#[derive(Clone, Debug)]
+pub struct Spec {
+    /// Reward of each step, like `mem[0x2F0] - prev(mem[0x2F0])` for the increase of a score
+    pub reward: Expr,
+    /// Condition ending the episode when not zero, like `v7 == 0` for the last life lost
+    pub done: Expr,
+    /// Frames run in each step with the same action
+    pub frames_per_step: usize,
+    /// Instructions run per frame
+    pub ticks_per_frame: usize,
+    /// Steps after which the episode is truncated
+    pub max_steps: Option<usize>,
+}
+
+impl Spec {
+    /// Returns the spec with the reward and done expressions, running four frames of ten instructions per step
+    pub fn new(reward: Expr, done: Expr) -> Self {
+        Self {
+            reward,
+            done,
+            frames_per_step: 4,
+            ticks_per_frame: 10,
+            max_steps: None,
+        }
+    }
+}
+
+/// Result of a step
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// Frame after the step
+    pub observation: Observation,
+    /// Value of the reward expression
+    pub reward: i64,
+    /// True if the episode is over: the done expression is true, the ROM halted or the steps were exhausted
+    pub done: bool,
+}
+
+/// Gym-style environment running a ROM in an emulator
+#[derive(Debug)]
+pub struct Env {
+    oito: OitoCore,
+    /// Settings used to rebuild the emulator in the resets, seeded on each one
+    config: Config,
+    rom: Vec<u8>,
+    spec: Spec,
+    /// State after the last step, read by the `prev` expressions
+    previous: Snapshot,
+    /// Steps run in the current episode
+    steps: usize,
+}
+
+impl Env {
+    /// Returns an environment running the ROM with the spec. It must be reset before the first step.
+    pub fn new(config: Config, rom: &[u8], spec: Spec) -> Self {
+        let mut env = Self {
+            oito: OitoCore::new(),
+            config,
+            rom: rom.to_vec(),
+            spec,
+            previous: Snapshot::default(),
+            steps: 0,
+        };
+        env.reset(0);
+        env
+    }
+
+    /// Returns the emulator
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Returns the current frame
+    pub fn observation(&self) -> Observation {
+        self.oito.frame_rows().try_into().unwrap()
+    }
+
+    /// Starts a new episode with the random numbers of the seed and returns the first observation
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.config.seed = Some(seed);
+        self.oito = OitoCore::with_config(self.config.clone());
+        self.oito.load(&self.rom);
+        self.previous = Snapshot::of(&self.oito);
+        self.steps = 0;
+        self.observation()
+    }
+
+    /// Runs the frames of a step holding the keys of the action, a bitmask where bit N is the key of value N
+    pub fn step(&mut self, action: u16) -> Result<Step, Exception> {
+        self.oito.set_keys(action);
+        'frames: for _ in 0..self.spec.frames_per_step {
+            for _ in 0..self.spec.ticks_per_frame {
+                self.oito.tick()?;
+                if self.oito.status().is_stopped() {
+                    break 'frames;
+                }
+            }
+            self.oito.frame_tick();
+        }
+        self.steps += 1;
+
+        let now = Snapshot::of(&self.oito);
+        let reward = self.spec.reward.eval(&now, &self.previous);
+        let done = self.spec.done.eval(&now, &self.previous) != 0
+            || self.oito.status().is_stopped()
+            || matches!(self.oito.status(), Status::Break(_))
+            || self.spec.max_steps.is_some_and(|max| self.steps >= max);
+        self.previous = now;
+        Ok(Step {
+            observation: self.observation(),
+            reward,
+            done,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::config::Config;
+
+    use super::{Env, Spec};
+
+    /// Counts in V1 the frames holding the key 5, storing it at 0x301:
+    /// LD V0, 5; SKP V0; JP 0x202; ADD V1, 1; LD I, 0x300; LD [I], V1; JP 0x202
+    const ROM: [u8; 14] = [
+        0x60, 0x05, 0xE0, 0x9E, 0x12, 0x02, 0x71, 0x01, 0xA3, 0x00, 0xF1, 0x55, 0x12, 0x02,
+    ];
+
+    fn env() -> Env {
+        let mut spec = Spec::new(
+            "mem[0x301] - prev(mem[0x301])".parse().unwrap(),
+            "v1 >= 3".parse().unwrap(),
+        );
+        spec.frames_per_step = 1;
+        spec.ticks_per_frame = 5;
+        Env::new(Config::default(), &ROM, spec)
+    }
+
+    #[test]
+    fn step() {
+        let mut env = env();
+        assert_eq!([0; 32], env.reset(1));
+
+        let step = env.step(0x0020).unwrap();
+        assert_eq!((1, false), (step.reward, step.done));
+        assert_eq!(0x0020, env.oito().keys());
+        let step = env.step(0x0000).unwrap();
+        assert_eq!((0, false), (step.reward, step.done));
+        let step = env.step(0x0020).unwrap();
+        assert_eq!((1, false), (step.reward, step.done));
+        let step = env.step(0x0020).unwrap();
+        assert_eq!((1, true), (step.reward, step.done));
+
+        env.reset(1);
+        assert_eq!(0, env.oito().registers().v[1]);
+        assert_eq!(0, env.step(0x0000).unwrap().reward);
+    }
+
+    #[test]
+    fn max_steps() {
+        let mut env = env();
+        env.spec.max_steps = Some(2);
+        assert!(!env.step(0x0000).unwrap().done);
+        assert!(env.step(0x0000).unwrap().done);
+    }
+
+    #[test]
+    fn halted() {
+        let spec = Spec::new("0".parse().unwrap(), "0".parse().unwrap());
+        // JP 0x200
+        let mut env = Env::new(Config::default(), &[0x12, 0x00], spec);
+        assert!(env.step(0x0000).unwrap().done);
+    }
+}
@@ -0,0 +1,353 @@
+use std::{iter::Peekable, str::FromStr, vec::IntoIter};
+
+use oito_core::{
+    core::{OitoCore, Registers},
+    Byte,
+};
+
+/// Size of the memory of the emulator
+const MEMORY: usize = 4096;
+
+/// Copy of the memory and registers of an emulator, the values read by the expressions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    memory: Vec<Byte>,
+    registers: Registers,
+}
+
+impl Snapshot {
+    /// Returns the snapshot of the current state of the emulator
+    pub fn of(oito: &OitoCore) -> Self {
+        Self {
+            memory: oito.memory(0, MEMORY).unwrap().to_vec(),
+            registers: oito.registers(),
+        }
+    }
+
+    /// Returns the byte at the address, zero out of the memory
+    fn byte(&self, address: i64) -> i64 {
+        usize::try_from(address)
+            .ok()
+            .and_then(|address| self.memory.get(address))
+            .map_or(0, |byte| *byte as i64)
+    }
+}
+
+/// Registers and timers readable by the expressions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    V(usize),
+    I,
+    Pc,
+    Sp,
+    Dt,
+    St,
+}
+
+/// Binary operators, from the highest precedence to the lowest one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// Integer expression over the memory and registers of the emulator, like `mem[i + 2] * 10` or `v3 == 0`.
+/// The comparisons and logical operators return 1 if true and 0 otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// Constant, decimal or hexadecimal with the `0x` prefix
+    Number(i64),
+    /// Content of a register or timer: `v0` to `vf`, `i`, `pc`, `sp`, `dt` or `st`
+    Register(Source),
+    /// Byte at the address, `mem[address]`
+    Memory(Box<Expr>),
+    /// Value of the expression before the last step, `prev(expr)`
+    Prev(Box<Expr>),
+    /// Arithmetic negation, `-expr`
+    Neg(Box<Expr>),
+    /// Logical negation, `!expr`
+    Not(Box<Expr>),
+    /// Binary operation
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Returns the value of the expression with the current state and the one before the last step.
+    /// Divisions by zero and addresses out of the memory are zero.
+    pub fn eval(&self, now: &Snapshot, prev: &Snapshot) -> i64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::Register(source) => {
+                let registers = &now.registers;
+                match source {
+                    Source::V(x) => registers.v[*x] as i64,
+                    Source::I => registers.i as i64,
+                    Source::Pc => registers.pc as i64,
+                    Source::Sp => registers.sp as i64,
+                    Source::Dt => registers.dt as i64,
+                    Source::St => registers.st as i64,
+                }
+            }
+            Expr::Memory(address) => now.byte(address.eval(now, prev)),
+            Expr::Prev(expr) => expr.eval(prev, prev),
+            Expr::Neg(expr) => expr.eval(now, prev).wrapping_neg(),
+            Expr::Not(expr) => (expr.eval(now, prev) == 0) as i64,
+            Expr::Binary(op, left, right) => {
+                let (a, b) = (left.eval(now, prev), right.eval(now, prev));
+                match op {
+                    Op::Mul => a.wrapping_mul(b),
+                    Op::Div => a.checked_div(b).unwrap_or(0),
+                    Op::Rem => a.checked_rem(b).unwrap_or(0),
+                    Op::Add => a.wrapping_add(b),
+                    Op::Sub => a.wrapping_sub(b),
+                    Op::Eq => (a == b) as i64,
+                    Op::Ne => (a != b) as i64,
+                    Op::Lt => (a < b) as i64,
+                    Op::Le => (a <= b) as i64,
+                    Op::Gt => (a > b) as i64,
+                    Op::Ge => (a >= b) as i64,
+                    Op::And => (a != 0 && b != 0) as i64,
+                    Op::Or => (a != 0 || b != 0) as i64,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?.into_iter().peekable(),
+        };
+        let expr = parser.binary(0)?;
+        match parser.tokens.next() {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {token} in expression: {s}")),
+        }
+    }
+}
+
+/// Levels of precedence of the binary operators, from the lowest to the highest
+const PRECEDENCE: [&[(&str, Op)]; 5] = [
+    &[("||", Op::Or)],
+    &[("&&", Op::And)],
+    &[
+        ("==", Op::Eq),
+        ("!=", Op::Ne),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ],
+    &[("+", Op::Add), ("-", Op::Sub)],
+    &[("*", Op::Mul), ("/", Op::Div), ("%", Op::Rem)],
+];
+
+/// Symbols of the expressions, the longest ones first
+const SYMBOLS: [&str; 18] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!", "(", ")", "[", "]",
+];
+
+/// Splits the expression in numbers, names and symbols
+fn tokenize(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_alphanumeric() || c == '_' {
+            rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len())
+        } else {
+            SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .map(|symbol| symbol.len())
+                .ok_or_else(|| format!("unexpected {c} in expression: {s}"))?
+        };
+        tokens.push(rest[..len].to_ascii_lowercase());
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser of the tokens of an expression
+struct Parser {
+    tokens: Peekable<IntoIter<String>>,
+}
+
+impl Parser {
+    /// Parses the binary operations of the level of precedence and the higher ones
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+        let mut expr = self.binary(level + 1)?;
+        while let Some(op) = self
+            .tokens
+            .peek()
+            .and_then(|token| operators.iter().find(|(symbol, _)| symbol == token))
+            .map(|(_, op)| *op)
+        {
+            self.tokens.next();
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.binary(level + 1)?));
+        }
+        Ok(expr)
+    }
+
+    /// Parses a negation or an operand
+    fn unary(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.next().ok_or("unexpected end of expression")?;
+        match token.as_str() {
+            "-" => Ok(Expr::Neg(Box::new(self.unary()?))),
+            "!" => Ok(Expr::Not(Box::new(self.unary()?))),
+            "(" => self.enclosed(")"),
+            "mem" => {
+                self.expect("[")?;
+                Ok(Expr::Memory(Box::new(self.enclosed("]")?)))
+            }
+            "prev" => {
+                self.expect("(")?;
+                Ok(Expr::Prev(Box::new(self.enclosed(")")?)))
+            }
+            "i" => Ok(Expr::Register(Source::I)),
+            "pc" => Ok(Expr::Register(Source::Pc)),
+            "sp" => Ok(Expr::Register(Source::Sp)),
+            "dt" => Ok(Expr::Register(Source::Dt)),
+            "st" => Ok(Expr::Register(Source::St)),
+            name => parse_v(name)
+                .map(|x| Expr::Register(Source::V(x)))
+                .or_else(|| parse_number(name).map(Expr::Number))
+                .ok_or_else(|| format!("unexpected {name} in expression")),
+        }
+    }
+
+    /// Parses an expression followed by the closing symbol
+    fn enclosed(&mut self, close: &str) -> Result<Expr, String> {
+        let expr = self.binary(0)?;
+        self.expect(close)?;
+        Ok(expr)
+    }
+
+    /// Consumes the next token, failing if it's not the expected one
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.tokens.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!(
+                "expected {expected} but found {token} in expression"
+            )),
+            None => Err(format!("expected {expected} at the end of expression")),
+        }
+    }
+}
+
+/// Parses the name of a V-Register, `v0` to `vf`
+fn parse_v(name: &str) -> Option<usize> {
+    let x = name.strip_prefix('v')?;
+    if x.len() != 1 {
+        return None;
+    }
+    usize::from_str_radix(x, 16).ok()
+}
+
+/// Parses a number in decimal, or in hexadecimal if it starts with `0x`
+fn parse_number(s: &str) -> Option<i64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::core::Registers;
+
+    use oito_core::{Address, Byte};
+
+    use super::{Expr, Op, Snapshot, Source, MEMORY};
+
+    fn snapshot(registers: Registers, memory: &[(Address, Byte)]) -> Snapshot {
+        let mut snapshot = Snapshot {
+            memory: vec![0; MEMORY],
+            registers,
+        };
+        for (address, byte) in memory {
+            snapshot.memory[*address as usize] = *byte;
+        }
+        snapshot
+    }
+
+    fn eval(s: &str) -> i64 {
+        let mut v = [0; 16];
+        v[3] = 7;
+        let registers = Registers {
+            i: 0x300,
+            v,
+            ..Default::default()
+        };
+        let now = snapshot(registers, &[(0x300, 1), (0x302, 42)]);
+        let prev = snapshot(Registers::default(), &[(0x302, 40)]);
+        s.parse::<Expr>().unwrap().eval(&now, &prev)
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Ok(Expr::Binary(
+                Op::Add,
+                Box::new(Expr::Number(1)),
+                Box::new(Expr::Binary(
+                    Op::Mul,
+                    Box::new(Expr::Register(Source::V(0xA))),
+                    Box::new(Expr::Number(0x10))
+                ))
+            )),
+            "1 + VA * 0x10".parse()
+        );
+        assert!("".parse::<Expr>().is_err());
+        assert!("1 +".parse::<Expr>().is_err());
+        assert!("mem[1".parse::<Expr>().is_err());
+        assert!("(1))".parse::<Expr>().is_err());
+        assert!("v10".parse::<Expr>().is_err());
+        assert!("x".parse::<Expr>().is_err());
+        assert!("1 $ 2".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(7, eval("1 + 2 * 3"));
+        assert_eq!(9, eval("(1 + 2) * 3"));
+        assert_eq!(-4, eval("-(1 + 3)"));
+        assert_eq!(1, eval("7 % 3"));
+        assert_eq!(0, eval("7 / 0"));
+        assert_eq!(2, eval("10 - 5 - 3"));
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(1, eval("v3 == 7 && !v0"));
+        assert_eq!(0, eval("v3 < 7 || v3 > 7"));
+        assert_eq!(1, eval("v3 <= 7 && v3 >= 7 && v3 != 8"));
+    }
+
+    #[test]
+    fn state() {
+        assert_eq!(0x300, eval("i"));
+        assert_eq!(42, eval("mem[i + 2]"));
+        assert_eq!(2, eval("mem[i + 2] - prev(mem[0x302])"));
+        // prev reads the registers of the previous state too
+        assert_eq!(40, eval("prev(mem[i + 2] + mem[0x302])"));
+        assert_eq!(0, eval("mem[0x1000] + mem[-1]"));
+    }
+}
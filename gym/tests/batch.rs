@@ -0,0 +1,87 @@
+//! Checks that the environments stepped in parallel behave as if they were stepped one by one,
+//! and that the seeds make the episodes reproducible.
+
+use gym::{
+    batch::Batch,
+    env::{Env, Spec},
+};
+use oito_core::config::Config;
+
+/// Adds a random number to V2 while the key of value V0 is held, storing V0 to V2 at 0x300:
+/// LD V0, 1; SKP V0; JP 0x202; RND V1, 0x0F; ADD V2, V1; LD I, 0x300; LD [I], V2; JP 0x202
+const ROM: [u8; 16] = [
+    0x60, 0x01, 0xE0, 0x9E, 0x12, 0x02, 0xC1, 0x0F, 0x82, 0x14, 0xA3, 0x00, 0xF2, 0x55, 0x12, 0x02,
+];
+
+fn spec() -> Spec {
+    let mut spec = Spec::new(
+        "mem[0x302] - prev(mem[0x302])".parse().unwrap(),
+        "v2 >= 200".parse().unwrap(),
+    );
+    spec.max_steps = Some(50);
+    spec
+}
+
+/// Action of the step of an environment, holding the key in some of them
+fn action(env: usize, step: usize) -> u16 {
+    if (env + step).is_multiple_of(3) {
+        0x0000
+    } else {
+        0x0002
+    }
+}
+
+#[test]
+fn parallel_equals_sequential() {
+    let copies = 8;
+    let seeds: Vec<u64> = (0..copies as u64).collect();
+    let mut batch = Batch::with_copies(copies, Config::default(), &ROM, spec());
+    let mut envs: Vec<Env> = (0..copies)
+        .map(|_| Env::new(Config::default(), &ROM, spec()))
+        .collect();
+
+    let observations = batch.reset(&seeds);
+    assert_eq!(copies, observations.len());
+    for (env, seed) in envs.iter_mut().zip(&seeds) {
+        env.reset(*seed);
+    }
+
+    for step in 0..50 {
+        let actions: Vec<u16> = (0..copies).map(|env| action(env, step)).collect();
+        let steps = batch.step(&actions);
+        for (i, env) in envs.iter_mut().enumerate() {
+            assert_eq!(env.step(actions[i]).unwrap(), *steps[i].as_ref().unwrap());
+        }
+    }
+    // The episodes got different random numbers
+    let totals: Vec<u8> = batch
+        .envs()
+        .iter()
+        .map(|env| env.oito().registers().v[2])
+        .collect();
+    assert!(totals.iter().any(|total| *total != totals[0]));
+}
+
+#[test]
+fn seeds_are_reproducible() {
+    let mut batch = Batch::with_copies(2, Config::default(), &ROM, spec());
+    let run = |batch: &mut Batch, seeds: &[u64]| {
+        batch.reset(seeds);
+        (0..20)
+            .map(|_| batch.step(&[0x0002, 0x0002]))
+            .map(|steps| {
+                steps
+                    .into_iter()
+                    .map(|step| step.unwrap().reward)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let first = run(&mut batch, &[3, 3]);
+    assert!(first.iter().all(|rewards| rewards[0] == rewards[1]));
+    assert_eq!(first, run(&mut batch, &[3, 3]));
+    assert_ne!(first, run(&mut batch, &[3, 4]));
+
+    batch.reset_one(1, 9);
+    assert_eq!(0, batch.envs()[1].oito().instructions());
+}
@@ -0,0 +1,47 @@
+use oito_core::{config::Protection, video::Upscaler};
+use structopt::StructOpt;
+
+use headless::{output::Format, script::KeyPress};
+
+/// Run a Chip-8 ROM without window, dumping its frames and final state
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    /// The ROM file to run
+    pub file: String,
+    /// Maximum number of frames to run, the run stops before if the ROM halts
+    #[structopt(long = "frames", short = "n", default_value = "600")]
+    pub frames: usize,
+    /// Number of instructions run each frame
+    #[structopt(long = "ticks", short = "t", default_value = "10")]
+    pub ticks: usize,
+    /// Key to press at a frame as FRAME:KEY[:DURATION], like 60:A or 60:5:10. Can be repeated.
+    #[structopt(long = "press", short = "p")]
+    pub presses: Vec<KeyPress>,
+    /// Directory where the frames and the summary will be written
+    #[structopt(long = "out", short = "o", default_value = "out")]
+    pub out: String,
+    /// Format of the written frames: png or pbm
+    #[structopt(long = "format", short = "f", default_value = "png")]
+    pub format: Format,
+    /// Upscaler applied to the written frames: nearest[:factor], scale2x, scale3x or epx
+    #[structopt(long = "upscaler", short = "u", default_value = "nearest")]
+    pub upscaler: Upscaler,
+    /// Maximum number of instructions to run
+    #[structopt(long = "max-instructions")]
+    pub max_instructions: Option<u64>,
+    /// Maximum number of seconds to run
+    #[structopt(long = "max-time")]
+    pub max_time: Option<u64>,
+    /// Memory protected against the writes of the ROM as REGION[:ACTION], like font or rom:ignore. Can be repeated.
+    #[structopt(long = "protect")]
+    pub protected: Vec<Protection>,
+    /// Fx0A waits for the key to be released, like the COSMAC VIP
+    #[structopt(long = "wait-release")]
+    pub wait_release: bool,
+    /// Reports the suspicious behaviour of the ROM, like reads of uninitialized memory
+    #[structopt(long = "sanitize", short = "s")]
+    pub sanitize: bool,
+    /// Writes every frame and not only the last one
+    #[structopt(long = "dump-all", short = "a")]
+    pub dump_all: bool,
+}
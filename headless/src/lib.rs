@@ -0,0 +1,3 @@
+pub mod output;
+pub mod runner;
+pub mod script;
@@ -0,0 +1,91 @@
+use headless::{
+    output::{self, Summary, BACKGROUND},
+    runner::Runner,
+    script::Script,
+};
+use oito_core::{
+    config::{Config, Limits, Quirks},
+    video::{FrameConverter, Palette, PixelFormat, Rgb},
+};
+use rom_loader::{desktop::FilePathLoader, RomLoader};
+use std::{error::Error, fs, path::Path, time::Duration};
+use structopt::StructOpt;
+
+mod args;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = args::Args::from_args();
+    simple_logger::init_with_level(log::Level::Warn)?;
+
+    let loader = FilePathLoader::new(&args.file);
+    let out = Path::new(&args.out);
+    fs::create_dir_all(out)?;
+
+    let converter = FrameConverter::new(
+        Palette::mono(BACKGROUND, Rgb(0xFF, 0xFF, 0xFF)),
+        PixelFormat::Rgba8888,
+        args.upscaler,
+    );
+    let mut image = vec![0; converter.buffer_size()];
+    let mut write_frame = |oito: &oito_core::core::OitoCore, name: &str| {
+        converter.convert(oito.frame_rows(), &mut image);
+        let path = out.join(format!("{name}.{}", args.format.extension()));
+        args.format
+            .write(&path, &image, converter.width(), converter.height())
+    };
+
+    let config = Config {
+        limits: Limits {
+            instructions: args.max_instructions,
+            time: args.max_time.map(Duration::from_secs),
+        },
+        sanitize: args.sanitize,
+        protected: args.protected,
+        quirks: Quirks {
+            wait_release: args.wait_release,
+        },
+        ..Default::default()
+    };
+    let mut runner =
+        Runner::with_config(config, loader.rom(), Script::new(args.presses), args.ticks);
+    let mut dump_error = None;
+    let outcome = runner.run(args.frames, |frame, oito| {
+        if args.dump_all && dump_error.is_none() {
+            dump_error = write_frame(oito, &format!("frame_{frame:05}")).err();
+        }
+    });
+    if let Some(error) = dump_error {
+        return Err(error);
+    }
+    write_frame(runner.oito(), "final")?;
+
+    let diagnostics = runner.oito().diagnostics();
+    for diagnostic in diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let summary = Summary {
+        rom: args.file.clone(),
+        frames: outcome.frames,
+        instructions: outcome.instructions,
+        halted: outcome.halted,
+        status: outcome.status.to_string(),
+        exception: outcome.exception.as_ref().map(|e| e.to_string()),
+        registers: runner.oito().registers().into(),
+        display: output::display(runner.oito().frame_rows()),
+        diagnostics: diagnostics.iter().map(|d| d.to_string()).collect(),
+    };
+    fs::write(
+        out.join("summary.json"),
+        serde_json::to_string_pretty(&summary)?,
+    )?;
+
+    println!(
+        "{}: {} frames and {} instructions run, {}",
+        args.file, outcome.frames, outcome.instructions, outcome.status
+    );
+    match outcome.exception {
+        Some(exception) => Err(exception.into()),
+        None => Ok(()),
+    }
+}
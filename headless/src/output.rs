@@ -0,0 +1,159 @@
+use std::{error::Error, fs::File, io::BufWriter, path::Path, str::FromStr};
+
+use oito_core::{core::Registers, video::Rgb, Row};
+use serde::Serialize;
+
+/// Color of the unlit pixels in the converted frames
+pub const BACKGROUND: Rgb = Rgb(0x00, 0x00, 0x00);
+/// Number of bytes of each pixel of the converted frames
+const BYTES_PER_PIXEL: usize = 4;
+
+/// Image formats of the written frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    /// Portable bitmap, with the lit pixels as ink
+    Pbm,
+}
+
+impl Format {
+    /// Returns the extension of the files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Pbm => "pbm",
+        }
+    }
+
+    /// Writes the RGBA image into the specified file
+    pub fn write(
+        &self,
+        path: &Path,
+        image: &[u8],
+        width: usize,
+        height: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        match self {
+            Format::Png => {
+                let file = BufWriter::new(File::create(path)?);
+                let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.write_header()?.write_image_data(image)?;
+            }
+            Format::Pbm => std::fs::write(path, pbm(image, width, height))?,
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(Format::Png),
+            "pbm" => Ok(Format::Pbm),
+            _ => Err(format!("invalid format: {s}")),
+        }
+    }
+}
+
+/// Encodes the RGBA image as a binary portable bitmap, marking the pixels not painted with the background
+fn pbm(image: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let Rgb(r, g, b) = BACKGROUND;
+    let mut pbm = format!("P4\n{width} {height}\n").into_bytes();
+    for row in image.chunks(width * BYTES_PER_PIXEL).take(height) {
+        let mut bits = vec![0u8; width.div_ceil(8)];
+        for (x, pixel) in row.chunks(BYTES_PER_PIXEL).enumerate() {
+            if pixel[..3] != [r, g, b] {
+                bits[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        pbm.extend_from_slice(&bits);
+    }
+    pbm
+}
+
+/// State of the emulator at the end of the run
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    /// ROM file run
+    pub rom: String,
+    /// Number of frames run
+    pub frames: usize,
+    /// Number of instructions executed
+    pub instructions: usize,
+    /// True if the run stopped because the ROM halted
+    pub halted: bool,
+    /// Execution state of the emulator at the end of the run
+    pub status: String,
+    /// Exception that stopped the run, if any
+    pub exception: Option<String>,
+    /// Registers and timers
+    pub registers: RegistersSummary,
+    /// Rows of the last frame in hexadecimal, the leftmost pixel is the most significant bit
+    pub display: Vec<String>,
+    /// Bugs of the ROM detected by the sanitizer, empty unless it's enabled
+    pub diagnostics: Vec<String>,
+}
+
+/// Registers and timers of the emulator
+#[derive(Debug, Serialize)]
+pub struct RegistersSummary {
+    pub pc: u16,
+    pub i: u16,
+    pub v: [u8; 16],
+    pub sp: usize,
+    pub dt: u8,
+    pub st: u8,
+}
+
+impl From<Registers> for RegistersSummary {
+    fn from(registers: Registers) -> Self {
+        Self {
+            pc: registers.pc,
+            i: registers.i,
+            v: registers.v,
+            sp: registers.sp,
+            dt: registers.dt,
+            st: registers.st,
+        }
+    }
+}
+
+/// Formats the rows of the frame in hexadecimal
+pub fn display(rows: &[Row]) -> Vec<String> {
+    rows.iter().map(|row| format!("{row:016x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::Format;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Format::Png), "png".parse());
+        assert_eq!(Ok(Format::Pbm), "pbm".parse());
+        assert!("gif".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn pbm() {
+        #[rustfmt::skip]
+        let image = [
+            0, 0, 0, 255, 255, 255, 255, 255,
+            255, 255, 255, 255, 0, 0, 0, 255,
+        ];
+
+        assert_eq!(b"P4\n2 2\n\x40\x80".to_vec(), super::pbm(&image, 2, 2));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            vec!["8000000000000001", "0000000000000000"],
+            super::display(&[0x8000_0000_0000_0001, 0])
+        );
+    }
+}
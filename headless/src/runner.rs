@@ -0,0 +1,182 @@
+use oito_core::{
+    config::Config,
+    core::{OitoCore, Status},
+    exception::Exception,
+    Rom,
+};
+
+use crate::script::Script;
+
+/// Result of a run
+#[derive(Debug)]
+pub struct Outcome {
+    /// Number of frames run
+    pub frames: usize,
+    /// Number of instructions executed
+    pub instructions: usize,
+    /// True if the run stopped because the ROM halted
+    pub halted: bool,
+    /// Execution state of the emulator at the end of the run
+    pub status: Status,
+    /// Exception that stopped the run, if any
+    pub exception: Option<Exception>,
+}
+
+/// Runs the emulator frame by frame with the scripted input
+pub struct Runner {
+    oito: OitoCore,
+    script: Script,
+    /// Instructions run each frame
+    ticks: usize,
+}
+
+impl Runner {
+    pub fn new(rom: Rom, script: Script, ticks: usize) -> Self {
+        Self::with_config(Config::default(), rom, script, ticks)
+    }
+
+    /// Returns a runner of an emulator customized with the specified settings
+    pub fn with_config(config: Config, rom: Rom, script: Script, ticks: usize) -> Self {
+        let mut oito = OitoCore::with_config(config);
+        oito.load(rom);
+        Self {
+            oito,
+            script,
+            ticks,
+        }
+    }
+
+    /// Returns the emulator being run
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Runs up to the specified number of frames, calling the callback after each one.
+    /// The run stops before if an exception is raised, if a limit of the configuration is reached or
+    /// if the ROM halts: it jumps to itself, exits, or waits for a key when no more keys will be pressed.
+    pub fn run(&mut self, frames: usize, mut on_frame: impl FnMut(usize, &OitoCore)) -> Outcome {
+        let mut outcome = Outcome {
+            frames: 0,
+            instructions: 0,
+            halted: false,
+            status: Status::Running,
+            exception: None,
+        };
+        'run: for frame in 0..frames {
+            outcome.frames += 1;
+            self.script.apply(frame, &mut self.oito);
+            for _ in 0..self.ticks {
+                if let Err(exception) = self.oito.tick() {
+                    outcome.exception = Some(exception);
+                    break 'run;
+                }
+                outcome.status = self.oito.status();
+                if let Status::LimitReached(_) = outcome.status {
+                    break 'run;
+                }
+                outcome.instructions += 1;
+                outcome.halted = match outcome.status {
+                    Status::Halted(_) => true,
+                    Status::WaitingKey => self.script.finished(frame),
+                    _ => false,
+                };
+                if outcome.halted {
+                    break 'run;
+                }
+            }
+            self.oito.frame_tick();
+            on_frame(frame, &self.oito);
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::{
+        config::{Config, Limits},
+        core::{Halt, Limit, Status},
+        exception::Exception,
+    };
+
+    use crate::script::Script;
+
+    use super::Runner;
+
+    #[test]
+    fn halt() {
+        let rom = include_bytes!("../../rom_loader/test/test_opcode.ch8");
+        let mut runner = Runner::new(rom, Script::default(), 10);
+        let mut dumped = 0;
+
+        let outcome = runner.run(1000, |_, _| dumped += 1);
+        assert!(outcome.halted);
+        assert_eq!(Status::Halted(Halt::JumpToSelf), outcome.status);
+        assert!(outcome.exception.is_none());
+        assert!(outcome.frames < 1000);
+        assert_eq!(outcome.frames - 1, dumped);
+        assert!(runner.oito().frame_rows().iter().any(|row| *row != 0));
+    }
+
+    #[test]
+    fn frames() {
+        // Loop drawing and clearing the screen
+        let rom = [0xD0, 0x05, 0x00, 0xE0, 0x12, 0x00];
+        let mut runner = Runner::new(&rom, Script::default(), 10);
+
+        let outcome = runner.run(5, |_, _| ());
+        assert_eq!(5, outcome.frames);
+        assert_eq!(50, outcome.instructions);
+        assert!(!outcome.halted);
+    }
+
+    #[test]
+    fn wait_key() {
+        // Waits for a key and then jumps to itself
+        let rom = [0xF0, 0x0A, 0x12, 0x02];
+        let script = Script::new(vec!["3:7".parse().unwrap()]);
+        let mut runner = Runner::new(&rom, script, 10);
+
+        let outcome = runner.run(10, |_, _| ());
+        assert!(outcome.halted);
+        assert_eq!(Status::Halted(Halt::JumpToSelf), outcome.status);
+        assert_eq!(4, outcome.frames);
+        assert_eq!(7, runner.oito().registers().v[0]);
+
+        // Without more key presses, waiting for a key halts the ROM
+        let mut runner = Runner::new(&rom, Script::default(), 10);
+        let outcome = runner.run(10, |_, _| ());
+        assert!(outcome.halted);
+        assert_eq!(Status::WaitingKey, outcome.status);
+        assert_eq!(1, outcome.frames);
+    }
+
+    #[test]
+    fn limits() {
+        let rom = [0xD0, 0x05, 0x00, 0xE0, 0x12, 0x00];
+        let config = Config {
+            limits: Limits {
+                instructions: Some(25),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut runner = Runner::with_config(config, &rom, Script::default(), 10);
+
+        let outcome = runner.run(10, |_, _| ());
+        assert!(!outcome.halted);
+        assert_eq!(Status::LimitReached(Limit::Instructions), outcome.status);
+        assert_eq!(25, outcome.instructions);
+        assert_eq!(3, outcome.frames);
+    }
+
+    #[test]
+    fn exception() {
+        let rom = [0x00, 0xEE];
+        let mut runner = Runner::new(&rom, Script::default(), 10);
+
+        let outcome = runner.run(10, |_, _| ());
+        assert_eq!(Some(Exception::StackUnderflow), outcome.exception);
+        assert_eq!(1, outcome.frames);
+    }
+}
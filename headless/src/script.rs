@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use oito_core::{core::OitoCore, key::Key};
+
+/// Press of a key scheduled at a frame
+#[derive(Debug, PartialEq, Eq)]
+pub struct KeyPress {
+    /// Frame when the key is pressed
+    pub frame: usize,
+    /// Key pressed
+    pub key: Key,
+    /// Number of frames the key is held
+    pub duration: usize,
+}
+
+impl FromStr for KeyPress {
+    type Err = String;
+
+    /// Parses a key press as FRAME:KEY[:DURATION], by default keys are held one frame
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid key press: {s}");
+        let mut parts = s.split(':');
+        let frame = parts
+            .next()
+            .and_then(|frame| frame.parse().ok())
+            .ok_or_else(invalid)?;
+        let key = parts
+            .next()
+            .filter(|key| key.len() == 1)
+            .and_then(|key| u8::from_str_radix(key, 16).ok())
+            .and_then(|key| Key::try_from(key).ok())
+            .ok_or_else(invalid)?;
+        let duration = match parts.next() {
+            Some(duration) => duration
+                .parse()
+                .ok()
+                .filter(|duration| *duration > 0)
+                .ok_or_else(invalid)?,
+            None => 1,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            frame,
+            key,
+            duration,
+        })
+    }
+}
+
+/// Scripted input of a run
+#[derive(Debug, Default)]
+pub struct Script {
+    presses: Vec<KeyPress>,
+}
+
+impl Script {
+    pub fn new(presses: Vec<KeyPress>) -> Self {
+        Self { presses }
+    }
+
+    /// Releases and presses the keys scheduled for the start of the frame
+    pub fn apply(&self, frame: usize, oito: &mut OitoCore) {
+        for press in &self.presses {
+            if press.frame + press.duration == frame {
+                oito.key_release(press.key);
+            }
+        }
+        for press in &self.presses {
+            if press.frame == frame {
+                oito.key_press(press.key);
+            }
+        }
+    }
+
+    /// Returns true if no key is held or will be pressed from the specified frame on
+    pub fn finished(&self, frame: usize) -> bool {
+        self.presses
+            .iter()
+            .all(|press| press.frame + press.duration <= frame)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::key::Key;
+
+    use super::{KeyPress, Script};
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Ok(KeyPress {
+                frame: 60,
+                key: Key::A,
+                duration: 1
+            }),
+            "60:A".parse()
+        );
+        assert_eq!(
+            Ok(KeyPress {
+                frame: 0,
+                key: Key::Five,
+                duration: 10
+            }),
+            "0:5:10".parse()
+        );
+        assert!("60".parse::<KeyPress>().is_err());
+        assert!("60:G".parse::<KeyPress>().is_err());
+        assert!("60:10".parse::<KeyPress>().is_err());
+        assert!("60:1:0".parse::<KeyPress>().is_err());
+        assert!("60:1:2:3".parse::<KeyPress>().is_err());
+    }
+
+    #[test]
+    fn finished() {
+        let script = Script::new(vec!["10:1:5".parse().unwrap(), "2:F".parse().unwrap()]);
+
+        assert!(!script.finished(0));
+        assert!(!script.finished(14));
+        assert!(script.finished(15));
+        assert!(Script::default().finished(0));
+    }
+}
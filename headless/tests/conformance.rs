@@ -0,0 +1,216 @@
+//! Conformance suite: runs the test ROMs under every platform profile with every backend and compares their last
+//! frame with the golden images of the profile. The ROMs are taken from the `ROM_DIRS`, each one can have a `.keys`
+//! file next to it with a key press per line, in the same FRAME:KEY[:DURATION] format of the runner, or a
+//! `.<profile>.keys` one for a single profile. Run the suite with `OITO_BLESS` set to write the golden images with
+//! the interpreter instead of comparing them.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use headless::{
+    runner::Runner,
+    script::{KeyPress, Script},
+};
+use oito_core::{
+    config::{Backend, Config, Quirks},
+    fontset::Font,
+    Row, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+/// Directories holding the test ROMs
+const ROM_DIRS: [&str; 2] = ["../rom_loader/test", "tests/roms"];
+/// Directory holding the golden images, a directory per profile with one PBM per ROM with the same name
+const GOLDEN_DIR: &str = "tests/golden";
+/// Community test ROMs expected in `tests/roms`, fetched with `tests/roms/fetch.sh` except for BC_test
+const COMMUNITY_ROMS: [&str; 5] = ["3-corax+", "4-flags", "5-quirks", "6-keypad", "BC_test"];
+/// Maximum number of frames to run each ROM, the run stops before if the ROM halts
+const FRAMES: usize = 600;
+/// Number of instructions run each frame
+const TICKS_PER_FRAME: usize = 10;
+/// Backends to check, the golden images are written with the first one
+const BACKENDS: [Backend; 2] = [Backend::Interpreter, Backend::Recompiler];
+
+/// Platform profiles the ROMs run under, with the settings of the interpreters they stand for
+const PROFILES: [Profile; 3] = [
+    Profile {
+        name: "vip",
+        font: Font::Vip,
+        quirks: Quirks { wait_release: true },
+    },
+    Profile {
+        name: "schip",
+        font: Font::Schip,
+        quirks: Quirks {
+            wait_release: false,
+        },
+    },
+    Profile {
+        name: "modern",
+        font: Font::Modern,
+        quirks: Quirks {
+            wait_release: false,
+        },
+    },
+];
+
+/// Settings of the emulator matching a platform
+struct Profile {
+    /// Name of the profile, also the directory of its golden images
+    name: &'static str,
+    /// Font of the hexadecimal digits
+    font: Font,
+    /// Behaviours of the interpreter
+    quirks: Quirks,
+}
+
+#[test]
+fn conformance() {
+    let bless = env::var_os("OITO_BLESS").is_some();
+    let roms = roms();
+    assert!(!roms.is_empty(), "no test ROMs found");
+
+    let mut failures = Vec::new();
+    for path in roms {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let rom = fs::read(&path).unwrap();
+
+        for profile in &PROFILES {
+            let golden = Path::new(GOLDEN_DIR)
+                .join(profile.name)
+                .join(format!("{name}.pbm"));
+            for backend in BACKENDS {
+                let config = Config {
+                    backend,
+                    font: profile.font.clone(),
+                    quirks: profile.quirks,
+                    ..Default::default()
+                };
+                let script = script(&path, profile);
+                let mut runner = Runner::with_config(config, &rom, script, TICKS_PER_FRAME);
+                let outcome = runner.run(FRAMES, |_, _| ());
+                let run = format!("{name} ({}, {backend:?})", profile.name);
+                if let Some(exception) = outcome.exception {
+                    failures.push(format!("{run}: {exception}"));
+                    continue;
+                }
+
+                let frame = runner.oito().frame_rows();
+                if bless && backend == BACKENDS[0] {
+                    fs::create_dir_all(golden.parent().unwrap()).unwrap();
+                    fs::write(&golden, pbm(frame)).unwrap();
+                }
+                match fs::read_to_string(&golden) {
+                    Ok(expected) if expected == pbm(frame) => {}
+                    Ok(expected) => failures.push(format!(
+                        "{run} doesn't match its golden image:\n{}",
+                        diff(&expected, frame)
+                    )),
+                    Err(_) => failures.push(format!(
+                        "{run} has no golden image, run with OITO_BLESS=1 to write it"
+                    )),
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Checks the golden images of the opcode test against its documented result: every one of the eighteen
+/// opcode checks shows the OK sprite of the ROM, and none the NO one. Doesn't depend on the emulator,
+/// so it catches golden images blessed from a broken build.
+#[test]
+fn test_opcode_golden() {
+    let rom = fs::read("../rom_loader/test/test_opcode.ch8").unwrap();
+    // the sprites are stored right after the initial jump
+    let (ok, no) = (&rom[2..6], &rom[6..10]);
+    let columns = [10, 32, 52];
+    let rows = [1, 6, 11, 16, 21, 26];
+
+    for profile in &PROFILES {
+        let golden = Path::new(GOLDEN_DIR)
+            .join(profile.name)
+            .join("test_opcode.pbm");
+        let frame = rows_of(&fs::read_to_string(&golden).unwrap());
+        for y in rows {
+            for x in columns {
+                let sprite: Vec<u8> = (y..y + ok.len())
+                    .map(|y| (frame[y] >> (SCREEN_WIDTH - 8 - x)) as u8)
+                    .collect();
+                assert_eq!(ok, sprite, "{} check at ({x}, {y}) isn't OK", profile.name);
+                assert_ne!(no, sprite);
+            }
+        }
+    }
+}
+
+/// Checks that the community test ROMs are present, run it after fetching them
+#[test]
+#[ignore = "needs the community test ROMs, fetched with tests/roms/fetch.sh"]
+fn community_roms() {
+    let missing: Vec<&str> = COMMUNITY_ROMS
+        .into_iter()
+        .filter(|name| !Path::new("tests/roms").join(format!("{name}.ch8")).exists())
+        .collect();
+    assert!(missing.is_empty(), "missing test ROMs: {missing:?}");
+}
+
+/// Returns the paths of the test ROMs, sorted
+fn roms() -> Vec<PathBuf> {
+    let mut roms: Vec<PathBuf> = ROM_DIRS
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ch8"))
+        .collect();
+    roms.sort();
+    roms
+}
+
+/// Reads the key presses of the ROM under the profile, if it has any
+fn script(rom: &Path, profile: &Profile) -> Script {
+    let presses = fs::read_to_string(rom.with_extension(format!("{}.keys", profile.name)))
+        .or_else(|_| fs::read_to_string(rom.with_extension("keys")))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.parse::<KeyPress>().unwrap())
+        .collect();
+    Script::new(presses)
+}
+
+/// Encodes the frame as a plain PBM, one line per row
+fn pbm(frame: &[Row]) -> String {
+    let mut pbm = format!("P1\n{SCREEN_WIDTH} {SCREEN_HEIGHT}\n");
+    for row in frame {
+        pbm.push_str(&format!("{row:0width$b}\n", width = SCREEN_WIDTH));
+    }
+    pbm
+}
+
+/// Decodes the rows of a plain PBM written by `pbm`
+fn rows_of(pbm: &str) -> Vec<Row> {
+    pbm.lines()
+        .skip(2)
+        .map(|row| Row::from_str_radix(row, 2).unwrap())
+        .collect()
+}
+
+/// Draws the rows of the expected frame next to the actual ones, marking the ones that differ
+fn diff(expected: &str, frame: &[Row]) -> String {
+    let draw = |row: &str| row.replace('1', "#").replace('0', ".");
+    let actual = pbm(frame);
+    expected
+        .lines()
+        .zip(actual.lines())
+        .skip(2)
+        .map(|(expected, actual)| {
+            let mark = if expected == actual { ' ' } else { '!' };
+            format!("{mark} {} {}", draw(expected), draw(actual))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
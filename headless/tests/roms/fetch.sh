@@ -0,0 +1,10 @@
+#!/bin/sh
+# Downloads into this directory the community test ROMs of the conformance suite.
+# BC_test isn't published in a stable location, drop BC_test.ch8 here by hand.
+set -e
+cd "$(dirname "$0")"
+
+SUITE=https://github.com/Timendus/chip8-test-suite/raw/main/bin
+for rom in 3-corax+ 4-flags 5-quirks 6-keypad; do
+    curl -fsSL -o "$rom.ch8" "$SUITE/$rom.ch8"
+done
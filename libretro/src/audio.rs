@@ -0,0 +1,80 @@
+use oito_frontend::{io::AudioSink, runner::FRAME_RATE};
+
+/// Samples per second of the audio
+pub const SAMPLE_RATE: u32 = 44100;
+/// Stereo samples generated in each frame
+pub const SAMPLES_PER_FRAME: usize = (SAMPLE_RATE / FRAME_RATE) as usize;
+/// Frequency of the beep
+const TONE: u32 = 440;
+/// Volume of the beep
+const AMPLITUDE: i16 = 0x1000;
+
+/// Generator of the beep as a square wave, one frame of interleaved stereo samples at a time
+#[derive(Debug)]
+pub struct SquareWave {
+    /// True while the beep is playing
+    on: bool,
+    /// Position in the wave of the next sample, kept between frames to avoid clicks
+    phase: u32,
+    /// Samples of the last frame, left and right interleaved
+    samples: Vec<i16>,
+}
+
+impl SquareWave {
+    /// Returns the samples of the last frame, left and right interleaved
+    pub fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+}
+
+impl Default for SquareWave {
+    fn default() -> Self {
+        Self {
+            on: false,
+            phase: 0,
+            samples: vec![0; SAMPLES_PER_FRAME * 2],
+        }
+    }
+}
+
+impl AudioSink for SquareWave {
+    /// Generates the samples of the frame, silent if the beep is off
+    fn beep(&mut self, on: bool) {
+        self.on = on;
+        for frame in self.samples.chunks_exact_mut(2) {
+            let sample = if !on {
+                0
+            } else if self.phase < SAMPLE_RATE / 2 {
+                AMPLITUDE
+            } else {
+                -AMPLITUDE
+            };
+            frame.fill(sample);
+            self.phase = (self.phase + TONE) % SAMPLE_RATE;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_frontend::io::AudioSink;
+
+    use super::{SquareWave, AMPLITUDE, SAMPLES_PER_FRAME};
+
+    #[test]
+    fn beep() {
+        let mut wave = SquareWave::default();
+        wave.beep(false);
+        assert_eq!(SAMPLES_PER_FRAME * 2, wave.samples().len());
+        assert!(wave.samples().iter().all(|sample| *sample == 0));
+
+        wave.beep(true);
+        let samples = wave.samples();
+        assert_eq!(samples[0], samples[1]);
+        assert_eq!(AMPLITUDE, samples[0]);
+        assert!(samples.contains(&-AMPLITUDE));
+        // The 735 samples of a frame hold 14.7 half periods of 440 Hz at 44.1 kHz
+        let changes = samples.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        assert!((14..=15).contains(&changes));
+    }
+}
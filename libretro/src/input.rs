@@ -0,0 +1,96 @@
+use std::ffi::c_uint;
+
+use oito_core::{
+    action::{Action, ActionMap},
+    key::Key,
+};
+
+use crate::sys::*;
+
+/// Buttons of the RetroPad
+pub const BUTTONS: [c_uint; 16] = [
+    RETRO_DEVICE_ID_JOYPAD_B,
+    RETRO_DEVICE_ID_JOYPAD_Y,
+    RETRO_DEVICE_ID_JOYPAD_SELECT,
+    RETRO_DEVICE_ID_JOYPAD_START,
+    RETRO_DEVICE_ID_JOYPAD_UP,
+    RETRO_DEVICE_ID_JOYPAD_DOWN,
+    RETRO_DEVICE_ID_JOYPAD_LEFT,
+    RETRO_DEVICE_ID_JOYPAD_RIGHT,
+    RETRO_DEVICE_ID_JOYPAD_A,
+    RETRO_DEVICE_ID_JOYPAD_X,
+    RETRO_DEVICE_ID_JOYPAD_L,
+    RETRO_DEVICE_ID_JOYPAD_R,
+    RETRO_DEVICE_ID_JOYPAD_L2,
+    RETRO_DEVICE_ID_JOYPAD_R2,
+    RETRO_DEVICE_ID_JOYPAD_L3,
+    RETRO_DEVICE_ID_JOYPAD_R3,
+];
+
+/// Returns the key pressed by the button of the RetroPad.
+/// The D-pad and A are the actions of the ROM, and the rest of buttons the keys left by the default actions.
+pub fn key(button: c_uint, actions: &ActionMap) -> Option<Key> {
+    let key = match button {
+        RETRO_DEVICE_ID_JOYPAD_UP => actions.key(Action::Up),
+        RETRO_DEVICE_ID_JOYPAD_DOWN => actions.key(Action::Down),
+        RETRO_DEVICE_ID_JOYPAD_LEFT => actions.key(Action::Left),
+        RETRO_DEVICE_ID_JOYPAD_RIGHT => actions.key(Action::Right),
+        RETRO_DEVICE_ID_JOYPAD_A => actions.key(Action::Fire),
+        RETRO_DEVICE_ID_JOYPAD_B => Key::Zero,
+        RETRO_DEVICE_ID_JOYPAD_Y => Key::One,
+        RETRO_DEVICE_ID_JOYPAD_X => Key::Three,
+        RETRO_DEVICE_ID_JOYPAD_L => Key::Seven,
+        RETRO_DEVICE_ID_JOYPAD_R => Key::Nine,
+        RETRO_DEVICE_ID_JOYPAD_L2 => Key::A,
+        RETRO_DEVICE_ID_JOYPAD_R2 => Key::B,
+        RETRO_DEVICE_ID_JOYPAD_L3 => Key::C,
+        RETRO_DEVICE_ID_JOYPAD_R3 => Key::D,
+        RETRO_DEVICE_ID_JOYPAD_SELECT => Key::E,
+        RETRO_DEVICE_ID_JOYPAD_START => Key::F,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Returns the mask of the keys pressed by the buttons, bit N is the key of value N
+pub fn keys(actions: &ActionMap, pressed: impl Fn(c_uint) -> bool) -> u16 {
+    BUTTONS
+        .into_iter()
+        .filter(|button| pressed(*button))
+        .filter_map(|button| key(button, actions))
+        .fold(0, |mask, key| mask | key.mask())
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::{
+        action::{Action, ActionMap},
+        key::Key,
+    };
+
+    use crate::sys::*;
+
+    use super::{key, keys, BUTTONS};
+
+    #[test]
+    fn every_key() {
+        let actions = ActionMap::default();
+        let mask = keys(&actions, |_| true);
+        assert_eq!(0xFFFF, mask);
+        assert_eq!(None, key(16, &actions));
+        assert_eq!(16, BUTTONS.len());
+    }
+
+    #[test]
+    fn actions() {
+        let mut actions = ActionMap::default();
+        assert_eq!(Some(Key::Two), key(RETRO_DEVICE_ID_JOYPAD_UP, &actions));
+        actions.set(Action::Fire, Key::A);
+        assert_eq!(Some(Key::A), key(RETRO_DEVICE_ID_JOYPAD_A, &actions));
+        assert_eq!(
+            Key::A.mask() | Key::Zero.mask(),
+            keys(&actions, |button| button == RETRO_DEVICE_ID_JOYPAD_A
+                || button == RETRO_DEVICE_ID_JOYPAD_B)
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Libretro core of the OitoCore, exporting the functions of `libretro.h` over a [Runner].
+//! The API is a singleton, so the loaded game and the callbacks of the front-end live in globals.
+
+use std::{
+    ffi::{c_char, c_uint, c_void},
+    ptr, slice,
+    sync::{Mutex, MutexGuard},
+};
+
+use audio::{SquareWave, SAMPLES_PER_FRAME, SAMPLE_RATE};
+use oito_core::{action::ActionMap, config::Config, core::State, key::Key};
+use oito_frontend::runner::{Runner, FRAME_RATE};
+use sys::*;
+use video::Framebuffer;
+
+pub mod audio;
+pub mod input;
+pub mod sys;
+pub mod video;
+
+/// Callbacks registered by the front-end
+#[derive(Clone, Copy)]
+struct Callbacks {
+    environment: Option<RetroEnvironment>,
+    video_refresh: Option<RetroVideoRefresh>,
+    audio_sample_batch: Option<RetroAudioSampleBatch>,
+    input_poll: Option<RetroInputPoll>,
+    input_state: Option<RetroInputState>,
+}
+
+/// Game being run
+struct Core {
+    runner: Runner,
+    video: Framebuffer,
+    audio: SquareWave,
+    /// Keys pressed by the actions of the ROM
+    actions: ActionMap,
+    /// Mask of the keys pressed in the last run
+    keys: u16,
+}
+
+impl Core {
+    /// Presses and releases the keys whose buttons changed since the last run
+    fn input(&mut self, pressed: impl Fn(c_uint) -> bool) {
+        let keys = input::keys(&self.actions, pressed);
+        let oito = self.runner.oito_mut();
+        for key in Key::ALL {
+            match (self.keys & key.mask() != 0, keys & key.mask() != 0) {
+                (false, true) => oito.key_press(key),
+                (true, false) => oito.key_release(key),
+                _ => {}
+            }
+        }
+        self.keys = keys;
+    }
+}
+
+static CALLBACKS: Mutex<Callbacks> = Mutex::new(Callbacks {
+    environment: None,
+    video_refresh: None,
+    audio_sample_batch: None,
+    input_poll: None,
+    input_state: None,
+});
+
+static CORE: Mutex<Option<Core>> = Mutex::new(None);
+
+/// Returns the callbacks of the front-end
+fn callbacks() -> MutexGuard<'static, Callbacks> {
+    CALLBACKS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Returns the game being run, if any
+fn core() -> MutexGuard<'static, Option<Core>> {
+    CORE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[no_mangle]
+pub extern "C" fn retro_api_version() -> c_uint {
+    RETRO_API_VERSION
+}
+
+/// Writes the name and version of the core and the extensions of the ROMs
+///
+/// # Safety
+/// The info must point to a writable `retro_system_info`.
+#[no_mangle]
+pub unsafe extern "C" fn retro_get_system_info(info: *mut RetroSystemInfo) {
+    *info = RetroSystemInfo {
+        library_name: c"Oito".as_ptr(),
+        library_version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char,
+        valid_extensions: c"ch8|c8|rom".as_ptr(),
+        need_fullpath: false,
+        block_extract: false,
+    };
+}
+
+/// Writes the geometry of the screen and the rates of the frames and the audio
+///
+/// # Safety
+/// The info must point to a writable `retro_system_av_info`.
+#[no_mangle]
+pub unsafe extern "C" fn retro_get_system_av_info(info: *mut RetroSystemAvInfo) {
+    let video = Framebuffer::default();
+    let (width, height) = (video.width() as c_uint, video.height() as c_uint);
+    *info = RetroSystemAvInfo {
+        geometry: RetroGameGeometry {
+            base_width: width,
+            base_height: height,
+            max_width: width,
+            max_height: height,
+            aspect_ratio: width as f32 / height as f32,
+        },
+        timing: RetroSystemTiming {
+            fps: FRAME_RATE as f64,
+            sample_rate: SAMPLE_RATE as f64,
+        },
+    };
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_environment(callback: RetroEnvironment) {
+    callbacks().environment = Some(callback);
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_video_refresh(callback: RetroVideoRefresh) {
+    callbacks().video_refresh = Some(callback);
+}
+
+/// The audio is sent in batches, the single samples are never used
+#[no_mangle]
+pub extern "C" fn retro_set_audio_sample(_callback: RetroAudioSample) {}
+
+#[no_mangle]
+pub extern "C" fn retro_set_audio_sample_batch(callback: RetroAudioSampleBatch) {
+    callbacks().audio_sample_batch = Some(callback);
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_input_poll(callback: RetroInputPoll) {
+    callbacks().input_poll = Some(callback);
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_input_state(callback: RetroInputState) {
+    callbacks().input_state = Some(callback);
+}
+
+/// Only the RetroPad is supported
+#[no_mangle]
+pub extern "C" fn retro_set_controller_port_device(_port: c_uint, _device: c_uint) {}
+
+#[no_mangle]
+pub extern "C" fn retro_init() {}
+
+#[no_mangle]
+pub extern "C" fn retro_deinit() {
+    *core() = None;
+}
+
+/// Restarts the game, running it again even if an exception paused it
+#[no_mangle]
+pub extern "C" fn retro_reset() {
+    if let Some(core) = core().as_mut() {
+        core.runner.reset();
+        core.runner.resume();
+        core.keys = 0;
+    }
+}
+
+/// Runs a frame: polls the RetroPad, runs the emulator and sends the image and the audio to the front-end.
+/// An exception pauses the emulator, so the front-end keeps showing the last frame.
+#[no_mangle]
+pub extern "C" fn retro_run() {
+    let callbacks = *callbacks();
+    let mut core = core();
+    let Some(core) = core.as_mut() else {
+        return;
+    };
+
+    if let Some(poll) = callbacks.input_poll {
+        unsafe { poll() };
+    }
+    if let Some(state) = callbacks.input_state {
+        core.input(|button| unsafe { state(0, RETRO_DEVICE_JOYPAD, 0, button) } != 0);
+    }
+    if core.runner.frame(&mut core.video, &mut core.audio).is_err() {
+        core.runner.pause();
+    }
+
+    if let Some(video_refresh) = callbacks.video_refresh {
+        let video = &core.video;
+        unsafe {
+            video_refresh(
+                video.image().as_ptr() as *const c_void,
+                video.width() as c_uint,
+                video.height() as c_uint,
+                video.pitch(),
+            )
+        };
+    }
+    if let Some(audio_sample_batch) = callbacks.audio_sample_batch {
+        unsafe { audio_sample_batch(core.audio.samples().as_ptr(), SAMPLES_PER_FRAME) };
+    }
+}
+
+/// Returns the size of the saved states, zero without a game
+#[no_mangle]
+pub extern "C" fn retro_serialize_size() -> usize {
+    core()
+        .as_ref()
+        .map_or(0, |core| core.runner.oito().save_state().to_bytes().len())
+}
+
+/// Writes the state of the game, returning false if there is no game or it doesn't fit
+///
+/// # Safety
+/// The data must point to `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_serialize(data: *mut c_void, size: usize) -> bool {
+    let core = core();
+    let Some(core) = core.as_ref() else {
+        return false;
+    };
+    let state = core.runner.oito().save_state().to_bytes();
+    if data.is_null() || size < state.len() {
+        return false;
+    }
+    slice::from_raw_parts_mut(data as *mut u8, state.len()).copy_from_slice(&state);
+    true
+}
+
+/// Restores a state written by `retro_serialize`, returning false if there is no game or the state is not valid
+///
+/// # Safety
+/// The data must point to `size` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_unserialize(data: *const c_void, size: usize) -> bool {
+    let mut core = core();
+    let Some(core) = core.as_mut() else {
+        return false;
+    };
+    if data.is_null() {
+        return false;
+    }
+    match State::try_from(slice::from_raw_parts(data as *const u8, size)) {
+        Ok(state) => {
+            core.runner.oito_mut().load_state(&state);
+            core.runner.resume();
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn retro_cheat_reset() {}
+
+#[no_mangle]
+pub extern "C" fn retro_cheat_set(_index: c_uint, _enabled: bool, _code: *const c_char) {}
+
+/// Loads the ROM of the game, returning false if there is none or the front-end doesn't support XRGB8888
+///
+/// # Safety
+/// The game must be null or point to a `retro_game_info` with `size` readable bytes of data.
+#[no_mangle]
+pub unsafe extern "C" fn retro_load_game(game: *const RetroGameInfo) -> bool {
+    let Some(game) = game.as_ref() else {
+        return false;
+    };
+    if game.data.is_null() {
+        return false;
+    }
+    let Some(environment) = callbacks().environment else {
+        return false;
+    };
+    let mut format = RETRO_PIXEL_FORMAT_XRGB8888;
+    if !environment(
+        RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
+        &mut format as *mut c_uint as *mut c_void,
+    ) {
+        return false;
+    }
+
+    let rom = slice::from_raw_parts(game.data as *const u8, game.size);
+    *core() = Some(Core {
+        runner: Runner::new(Config::default(), rom),
+        video: Framebuffer::default(),
+        audio: SquareWave::default(),
+        actions: ActionMap::default(),
+        keys: 0,
+    });
+    true
+}
+
+/// There are no special games
+#[no_mangle]
+pub extern "C" fn retro_load_game_special(
+    _game_type: c_uint,
+    _info: *const RetroGameInfo,
+    _num_info: usize,
+) -> bool {
+    false
+}
+
+#[no_mangle]
+pub extern "C" fn retro_unload_game() {
+    *core() = None;
+}
+
+#[no_mangle]
+pub extern "C" fn retro_get_region() -> c_uint {
+    RETRO_REGION_NTSC
+}
+
+/// The memory is not exposed
+#[no_mangle]
+pub extern "C" fn retro_get_memory_data(_id: c_uint) -> *mut c_void {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub extern "C" fn retro_get_memory_size(_id: c_uint) -> usize {
+    0
+}
@@ -0,0 +1,56 @@
+use oito_core::{
+    core::OitoCore,
+    video::{FrameConverter, Palette, PixelFormat, Upscaler},
+};
+use oito_frontend::io::VideoSink;
+
+/// Image of the last frame in the XRGB8888 format of libretro, white pixels over black
+#[derive(Debug)]
+pub struct Framebuffer {
+    converter: FrameConverter,
+    image: Vec<u8>,
+}
+
+impl Framebuffer {
+    /// Returns the converted image
+    pub fn image(&self) -> &[u8] {
+        &self.image
+    }
+
+    /// Returns the width of the image in pixels
+    pub fn width(&self) -> usize {
+        self.converter.width()
+    }
+
+    /// Returns the height of the image in pixels
+    pub fn height(&self) -> usize {
+        self.converter.height()
+    }
+
+    /// Returns the number of bytes of each row of the image
+    pub fn pitch(&self) -> usize {
+        self.converter.pitch()
+    }
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        let converter = FrameConverter::new(
+            Palette::default(),
+            PixelFormat::Xrgb8888,
+            Upscaler::default(),
+        );
+        Self {
+            image: vec![0; converter.buffer_size()],
+            converter,
+        }
+    }
+}
+
+impl VideoSink for Framebuffer {
+    /// Converts the whole frame, libretro expects a complete image on each run
+    fn draw(&mut self, oito: &mut OitoCore) {
+        oito.take_damage();
+        self.converter.convert(oito.frame_rows(), &mut self.image);
+    }
+}
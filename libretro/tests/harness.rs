@@ -0,0 +1,218 @@
+//! Minimal libretro front-end: loads the built core with `dlopen` and drives it through the API,
+//! checking the video, the audio, the input and the saved states without any external front-end.
+
+use std::{
+    env,
+    ffi::{c_uint, c_void, CStr},
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Mutex,
+    },
+};
+
+use libloading::{Library, Symbol};
+use oito_libretro::sys::*;
+
+/// Draws the digit 7 at (7, 7) and beeps once the key 5 is pressed:
+/// LD V0, 7; LD F, V0; DRW V0, V0, 5; LD V1, 5; SKP V1; JP 0x208; LD V2, 0x3C; LD ST, V2; JP 0x210
+const ROM: [u8; 18] = [
+    0x60, 0x07, 0xF0, 0x29, 0xD0, 0x05, 0x61, 0x05, 0xE1, 0x9E, 0x12, 0x08, 0x62, 0x3C, 0xF2, 0x18,
+    0x12, 0x10,
+];
+
+/// Draws the digit 7 at (7, 7) and raises a stack underflow a few frames later:
+/// LD V0, 7; LD F, V0; DRW V0, V0, 5; ADD V3, 1; SE V3, 0x1E; JP 0x206; RET
+const CRASHING_ROM: [u8; 14] = [
+    0x60, 0x07, 0xF0, 0x29, 0xD0, 0x05, 0x73, 0x01, 0x33, 0x1E, 0x12, 0x06, 0x00, 0xEE,
+];
+
+/// Last image received, with its width, height and pitch
+static FRAME: Mutex<(Vec<u8>, c_uint, c_uint, usize)> = Mutex::new((Vec::new(), 0, 0, 0));
+/// Last samples received
+static AUDIO: Mutex<Vec<i16>> = Mutex::new(Vec::new());
+/// Pixel format requested by the core
+static FORMAT: AtomicU32 = AtomicU32::new(u32::MAX);
+/// True while the A button is held
+static FIRE: AtomicBool = AtomicBool::new(false);
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => {
+            FORMAT.store(*(data as *const c_uint), Ordering::SeqCst);
+            true
+        }
+        _ => false,
+    }
+}
+
+unsafe extern "C" fn video_refresh(
+    data: *const c_void,
+    width: c_uint,
+    height: c_uint,
+    pitch: usize,
+) {
+    let image = std::slice::from_raw_parts(data as *const u8, pitch * height as usize);
+    *FRAME.lock().unwrap() = (image.to_vec(), width, height, pitch);
+}
+
+unsafe extern "C" fn audio_sample(_left: i16, _right: i16) {}
+
+unsafe extern "C" fn audio_sample_batch(data: *const i16, frames: usize) -> usize {
+    *AUDIO.lock().unwrap() = std::slice::from_raw_parts(data, frames * 2).to_vec();
+    frames
+}
+
+unsafe extern "C" fn input_poll() {}
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, _index: c_uint, id: c_uint) -> i16 {
+    let held = port == 0
+        && device == RETRO_DEVICE_JOYPAD
+        && id == RETRO_DEVICE_ID_JOYPAD_A
+        && FIRE.load(Ordering::SeqCst);
+    held as i16
+}
+
+/// Returns the color of the pixel of the last image
+fn pixel(x: usize, y: usize) -> u32 {
+    let frame = FRAME.lock().unwrap();
+    let offset = y * frame.3 + x * 4;
+    u32::from_ne_bytes(frame.0[offset..offset + 4].try_into().unwrap())
+}
+
+fn beeping() -> bool {
+    AUDIO.lock().unwrap().iter().any(|sample| *sample != 0)
+}
+
+#[test]
+fn core() {
+    // The tests are built in target/<profile>/deps, next to the libraries of the crate
+    let exe = env::current_exe().unwrap();
+    let path = exe
+        .parent()
+        .unwrap()
+        .join(libloading::library_filename("oito_libretro"));
+
+    unsafe {
+        let core = Library::new(path).unwrap();
+        macro_rules! api {
+            ($name:ident: $ty:ty) => {
+                let $name: Symbol<$ty> = core.get(stringify!($name).as_bytes()).unwrap();
+            };
+        }
+        api!(retro_api_version: extern "C" fn() -> c_uint);
+        api!(retro_get_system_info: unsafe extern "C" fn(*mut RetroSystemInfo));
+        api!(retro_get_system_av_info: unsafe extern "C" fn(*mut RetroSystemAvInfo));
+        api!(retro_set_environment: extern "C" fn(RetroEnvironment));
+        api!(retro_set_video_refresh: extern "C" fn(RetroVideoRefresh));
+        api!(retro_set_audio_sample: extern "C" fn(RetroAudioSample));
+        api!(retro_set_audio_sample_batch: extern "C" fn(RetroAudioSampleBatch));
+        api!(retro_set_input_poll: extern "C" fn(RetroInputPoll));
+        api!(retro_set_input_state: extern "C" fn(RetroInputState));
+        api!(retro_init: extern "C" fn());
+        api!(retro_deinit: extern "C" fn());
+        api!(retro_load_game: unsafe extern "C" fn(*const RetroGameInfo) -> bool);
+        api!(retro_unload_game: extern "C" fn());
+        api!(retro_run: extern "C" fn());
+        api!(retro_reset: extern "C" fn());
+        api!(retro_serialize_size: extern "C" fn() -> usize);
+        api!(retro_serialize: unsafe extern "C" fn(*mut c_void, usize) -> bool);
+        api!(retro_unserialize: unsafe extern "C" fn(*const c_void, usize) -> bool);
+
+        assert_eq!(RETRO_API_VERSION, retro_api_version());
+        let mut info = MaybeUninit::<RetroSystemInfo>::uninit();
+        retro_get_system_info(info.as_mut_ptr());
+        let info = info.assume_init();
+        assert_eq!(c"Oito", CStr::from_ptr(info.library_name));
+        assert!(!info.need_fullpath);
+
+        retro_set_environment(environment);
+        retro_set_video_refresh(video_refresh);
+        retro_set_audio_sample(audio_sample);
+        retro_set_audio_sample_batch(audio_sample_batch);
+        retro_set_input_poll(input_poll);
+        retro_set_input_state(input_state);
+        retro_init();
+
+        let mut av = MaybeUninit::<RetroSystemAvInfo>::uninit();
+        retro_get_system_av_info(av.as_mut_ptr());
+        let av = av.assume_init();
+        assert_eq!((64, 32), (av.geometry.base_width, av.geometry.base_height));
+        assert_eq!(60.0, av.timing.fps);
+
+        // Nothing is run without a game
+        assert_eq!(0, retro_serialize_size());
+        retro_run();
+        assert!(FRAME.lock().unwrap().0.is_empty());
+
+        let game = RetroGameInfo {
+            path: std::ptr::null(),
+            data: ROM.as_ptr() as *const c_void,
+            size: ROM.len(),
+            meta: std::ptr::null(),
+        };
+        assert!(retro_load_game(&game));
+        assert_eq!(RETRO_PIXEL_FORMAT_XRGB8888, FORMAT.load(Ordering::SeqCst));
+
+        // The digit is drawn while the ROM waits for the key
+        retro_run();
+        {
+            let frame = FRAME.lock().unwrap();
+            assert_eq!((64, 32, 256), (frame.1, frame.2, frame.3));
+        }
+        assert_eq!(0x00FFFFFF, pixel(7, 7));
+        assert_eq!(0x00000000, pixel(6, 7));
+        assert_eq!(735 * 2, AUDIO.lock().unwrap().len());
+        assert!(!beeping());
+
+        // The A button presses the key 5 of the fire action
+        FIRE.store(true, Ordering::SeqCst);
+        retro_run();
+        assert!(beeping());
+        FIRE.store(false, Ordering::SeqCst);
+
+        let size = retro_serialize_size();
+        let mut state = vec![0u8; size];
+        assert!(retro_serialize(state.as_mut_ptr() as *mut c_void, size));
+        assert!(!retro_serialize(
+            state.as_mut_ptr() as *mut c_void,
+            size - 1
+        ));
+        for _ in 0..60 {
+            retro_run();
+        }
+        assert!(!beeping());
+        assert!(retro_unserialize(state.as_ptr() as *const c_void, size));
+        retro_run();
+        assert!(beeping());
+        assert!(!retro_unserialize(
+            state.as_ptr() as *const c_void,
+            size - 1
+        ));
+
+        retro_reset();
+        retro_run();
+        assert!(!beeping());
+        assert_eq!(0x00FFFFFF, pixel(7, 7));
+
+        retro_unload_game();
+        assert_eq!(0, retro_serialize_size());
+
+        // The reset runs again the game paused by an exception
+        let game = RetroGameInfo {
+            data: CRASHING_ROM.as_ptr() as *const c_void,
+            size: CRASHING_ROM.len(),
+            ..game
+        };
+        assert!(retro_load_game(&game));
+        for _ in 0..20 {
+            retro_run();
+        }
+        retro_reset();
+        retro_run();
+        assert_eq!(0x00FFFFFF, pixel(7, 7));
+
+        retro_unload_game();
+        retro_deinit();
+    }
+}
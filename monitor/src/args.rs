@@ -0,0 +1,17 @@
+use structopt::StructOpt;
+
+/// Debug a Chip-8 ROM from the command line
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    /// The ROM file to load
+    pub file: Option<String>,
+    /// File of commands to run before the prompt, one per line
+    #[structopt(long = "script", short = "x")]
+    pub script: Option<String>,
+    /// Runs the script and exits without prompting
+    #[structopt(long = "batch")]
+    pub batch: bool,
+    /// Fx0A waits for the key to be released, like the COSMAC VIP
+    #[structopt(long = "wait-release")]
+    pub wait_release: bool,
+}
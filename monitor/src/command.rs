@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use oito_core::{core::Register, key::Key, Address};
+
+/// Instructions shown by default by the disassembler
+const DISASSEMBLED: usize = 10;
+/// Bytes shown by default by the memory dump
+const DUMPED: usize = 16;
+
+/// Commands of the monitor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Loads the ROM of the path, restarting the emulator
+    Load(String),
+    /// Stops before running the address, or lists the breakpoints
+    Break(Option<Address>),
+    /// Removes the breakpoint of the address
+    Delete(Address),
+    /// Stops after a change of the content of the address, or lists the watchpoints
+    Watch(Option<Address>),
+    /// Removes the watchpoint of the address
+    Unwatch(Address),
+    /// Runs the specified number of instructions, ignoring the breakpoints
+    Step(usize),
+    /// Runs the next instruction, and the whole subroutine if it's a call
+    Next,
+    /// Runs until a breakpoint, a watchpoint, a key wait or the end of the ROM
+    Continue,
+    /// Runs the specified number of frames
+    Frame(usize),
+    /// Shows the registers and timers
+    Regs,
+    /// Shows the content of the memory
+    Mem(Address, usize),
+    /// Shows the instructions starting at the address, or at the program counter
+    Dis(Option<Address>, usize),
+    /// Sets the value of a register or timer
+    Set(Register, u16),
+    /// Shows the frame
+    Screen,
+    /// Presses a key of the keypad
+    Press(Key),
+    /// Releases a key of the keypad
+    Release(Key),
+    /// Runs the commands of the file
+    Source(String),
+    /// Shows the available commands
+    Help,
+    /// Closes the monitor
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    /// Parses a command line, with the numbers in decimal or in hexadecimal with the `0x` prefix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        let arg = |i: usize| args.get(i).copied();
+        let number = |i: usize| arg(i).map(parse_number).transpose();
+        let required = |i: usize| number(i)?.ok_or_else(|| format!("missing argument of {name}"));
+        let address = |i: usize| required(i);
+        let count =
+            |i: usize, default: usize| Ok::<_, String>(number(i)?.map_or(default, usize::from));
+        let path = || {
+            arg(0)
+                .map(str::to_string)
+                .ok_or_else(|| format!("missing path of {name}"))
+        };
+        let key = || {
+            required(0).and_then(|key| {
+                u8::try_from(key)
+                    .map_err(|_| format!("invalid key: {key:#X}"))
+                    .and_then(Key::try_from)
+            })
+        };
+
+        match name {
+            "load" => Ok(Command::Load(path()?)),
+            "break" | "b" => Ok(Command::Break(number(0)?)),
+            "delete" | "d" => Ok(Command::Delete(address(0)?)),
+            "watch" | "w" => Ok(Command::Watch(number(0)?)),
+            "unwatch" => Ok(Command::Unwatch(address(0)?)),
+            "step" | "s" => Ok(Command::Step(count(0, 1)?)),
+            "next" | "n" => Ok(Command::Next),
+            "continue" | "c" => Ok(Command::Continue),
+            "frame" | "f" => Ok(Command::Frame(count(0, 1)?)),
+            "regs" | "r" => Ok(Command::Regs),
+            "mem" | "m" => Ok(Command::Mem(address(0)?, count(1, DUMPED)?)),
+            "dis" => Ok(Command::Dis(number(0)?, count(1, DISASSEMBLED)?)),
+            "set" => {
+                let register = arg(0)
+                    .ok_or("missing register of set")?
+                    .parse::<Register>()?;
+                Ok(Command::Set(register, required(1)?))
+            }
+            "screen" => Ok(Command::Screen),
+            "press" => Ok(Command::Press(key()?)),
+            "release" => Ok(Command::Release(key()?)),
+            "source" => Ok(Command::Source(path()?)),
+            "help" | "h" => Ok(Command::Help),
+            "quit" | "q" => Ok(Command::Quit),
+            _ => Err(format!("unknown command: {name}")),
+        }
+    }
+}
+
+/// Parses a number in decimal, or in hexadecimal if it starts with `0x`
+fn parse_number(s: &str) -> Result<u16, String> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|_| format!("invalid number: {s}"))
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::{core::Register, key::Key};
+
+    use super::{parse_number, Command};
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Command::Break(Some(0x2A4))), "break 0x2A4".parse());
+        assert_eq!(Ok(Command::Break(None)), "b".parse());
+        assert_eq!(Ok(Command::Step(1)), "step".parse());
+        assert_eq!(Ok(Command::Step(5)), "s 5".parse());
+        assert_eq!(Ok(Command::Mem(0x300, 32)), "mem 0x300 32".parse());
+        assert_eq!(Ok(Command::Dis(None, 10)), "dis".parse());
+        assert_eq!(
+            Ok(Command::Set(Register::V(3), 0x10)),
+            "set v3 0x10".parse()
+        );
+        assert_eq!(Ok(Command::Press(Key::A)), "press 0xA".parse());
+        assert_eq!(
+            Ok(Command::Load("pong.ch8".into())),
+            "load pong.ch8".parse()
+        );
+    }
+
+    #[test]
+    fn from_str_errors() {
+        assert!("jump".parse::<Command>().is_err());
+        assert!("mem".parse::<Command>().is_err());
+        assert!("set v3".parse::<Command>().is_err());
+        assert!("set x 1".parse::<Command>().is_err());
+        assert!("press 16".parse::<Command>().is_err());
+        assert!("load".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn number() {
+        assert_eq!(Ok(32), parse_number("32"));
+        assert_eq!(Ok(0x2A4), parse_number("0x2A4"));
+        assert!(parse_number("2A4").is_err());
+    }
+}
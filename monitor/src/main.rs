@@ -0,0 +1,63 @@
+use monitor::session::Monitor;
+use oito_core::config::{Config, Quirks};
+use rustyline::{error::ReadlineError, DefaultEditor};
+use std::{env, error::Error, fs, io::stdout, path::PathBuf};
+use structopt::StructOpt;
+
+mod args;
+
+const PROMPT: &str = "(oito) ";
+const HISTORY: &str = ".oito_history";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = args::Args::from_args();
+
+    let mut monitor = Monitor::new(Config {
+        quirks: Quirks {
+            wait_release: args.wait_release,
+        },
+        ..Default::default()
+    });
+    if let Some(file) = &args.file {
+        monitor.load(&fs::read(file)?);
+    }
+    if let Some(script) = &args.script {
+        if !monitor.source(&fs::read_to_string(script)?, &mut stdout())? || args.batch {
+            return Ok(());
+        }
+    }
+
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY));
+    let mut editor = DefaultEditor::new()?;
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    let mut last = String::new();
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            if last.is_empty() {
+                continue;
+            }
+        } else {
+            editor.add_history_entry(line)?;
+            last = line.to_string();
+        }
+        match monitor.execute(&last, &mut stdout()) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+
+    if let Some(history) = &history {
+        editor.save_history(history)?;
+    }
+    Ok(())
+}
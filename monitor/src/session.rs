@@ -0,0 +1,429 @@
+use std::{fmt::Write as _, fs, io::Write};
+
+use oito_core::{
+    config::Config,
+    core::{OitoCore, Status},
+    instruction::Instruction,
+    Address,
+};
+
+use crate::command::Command;
+
+/// Instructions run per frame
+const TICKS_PER_FRAME: usize = 10;
+/// Maximum number of instructions run by a command before giving the control back
+const MAX_INSTRUCTIONS: usize = 1_000_000;
+
+const HELP: &str = "\
+load PATH           loads the ROM, keeping the breakpoints and watchpoints
+break [ADDR]        stops before running the address, or lists the breakpoints (b)
+delete ADDR         removes a breakpoint (d)
+watch [ADDR]        stops after a change of the address, or lists the watchpoints (w)
+unwatch ADDR        removes a watchpoint
+step [N]            runs N instructions, one by default (s)
+next                runs the next instruction, or the whole subroutine it calls (n)
+continue            runs until a breakpoint, watchpoint, key wait or halt (c)
+frame [N]           runs N frames, one by default (f)
+regs                shows the registers and timers (r)
+mem ADDR [LEN]      shows LEN bytes of memory, 16 by default (m)
+dis [ADDR] [N]      disassembles N instructions, 10 from the PC by default
+set REG VALUE       sets a register: v0 to vf, i, pc, dt or st
+screen              shows the frame
+press KEY           presses a key of the keypad
+release KEY         releases a key of the keypad
+source PATH         runs the commands of the file
+help                shows this help (h)
+quit                closes the monitor (q)
+Numbers are decimal, or hexadecimal with the 0x prefix. An empty line repeats the last command.";
+
+/// Debugging session over an emulator
+pub struct Monitor {
+    oito: OitoCore,
+    /// Settings used to rebuild the emulator when a ROM is loaded
+    config: Config,
+    /// Instructions run since the last frame tick
+    ticks: usize,
+}
+
+impl Monitor {
+    pub fn new(config: Config) -> Self {
+        Self {
+            oito: OitoCore::with_config(config.clone()),
+            config,
+            ticks: 0,
+        }
+    }
+
+    /// Returns the emulator
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Restarts the emulator with the ROM, keeping the breakpoints and watchpoints
+    pub fn load(&mut self, rom: &[u8]) {
+        let mut oito = OitoCore::with_config(self.config.clone());
+        for address in self.oito.breakpoints() {
+            oito.add_breakpoint(address);
+        }
+        for address in self.oito.watchpoints() {
+            oito.add_watchpoint(address);
+        }
+        oito.load(rom);
+        self.oito = oito;
+        self.ticks = 0;
+    }
+
+    /// Runs the command line, writing its output.
+    /// Returns false if the command asks to quit.
+    pub fn execute(&mut self, line: &str, out: &mut impl Write) -> Result<bool, String> {
+        let command: Command = line.parse()?;
+        let output = match command {
+            Command::Quit => return Ok(false),
+            Command::Source(path) => {
+                let script = fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+                return self.source(&script, out);
+            }
+            command => self.run(command)?,
+        };
+        if !output.is_empty() {
+            writeln!(out, "{output}").map_err(|e| e.to_string())?;
+        }
+        Ok(true)
+    }
+
+    /// Runs each line of the script, skipping the empty ones and the comments starting with `#`.
+    /// Stops at the first error. Returns false if the script asks to quit.
+    pub fn source(&mut self, script: &str, out: &mut impl Write) -> Result<bool, String> {
+        for (number, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if !self
+                .execute(line, out)
+                .map_err(|e| format!("line {}: {e}", number + 1))?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Runs the command, returning its output
+    fn run(&mut self, command: Command) -> Result<String, String> {
+        match command {
+            Command::Load(path) => {
+                let rom = fs::read(&path).map_err(|e| format!("{path}: {e}"))?;
+                self.load(&rom);
+                Ok(format!("loaded {} bytes from {path}", rom.len()))
+            }
+            Command::Break(Some(address)) => {
+                self.oito.add_breakpoint(address);
+                Ok(format!("breakpoint at {address:#05X}"))
+            }
+            Command::Break(None) => Ok(list(&self.oito.breakpoints())),
+            Command::Delete(address) => match self.oito.remove_breakpoint(address) {
+                true => Ok(String::new()),
+                false => Err(format!("no breakpoint at {address:#05X}")),
+            },
+            Command::Watch(Some(address)) => {
+                self.oito.add_watchpoint(address);
+                Ok(format!("watchpoint at {address:#05X}"))
+            }
+            Command::Watch(None) => Ok(list(&self.oito.watchpoints())),
+            Command::Unwatch(address) => match self.oito.remove_watchpoint(address) {
+                true => Ok(String::new()),
+                false => Err(format!("no watchpoint at {address:#05X}")),
+            },
+            Command::Step(n) => {
+                for _ in 0..n {
+                    self.tick(false)?;
+                    if self.stopped() {
+                        break;
+                    }
+                }
+                Ok(self.location())
+            }
+            Command::Next => {
+                let pc = self.oito.registers().pc;
+                let depth = self.oito.registers().sp;
+                self.tick(false)?;
+                if let (_, Some(Instruction::CALL(_))) = self.instruction(pc)? {
+                    self.run_until(|oito| {
+                        let registers = oito.registers();
+                        registers.pc == pc + 2 && registers.sp == depth
+                    })?;
+                }
+                Ok(self.location())
+            }
+            Command::Continue => {
+                self.tick(false)?;
+                self.run_until(|_| false)?;
+                Ok(self.location())
+            }
+            Command::Frame(n) => {
+                let mut frames = 0;
+                while frames < n {
+                    if self.tick(true)? {
+                        frames += 1;
+                    }
+                    if self.stopped() {
+                        break;
+                    }
+                }
+                Ok(self.location())
+            }
+            Command::Regs => Ok(self.registers()),
+            Command::Mem(address, len) => self.dump(address, len),
+            Command::Dis(address, n) => {
+                let address = address.unwrap_or(self.oito.registers().pc);
+                self.disassemble(address, n)
+            }
+            Command::Set(register, value) => {
+                self.oito.set_register(register, value);
+                Ok(String::new())
+            }
+            Command::Screen => Ok(self.oito.screen()),
+            Command::Press(key) => {
+                self.oito.key_press(key);
+                Ok(String::new())
+            }
+            Command::Release(key) => {
+                self.oito.key_release(key);
+                Ok(String::new())
+            }
+            Command::Help => Ok(HELP.to_string()),
+            Command::Source(_) | Command::Quit => Ok(String::new()),
+        }
+    }
+
+    /// Runs an instruction, stopping at the breakpoints if specified, and the frame tick when it's due.
+    /// Returns true if the instruction ended a frame, the ticks stopping at a breakpoint don't count.
+    fn tick(&mut self, breakpoints: bool) -> Result<bool, String> {
+        let executed = self.oito.instructions();
+        let result = if breakpoints {
+            self.oito.tick()
+        } else {
+            self.oito.step()
+        };
+        result.map_err(|e| e.to_string())?;
+        if self.oito.instructions() == executed {
+            return Ok(false);
+        }
+        self.ticks += 1;
+        if self.ticks < TICKS_PER_FRAME {
+            return Ok(false);
+        }
+        self.oito.frame_tick();
+        self.ticks = 0;
+        Ok(true)
+    }
+
+    /// Runs instructions until the condition is met or the execution stops
+    fn run_until(&mut self, done: impl Fn(&OitoCore) -> bool) -> Result<(), String> {
+        for _ in 0..MAX_INSTRUCTIONS {
+            if self.stopped() || done(&self.oito) {
+                return Ok(());
+            }
+            self.tick(true)?;
+        }
+        Err(format!(
+            "still running after {MAX_INSTRUCTIONS} instructions"
+        ))
+    }
+
+    /// Returns true if the execution was stopped by a debugger stop, a key wait or the end of the ROM
+    fn stopped(&self) -> bool {
+        let status = self.oito.status();
+        status.is_stopped() || matches!(status, Status::Break(_) | Status::WaitingKey)
+    }
+
+    /// Returns the status, if not running, and the instruction at the program counter
+    fn location(&self) -> String {
+        let pc = self.oito.registers().pc;
+        let mut location = match self.oito.status() {
+            Status::Running => String::new(),
+            status => format!("{status}\n"),
+        };
+        location.push_str(&self.disassemble(pc, 1).unwrap_or_default());
+        location
+    }
+
+    /// Returns the opcode at the address and its instruction
+    fn instruction(&self, address: Address) -> Result<(u16, Option<Instruction>), String> {
+        self.oito.instruction_at(address).map_err(|e| e.to_string())
+    }
+
+    fn registers(&self) -> String {
+        let registers = self.oito.registers();
+        let mut text = format!(
+            "PC {:#05X}  I {:#05X}  SP {}  DT {:#04X}  ST {:#04X}",
+            registers.pc, registers.i, registers.sp, registers.dt, registers.st
+        );
+        for (x, v) in registers.v.iter().enumerate() {
+            let separator = if x % 8 == 0 { '\n' } else { ' ' };
+            let _ = write!(text, "{separator}V{x:X} {v:#04X}");
+        }
+        text
+    }
+
+    fn dump(&self, address: Address, len: usize) -> Result<String, String> {
+        let memory = self.oito.memory(address, len).map_err(|e| e.to_string())?;
+        let lines: Vec<String> = memory
+            .chunks(16)
+            .enumerate()
+            .map(|(i, bytes)| {
+                let bytes: Vec<String> = bytes.iter().map(|b| format!("{b:02X}")).collect();
+                format!("{:#05X}: {}", address as usize + i * 16, bytes.join(" "))
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    /// Returns the instructions starting at the address, marking the program counter with `>`
+    /// and the breakpoints with `*`
+    fn disassemble(&self, address: Address, n: usize) -> Result<String, String> {
+        let pc = self.oito.registers().pc;
+        let breakpoints = self.oito.breakpoints();
+        let mut lines = Vec::new();
+        for address in (address..).step_by(2).take(n) {
+            let Ok((opcode, instruction)) = self.instruction(address) else {
+                break;
+            };
+            let pointer = if address == pc { '>' } else { ' ' };
+            let breakpoint = if breakpoints.contains(&address) {
+                '*'
+            } else {
+                ' '
+            };
+            let instruction =
+                instruction.map_or_else(|| "invalid".to_string(), |i| format!("{i:?}"));
+            lines.push(format!(
+                "{pointer}{breakpoint}{address:#05X}: {opcode:04X}  {instruction}"
+            ));
+        }
+        if lines.is_empty() {
+            return Err(format!("invalid address: {address:#05X}"));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Returns the addresses in hexadecimal, one per line
+fn list(addresses: &[Address]) -> String {
+    let addresses: Vec<String> = addresses.iter().map(|a| format!("{a:#05X}")).collect();
+    addresses.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::config::Config;
+
+    use super::Monitor;
+
+    /// Calls the subroutine at 0x206 adding 2 to V0, then adds 1 to V1 and loops
+    const ROM: [u8; 10] = [0x22, 0x06, 0x71, 0x01, 0x12, 0x00, 0x70, 0x02, 0x00, 0xEE];
+
+    fn monitor() -> Monitor {
+        let mut monitor = Monitor::new(Config::default());
+        monitor.load(&ROM);
+        monitor
+    }
+
+    fn run(monitor: &mut Monitor, script: &str) -> String {
+        let mut out = Vec::new();
+        assert!(monitor.source(script, &mut out).unwrap());
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn step() {
+        let mut monitor = monitor();
+        let out = run(&mut monitor, "step\nstep 2");
+
+        assert!(out.contains("> 0x206: 7002"));
+        assert_eq!(0x202, monitor.oito().registers().pc);
+        assert_eq!(0x02, monitor.oito().registers().v[0]);
+    }
+
+    #[test]
+    fn next() {
+        let mut monitor = monitor();
+        run(&mut monitor, "next");
+
+        assert_eq!(0x202, monitor.oito().registers().pc);
+        assert_eq!(0x02, monitor.oito().registers().v[0]);
+    }
+
+    #[test]
+    fn breakpoint() {
+        let mut monitor = monitor();
+        let out = run(&mut monitor, "break 0x204\ncontinue");
+
+        assert!(out.contains("stopped by breakpoint at 0x204"));
+        assert_eq!(0x204, monitor.oito().registers().pc);
+        assert_eq!(0x01, monitor.oito().registers().v[1]);
+        // Continues from the breakpoint until reaching it again
+        run(&mut monitor, "c");
+        assert_eq!(0x02, monitor.oito().registers().v[1]);
+        assert!(run(&mut monitor, "delete 0x204\nbreak").is_empty());
+    }
+
+    #[test]
+    fn watchpoint() {
+        let mut monitor = Monitor::new(Config::default());
+        // Writes V0 at I and loops
+        monitor.load(&[0xF0, 0x55, 0x12, 0x02]);
+        let out = run(&mut monitor, "watch 0x300\nset i 0x300\nset v0 7\ncontinue");
+
+        assert!(out.contains("stopped by watchpoint at 0x300: 0x00 -> 0x07"));
+        assert_eq!(0x202, monitor.oito().registers().pc);
+        assert_eq!(&[0x07], monitor.oito().memory(0x300, 1).unwrap());
+    }
+
+    #[test]
+    fn inspect() {
+        let mut monitor = monitor();
+        let out = run(&mut monitor, "set v3 0x10\nregs\nmem 0x200 4\ndis 0x200 2");
+
+        assert!(out.contains("PC 0x200"));
+        assert!(out.contains("V3 0x10"));
+        assert!(out.contains("0x200: 22 06 71 01"));
+        assert!(out.contains("> 0x200: 2206  CALL(518)"));
+        assert!(out.contains("  0x202: 7101  ADDbr { x: 1, byte: 1 }"));
+    }
+
+    #[test]
+    fn frame() {
+        let mut monitor = monitor();
+        let out = run(&mut monitor, "frame 2\nscreen");
+
+        assert_eq!(20, monitor.oito().instructions());
+        assert!(out.contains("VRAM"));
+    }
+
+    #[test]
+    fn frame_after_break() {
+        let mut monitor = Monitor::new(Config::default());
+        monitor.load(&[0x70, 0x01, 0x12, 0x00]);
+        let out = run(&mut monitor, "break 0x202\ncontinue\ndelete 0x202\nframe 1");
+
+        assert!(out.contains("stopped by breakpoint at 0x202"));
+        assert_eq!(10, monitor.oito().instructions());
+    }
+
+    #[test]
+    fn errors() {
+        let mut monitor = monitor();
+        let mut out = Vec::new();
+
+        assert!(monitor.execute("delete 0x200", &mut out).is_err());
+        assert!(monitor.execute("mem 0xFFF 2", &mut out).is_err());
+        assert_eq!(
+            Err("line 2: unknown command: jump".to_string()),
+            monitor.source("step\njump\nstep", &mut out)
+        );
+        assert_eq!(Ok(false), monitor.source("quit\nstep", &mut out));
+        assert_eq!(0x206, monitor.oito().registers().pc);
+    }
+}
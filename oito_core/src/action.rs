@@ -0,0 +1,201 @@
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{key::Key, ParseError};
+
+/// Name of the profile applied to every ROM before its own one
+#[cfg(feature = "std")]
+const DEFAULT_PROFILE: &str = "default";
+
+/// Semantic actions of the games that front-ends can bind to their own controls
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Fire,
+}
+
+impl Action {
+    pub const SIZE: usize = 5;
+    /// Every action
+    pub const ALL: [Action; Action::SIZE] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Fire,
+    ];
+}
+
+impl FromStr for Action {
+    type Err = ParseError;
+
+    /// Parses an action: `up`, `down`, `left`, `right` or `fire`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Action::Up),
+            "down" => Ok(Action::Down),
+            "left" => Ok(Action::Left),
+            "right" => Ok(Action::Right),
+            "fire" => Ok(Action::Fire),
+            _ => Err(parse_error!("invalid action: {s}")),
+        }
+    }
+}
+
+/// Keys of the Chip8 keypad pressed by each action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActionMap {
+    keys: [Key; Action::SIZE],
+}
+
+impl ActionMap {
+    /// Returns the key pressed by the action
+    pub fn key(&self, action: Action) -> Key {
+        self.keys[action as usize]
+    }
+
+    /// Binds the action to the key
+    pub fn set(&mut self, action: Action, key: Key) {
+        self.keys[action as usize] = key;
+    }
+}
+
+impl Default for ActionMap {
+    /// Maps the actions to the usual layout of the keypad: 2, 8, 4 and 6 to move and 5 to fire
+    fn default() -> Self {
+        Self {
+            keys: [Key::Two, Key::Eight, Key::Four, Key::Six, Key::Five],
+        }
+    }
+}
+
+/// Bindings of the actions of each ROM, read from a configuration file like:
+///
+/// ```text
+/// # Applied to every ROM
+/// [default]
+/// fire = 5
+///
+/// # Applied to the ROM named tetris
+/// [tetris]
+/// left = 5
+/// right = 6
+/// up = 4
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActionProfiles {
+    /// Bindings of each profile, only the ones present in the configuration
+    profiles: HashMap<String, Vec<(Action, Key)>>,
+}
+
+#[cfg(feature = "std")]
+impl ActionProfiles {
+    /// Returns the action map of the ROM with the specified name, without extension.
+    /// It starts from the default layout and applies the `default` profile and then the one of the ROM.
+    pub fn map(&self, rom: &str) -> ActionMap {
+        let mut map = ActionMap::default();
+        for profile in [DEFAULT_PROFILE, &rom.to_lowercase()] {
+            for (action, key) in self.profiles.get(profile).into_iter().flatten() {
+                map.set(*action, *key);
+            }
+        }
+        map
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for ActionProfiles {
+    type Err = ParseError;
+
+    /// Parses the profiles, with the ROM names as sections and `action = KEY` lines.
+    /// Empty lines and the ones starting with `#` are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut profiles: HashMap<String, Vec<(Action, Key)>> = HashMap::new();
+        let mut profile = DEFAULT_PROFILE.to_string();
+        for (number, line) in s.lines().enumerate() {
+            let invalid = || parse_error!("invalid action binding at line {}: {line}", number + 1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                profile = name.trim().to_lowercase();
+                continue;
+            }
+            let (action, key) = line.split_once('=').ok_or_else(invalid)?;
+            let action = action.trim().parse().map_err(|_| invalid())?;
+            let key = u8::from_str_radix(key.trim(), 16)
+                .ok()
+                .and_then(|key| Key::try_from(key).ok())
+                .ok_or_else(invalid)?;
+            profiles
+                .entry(profile.clone())
+                .or_default()
+                .push((action, key));
+        }
+        Ok(Self { profiles })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::key::Key;
+
+    #[cfg(feature = "std")]
+    use super::ActionProfiles;
+    use super::{Action, ActionMap};
+
+    #[test]
+    fn action_map() {
+        let mut map = ActionMap::default();
+        assert_eq!(Key::Two, map.key(Action::Up));
+        assert_eq!(Key::Five, map.key(Action::Fire));
+
+        map.set(Action::Fire, Key::A);
+        assert_eq!(Key::A, map.key(Action::Fire));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn profiles() {
+        let profiles: ActionProfiles = "
+            # Common bindings
+            fire = 5
+
+            [default]
+            up = 3
+
+            [Tetris]
+            left = 5
+            right = 6
+            fire = 4
+        "
+        .parse()
+        .unwrap();
+
+        let map = profiles.map("pong");
+        assert_eq!(Key::Three, map.key(Action::Up));
+        assert_eq!(Key::Four, map.key(Action::Left));
+        assert_eq!(Key::Five, map.key(Action::Fire));
+
+        let map = profiles.map("TETRIS");
+        assert_eq!(Key::Three, map.key(Action::Up));
+        assert_eq!(Key::Five, map.key(Action::Left));
+        assert_eq!(Key::Six, map.key(Action::Right));
+        assert_eq!(Key::Four, map.key(Action::Fire));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn profiles_from_str() {
+        assert!("[pong]\njump = 5".parse::<ActionProfiles>().is_err());
+        assert!("fire = 10".parse::<ActionProfiles>().is_err());
+        assert!("fire".parse::<ActionProfiles>().is_err());
+        assert_eq!(Ok(ActionProfiles::default()), "".parse());
+    }
+}
@@ -0,0 +1,208 @@
+use core::str::FromStr;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+#[cfg(feature = "alloc")]
+use crate::fontset::FONTS_END;
+use crate::{fontset::Font, Address, ParseError};
+
+/// Strategies available to run the instructions of the loaded ROM
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Fetches, decodes and executes every instruction on each tick
+    #[default]
+    Interpreter,
+    /// Compiles straight-line runs of instructions into cached basic blocks of closures,
+    /// skipping the fetch, decode and dispatch of the instructions already compiled.
+    /// [run](crate::core::OitoCore::run) executes the whole blocks at once.
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    Recompiler,
+}
+
+/// Settings used to build a customized [OitoCore](crate::core::OitoCore)
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Backend in charge of running the instructions
+    pub backend: Backend,
+    /// Limits of the execution, none by default
+    pub limits: Limits,
+    /// Collects diagnostics of the suspicious behaviour of the ROM, like reads of uninitialized
+    /// memory or writes over its own code, without stopping it. Disabled by default.
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub sanitize: bool,
+    /// Memory regions protected against the writes of the ROM, none by default.
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub protected: Vec<Protection>,
+    /// Sprites of the hexadecimal digits, the modern font by default
+    pub font: Font,
+    /// Behaviours of the original interpreters to emulate, none by default
+    pub quirks: Quirks,
+    /// Seed of the random numbers of RND, to make the runs reproducible. Unseeded by default,
+    /// taking the numbers from the entropy of the system with the `std` feature and seeding with zero without it.
+    pub seed: Option<u64>,
+}
+
+/// Behaviours of the original interpreters differing from the modern ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quirks {
+    /// Fx0A waits for a key to be pressed and released, like the COSMAC VIP,
+    /// instead of returning the first key pressed
+    pub wait_release: bool,
+}
+
+/// Execution limits, the emulator stops running when any of them is reached.
+/// Useful to run untrusted or scripted ROMs without looping forever.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum number of instructions to run
+    pub instructions: Option<u64>,
+    /// Maximum time to run since the first tick. Not available in `wasm32` targets.
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub time: Option<Duration>,
+}
+
+/// Memory region protected against the writes of the ROM and what to do with them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Protection {
+    /// Protected memory
+    pub region: Region,
+    /// Action taken when the ROM writes into the region
+    pub action: WriteAction,
+}
+
+impl FromStr for Protection {
+    type Err = ParseError;
+
+    /// Parses a region with an optional action, like `font`, `rom:ignore` or `0x300-0x3FF:log`.
+    /// The ranges can't end before they start.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (region, action) = match s.split_once(':') {
+            Some((region, action)) => (region, Some(action)),
+            None => (s, None),
+        };
+        let invalid = || parse_error!("invalid protection: {s}");
+        let address = |address: &str| {
+            let address = address
+                .strip_prefix("0x")
+                .or_else(|| address.strip_prefix("0X"))
+                .unwrap_or(address);
+            Address::from_str_radix(address, 16).map_err(|_| invalid())
+        };
+        let region = match region {
+            "font" => Region::Font,
+            "rom" => Region::Rom,
+            range => match range.split_once('-') {
+                Some((start, end)) => match (address(start)?, address(end)?) {
+                    (start, end) if start <= end => Region::Range(start, end),
+                    _ => return Err(invalid()),
+                },
+                None => return Err(invalid()),
+            },
+        };
+        let action = match action {
+            None | Some("raise") => WriteAction::Raise,
+            Some("log") => WriteAction::Log,
+            Some("ignore") => WriteAction::Ignore,
+            Some(_) => return Err(invalid()),
+        };
+        Ok(Self { region, action })
+    }
+}
+
+/// Regions of the memory that can be protected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    /// Sprites of the small and big fonts, from `0x000` to `0x0EF`
+    Font,
+    /// Whole image of the loaded ROM, for read-only cartridges
+    Rom,
+    /// Addresses from the first to the second one, both included
+    Range(Address, Address),
+}
+
+impl Region {
+    /// Returns true if the address is part of the region, given the memory where the ROM is loaded
+    #[cfg(feature = "alloc")]
+    pub(crate) fn contains(&self, address: Address, rom: &Range<Address>) -> bool {
+        match self {
+            Region::Font => address < FONTS_END,
+            Region::Rom => rom.contains(&address),
+            Region::Range(start, end) => (*start..=*end).contains(&address),
+        }
+    }
+}
+
+/// Actions to take with the writes into protected memory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteAction {
+    /// Raises an exception stopping the execution
+    #[default]
+    Raise,
+    /// Logs a warning and discards the write
+    Log,
+    /// Discards the write silently
+    Ignore,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Protection, Region, WriteAction};
+    #[cfg(not(feature = "alloc"))]
+    use crate::ParseError;
+
+    #[test]
+    fn protection_from_str() {
+        let protection = |region, action| Protection { region, action };
+        assert_eq!(
+            Ok(protection(Region::Font, WriteAction::Raise)),
+            "font".parse()
+        );
+        assert_eq!(
+            Ok(protection(Region::Rom, WriteAction::Ignore)),
+            "rom:ignore".parse()
+        );
+        assert_eq!(
+            Ok(protection(Region::Range(0x300, 0x3FF), WriteAction::Log)),
+            "0x300-3FF:log".parse()
+        );
+        assert_eq!(
+            Ok(protection(Region::Range(0x300, 0x300), WriteAction::Raise)),
+            "0X300-0x300".parse()
+        );
+        assert!("font:skip".parse::<Protection>().is_err());
+        assert!("0x300".parse::<Protection>().is_err());
+        assert!("0x300-0xZZZ".parse::<Protection>().is_err());
+        assert!("0x3FF-0x300".parse::<Protection>().is_err());
+        assert!("0x0x300-0x3FF".parse::<Protection>().is_err());
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = "font:skip".parse::<Protection>().unwrap_err();
+        #[cfg(feature = "alloc")]
+        assert_eq!("invalid protection: font:skip", error);
+        #[cfg(not(feature = "alloc"))]
+        assert_eq!(ParseError, error);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn region_contains() {
+        let rom = 0x200..0x210;
+        assert!(Region::Font.contains(0x0EF, &rom));
+        assert!(!Region::Font.contains(0x0F0, &rom));
+        assert!(Region::Rom.contains(0x20F, &rom));
+        assert!(!Region::Rom.contains(0x210, &rom));
+        assert!(Region::Range(0x300, 0x3FF).contains(0x3FF, &rom));
+        assert!(!Region::Range(0x300, 0x3FF).contains(0x2FF, &rom));
+    }
+}
@@ -1,20 +1,57 @@
+#[cfg(feature = "alloc")]
+use crate::config::{Backend, Protection, WriteAction};
+use crate::config::{Config, Limits, Quirks};
 use crate::core::operations::{ArithOp, BitOp};
 use crate::cpu::Cpu;
+use crate::damage::Damage;
 use crate::exception::Exception;
+use crate::fontset::Font;
 use crate::instruction::Instruction;
+#[cfg(feature = "alloc")]
+use crate::key::KeyEvent;
 use crate::key::{Key, KeyMap};
-use crate::ram::Ram;
+use crate::ram::{Ram, RAM_SIZE};
 use crate::stack::Stack;
 use crate::timer::Timer;
 use crate::vram::VRam;
-use crate::{fontset, Address, BitMask, Byte, OpCode, Pixel, Rom};
+use crate::{fontset, Address, Byte, OpCode, Pixel, RegIndex, Rom, Row};
 
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::ops::Range;
 use num_traits::Zero;
+#[cfg(feature = "std")]
 use rand::random;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+pub use debug::{Break, Register};
+pub use registers::Registers;
+#[cfg(feature = "alloc")]
+pub use sanitizer::{Diagnostic, Issue};
+pub use state::State;
+pub use status::{Halt, Limit, Status};
+
+use debug::Debugger;
+#[cfg(feature = "alloc")]
+use recompiler::BlockCache;
+#[cfg(feature = "alloc")]
+use sanitizer::Sanitizer;
 
 pub(crate) mod operations;
+pub mod runtime;
 
-const BYTE_SIZE: u8 = 8;
+mod debug;
+#[cfg(feature = "alloc")]
+mod recompiler;
+mod registers;
+#[cfg(feature = "alloc")]
+mod sanitizer;
+mod state;
+mod status;
 
 /// Core of the emmulator
 #[derive(Debug)]
@@ -33,40 +70,294 @@ pub struct OitoCore {
     st: Timer,
     /// Key character map
     keys: KeyMap,
+    /// Compiled blocks, only present when running with the recompiler backend
+    #[cfg(feature = "alloc")]
+    blocks: Option<BlockCache>,
+    /// Execution state after the last tick
+    status: Status,
+    /// Limits of the execution
+    limits: Limits,
+    /// Number of instructions run
+    executed: u64,
+    /// Moment of the first tick, only tracked with a time limit
+    #[cfg(feature = "std")]
+    started: Option<Instant>,
+    /// Tracker of the ROM bugs, only present when the sanitizer is enabled
+    #[cfg(feature = "alloc")]
+    sanitizer: Option<Box<Sanitizer>>,
+    /// Memory regions protected against the writes of the ROM
+    #[cfg(feature = "alloc")]
+    protected: Vec<Protection>,
+    /// Memory where the ROM is loaded
+    #[cfg(feature = "alloc")]
+    rom: Range<Address>,
+    /// Behaviours of the original interpreters to emulate
+    quirks: Quirks,
+    /// Breakpoints and watchpoints of the debuggers
+    debugger: Debugger,
+    /// Generator of the random numbers, only present when seeded. It's the one behind `StdRng`,
+    /// used directly to save and restore its position.
+    rng: Option<ChaCha12Rng>,
 }
 
 impl OitoCore {
     /// Returns a new instance of the emulator core
     pub fn new() -> Self {
-        let mut oito = Self::default();
-        oito.ram.load(0, &fontset::FONTSET);
+        Self::with_config(Config::default())
+    }
+
+    /// Returns a new instance of the emulator core customized with the specified settings
+    pub fn with_config(config: Config) -> Self {
+        let mut oito = Self {
+            #[cfg(feature = "alloc")]
+            blocks: (config.backend == Backend::Recompiler).then(BlockCache::default),
+            #[cfg(feature = "alloc")]
+            sanitizer: config.sanitize.then(Box::default),
+            #[cfg(feature = "alloc")]
+            protected: config.protected,
+            limits: config.limits,
+            quirks: config.quirks,
+            rng: config.seed.map(ChaCha12Rng::seed_from_u64),
+            ..Default::default()
+        };
+        oito.load_font(&config.font);
         oito
     }
 
-    /// Loads the ROM content into the emmulator to run it
+    /// Loads the small sprites of the font at the start of the memory and the big ones, if any, after them
+    fn load_font(&mut self, font: &Font) {
+        self.ram.load(0, font.small());
+        if let Some(big) = font.big() {
+            self.ram.load(fontset::BIG_FONT_ADDRESS, big);
+        }
+        #[cfg(feature = "alloc")]
+        if let Some(sanitizer) = self.sanitizer.as_mut() {
+            let len = match font.big() {
+                Some(_) => fontset::FONTS_END as usize,
+                None => font.small().len(),
+            };
+            sanitizer.load_font(0, len);
+        }
+    }
+
+    /// Loads the ROM content into the emmulator to run it.
+    /// The execution starts again from the beginning, even if the previous ROM stopped the emulator,
+    /// and the whole screen is marked as changed for the first draw of the ROM.
     pub fn load(&mut self, data: Rom) {
-        self.ram.load(Cpu::STARTING_ADDRESS, data);
+        self.vram.damage_all();
+        self.cpu = Cpu::default();
+        self.stack = Stack::default();
+        self.status = Status::default();
+        self.executed = 0;
+        #[cfg(feature = "std")]
+        {
+            self.started = None;
+        }
+        self.store(Cpu::STARTING_ADDRESS, data);
+        #[cfg(feature = "alloc")]
+        {
+            self.rom = Cpu::STARTING_ADDRESS..Cpu::STARTING_ADDRESS + data.len() as Address;
+            if let Some(sanitizer) = self.sanitizer.as_mut() {
+                sanitizer.load_rom(Cpu::STARTING_ADDRESS, data.len());
+            }
+        }
     }
 
-    /// Performs a cycle of the emulator
+    /// Performs a cycle of the emulator.
+    /// Once the emulator is stopped, by the ROM or by the limits, the ticks do nothing.
+    /// Reaching a breakpoint stops the tick before running the instruction, the next one runs it.
     pub fn tick(&mut self) -> Result<(), Exception> {
-        let opcode = self.fetch(self.cpu.pc())?; // fetch
-        self.cpu.increase(); // advance
-        let instruction = Instruction::try_from(opcode)?; // decode
-        self.execute(instruction)?; // execute
+        self.cycle(true)
+    }
+
+    /// Performs a cycle of the emulator like [tick](Self::tick) but ignoring the breakpoints
+    pub fn step(&mut self) -> Result<(), Exception> {
+        self.cycle(false)
+    }
+
+    /// Performs a cycle of the emulator, stopping at the breakpoints if specified
+    fn cycle(&mut self, breakpoints: bool) -> Result<(), Exception> {
+        if self.status.is_stopped() {
+            return Ok(());
+        }
+        if let Some(limit) = self.reached_limit() {
+            self.status = Status::LimitReached(limit);
+            return Ok(());
+        }
+
+        let pc = self.cpu.pc();
+        let breakpoint = Status::Break(Break::Breakpoint(pc));
+        if breakpoints && self.debugger.breaks_at(pc) && self.status != breakpoint {
+            self.status = breakpoint;
+            return Ok(());
+        }
+
+        #[cfg(feature = "alloc")]
+        self.keys.process(self.executed); // input
+        #[cfg(feature = "alloc")]
+        let compiled = match self.blocks.as_mut() {
+            Some(blocks) => Some(blocks.next(pc, &self.ram)?), // fetch compiled
+            None => None,
+        };
+        #[cfg(not(feature = "alloc"))]
+        let compiled: Option<::core::convert::Infallible> = None; // nothing is compiled
+        let instruction = match &compiled {
+            #[cfg(feature = "alloc")]
+            Some((block, index)) => {
+                self.cpu.increase(); // advance
+                block.instruction(*index)
+            }
+            _ => {
+                let opcode = self.fetch(pc)?; // fetch
+                self.cpu.increase(); // advance
+                Instruction::try_from(opcode)? // decode
+            }
+        };
+        #[cfg(feature = "alloc")]
+        self.sanitize(pc, instruction); // check, only with the sanitizer
+        self.debugger.take_watched();
+        match compiled {
+            #[cfg(feature = "alloc")]
+            Some((block, index)) => block.run_op(index, self)?, // execute compiled
+            _ => self.execute(instruction)?, // execute
+        }
+        self.executed += 1;
+
+        self.status = self.status_after(instruction, pc);
+        if let Some(watched) = self.debugger.take_watched() {
+            self.status = Status::Break(watched);
+        }
         Ok(())
     }
 
-    /// Perfoms a frame-tied tick
+    /// Runs up to the specified number of cycles, like calling [tick](Self::tick) as many times,
+    /// but stopping early once the emulator stops or reaches a breakpoint or a watchpoint.
+    /// With the recompiler backend the compiled blocks fitting in the remaining cycles are run whole.
+    pub fn run(&mut self, cycles: u64) -> Result<(), Exception> {
+        let mut remaining = cycles;
+        while remaining > 0 {
+            #[cfg(feature = "alloc")]
+            if let Some(block) = self.runnable_block(remaining) {
+                block.run(self)?;
+                remaining -= block.len() as u64;
+            } else {
+                self.tick()?;
+                remaining -= 1;
+            }
+            #[cfg(not(feature = "alloc"))]
+            {
+                self.tick()?;
+                remaining -= 1;
+            }
+            if self.status.is_stopped() || matches!(self.status, Status::Break(_)) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the compiled block at the pc if it can be run whole within the cycles.
+    /// Anything a single cycle could stop at or react to inside the block, like the breakpoints,
+    /// watchpoints, sanitizer, limits or queued key events, makes it run one instruction at a time.
+    #[cfg(feature = "alloc")]
+    fn runnable_block(&mut self, cycles: u64) -> Option<alloc::sync::Arc<recompiler::Block>> {
+        if self.status.is_stopped()
+            || self.sanitizer.is_some()
+            || !self.debugger.watchpoints.is_empty()
+            || self.reached_limit().is_some()
+        {
+            return None;
+        }
+        let pc = self.cpu.pc();
+        // the faulty instructions are left to the cycle to report them
+        let block = self.blocks.as_mut()?.block(pc, &self.ram).ok()?;
+        let end = self.executed + block.len() as u64;
+        let fits = block.len() as u64 <= cycles
+            && self.limits.instructions.is_none_or(|limit| end <= limit)
+            && self.keys.next_event().is_none_or(|time| time >= end)
+            && !self.debugger.breakpoints.intersects(pc, block.size());
+        fits.then_some(block)
+    }
+
+    /// Returns the execution state after running the instruction stored at the address
+    fn status_after(&self, instruction: Instruction, address: Address) -> Status {
+        use Instruction::*;
+        match instruction {
+            EXIT => Status::Halted(Halt::Exit),
+            _ if self.cpu.pc() != address => Status::Running,
+            LDkr(_) => Status::WaitingKey,
+            SYS(_) | JP(_) | JPr(_) => Status::Halted(Halt::JumpToSelf),
+            _ => Status::Running,
+        }
+    }
+
+    /// Returns the execution state after the last tick
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the bugs of the ROM detected by the sanitizer, empty if it's not enabled
+    #[cfg(feature = "alloc")]
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        match self.sanitizer.as_ref() {
+            Some(sanitizer) => sanitizer.diagnostics(),
+            None => &[],
+        }
+    }
+
+    /// Returns the bugs of the ROM detected by the sanitizer since the last call
+    #[cfg(feature = "alloc")]
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        match self.sanitizer.as_mut() {
+            Some(sanitizer) => sanitizer.take_diagnostics(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Perfoms a frame-tied tick, decreasing the timers and forgetting the key taps older than a frame
     pub fn frame_tick(&mut self) {
         self.dt.decrease();
         self.st.decrease();
+        self.keys.expire_taps();
     }
 
-    /// Returns the boolean's buffer of the next frame to draw.
+    /// Returns the pixels of the next frame to draw, row by row, unpacked lazily from its rows.
     /// The value of the pixel indicates if the value it's drawn or not.
-    pub fn frame_buffer(&self) -> &[Pixel] {
-        self.vram.buffer()
+    pub fn frame_buffer(&self) -> impl ExactSizeIterator<Item = Pixel> + Clone + '_ {
+        self.vram.pixels()
+    }
+
+    /// Returns the buffer of the next frame to draw packed in rows.
+    /// Each row holds a line of the screen, with the leftmost pixel as the most significant bit.
+    pub fn frame_rows(&self) -> &[Row] {
+        self.vram.rows()
+    }
+
+    /// Returns true if the frame changed since the last time its damage was taken
+    pub fn display_changed(&self) -> bool {
+        !self.vram.damage().is_empty()
+    }
+
+    /// Returns the region of the frame changed since the last call and starts tracking again.
+    /// Front-ends can use it to skip the unchanged frames and redraw only the changed region.
+    pub fn take_damage(&mut self) -> Damage {
+        self.vram.take_damage()
+    }
+
+    /// Returns a snapshot of the current state of the registers and timers
+    pub fn registers(&self) -> Registers {
+        let mut v = [0; 16];
+        for (x, value) in v.iter_mut().enumerate() {
+            *value = self.cpu.v(x as RegIndex).get();
+        }
+        Registers {
+            pc: self.cpu.pc(),
+            i: self.cpu.i(),
+            v,
+            sp: self.stack.depth(),
+            dt: self.dt.get(),
+            st: self.st.get(),
+        }
     }
 
     /// Returns true if the sound timer it's set to not zero and the sound should be played
@@ -84,6 +375,157 @@ impl OitoCore {
         self.keys.release_key(key);
     }
 
+    /// Queues the press or release of a key to apply it before running the instruction of its time.
+    /// Allows to feed the input with the accuracy of the instructions instead of the frames.
+    #[cfg(feature = "alloc")]
+    pub fn key_event(&mut self, event: KeyEvent) {
+        self.keys.queue(event);
+    }
+
+    /// Returns the state of every key as a mask, bit N is set if the key of value N is pressed
+    pub fn keys(&self) -> u16 {
+        self.keys.mask()
+    }
+
+    /// Presses the keys set in the mask and releases the rest
+    pub fn set_keys(&mut self, mask: u16) {
+        self.keys.set_mask(mask);
+    }
+
+    /// Returns the number of instructions run, the time of the key events
+    pub fn instructions(&self) -> u64 {
+        self.executed
+    }
+
+    /// Stops the ticks before running the instruction at the address.
+    /// The addresses out of the memory are ignored.
+    pub fn add_breakpoint(&mut self, address: Address) {
+        self.debugger.breakpoints.insert(address);
+    }
+
+    /// Removes the breakpoint at the address. Returns false if there was none.
+    pub fn remove_breakpoint(&mut self, address: Address) -> bool {
+        self.debugger.breakpoints.remove(address)
+    }
+
+    /// Returns the addresses with breakpoints, sorted
+    #[cfg(feature = "alloc")]
+    pub fn breakpoints(&self) -> Vec<Address> {
+        self.debugger.breakpoints.iter().collect()
+    }
+
+    /// Stops the ticks after running an instruction changing the content of the address.
+    /// The addresses out of the memory are ignored.
+    pub fn add_watchpoint(&mut self, address: Address) {
+        self.debugger.watchpoints.insert(address);
+    }
+
+    /// Removes the watchpoint at the address. Returns false if there was none.
+    pub fn remove_watchpoint(&mut self, address: Address) -> bool {
+        self.debugger.watchpoints.remove(address)
+    }
+
+    /// Returns the watched addresses, sorted
+    #[cfg(feature = "alloc")]
+    pub fn watchpoints(&self) -> Vec<Address> {
+        self.debugger.watchpoints.iter().collect()
+    }
+
+    /// Returns the content of the memory starting at the address
+    pub fn memory(&self, address: Address, len: usize) -> Result<&[Byte], Exception> {
+        self.ram.slice(address, len)
+    }
+
+    /// Writes the content in memory, ignoring the protections
+    pub fn poke(&mut self, address: Address, content: &[Byte]) -> Result<(), Exception> {
+        self.ram.slice(address, content.len())?;
+        self.store(address, content);
+        Ok(())
+    }
+
+    /// Sets the value of the register or timer. The V-Registers and timers keep only the low byte.
+    pub fn set_register(&mut self, register: Register, value: u16) {
+        match register {
+            Register::V(x) => self.cpu.load_to_v(x % 16, value as Byte),
+            Register::I => self.cpu.set_i(value),
+            Register::Pc => self.cpu.point_at(value),
+            Register::Dt => self.dt.set(value as Byte),
+            Register::St => self.st.set(value as Byte),
+        }
+    }
+
+    /// Returns the opcode stored at the address and its instruction, if it's valid
+    pub fn instruction_at(
+        &self,
+        address: Address,
+    ) -> Result<(OpCode, Option<Instruction>), Exception> {
+        let bytes = self.ram.slice(address, 2)?;
+        let opcode = ((bytes[0] as OpCode) << 8) | bytes[1] as OpCode;
+        Ok((opcode, Instruction::try_from(opcode).ok()))
+    }
+
+    /// Returns the current frame drawn as text, with `X` for the lit pixels and `_` for the rest
+    #[cfg(feature = "alloc")]
+    pub fn screen(&self) -> String {
+        format!("{:?}", self.vram)
+    }
+
+    /// Returns a snapshot of the machine: memory, registers, stack, timers, screen and keys
+    pub fn save_state(&self) -> State {
+        let mut v = [0; 16];
+        for (x, value) in v.iter_mut().enumerate() {
+            *value = self.cpu.v(x as RegIndex).get();
+        }
+        let mut stack = [0; 16];
+        stack[..self.stack.depth()].copy_from_slice(self.stack.addresses());
+        State {
+            ram: self.ram.slice(0, RAM_SIZE).unwrap().try_into().unwrap(),
+            v,
+            i: self.cpu.i(),
+            pc: self.cpu.pc(),
+            stack,
+            depth: self.stack.depth(),
+            dt: self.dt.get(),
+            st: self.st.get(),
+            rows: self.vram.rows().try_into().unwrap(),
+            keys: self.keys.mask(),
+            latches: self.keys.latches(),
+            executed: self.executed,
+            rng: self
+                .rng
+                .as_ref()
+                .map(|rng| (rng.get_seed(), rng.get_word_pos())),
+        }
+    }
+
+    /// Restores the machine to the snapshot, resuming the execution from it.
+    /// The configuration, breakpoints and watchpoints are kept.
+    pub fn load_state(&mut self, state: &State) {
+        self.ram.load(0, &state.ram);
+        #[cfg(feature = "alloc")]
+        if let Some(blocks) = self.blocks.as_mut() {
+            blocks.invalidate(0, RAM_SIZE);
+        }
+        for (x, value) in state.v.iter().enumerate() {
+            self.cpu.load_to_v(x as RegIndex, *value);
+        }
+        self.cpu.set_i(state.i);
+        self.cpu.point_at(state.pc);
+        self.stack.restore(state.stack());
+        self.dt.set(state.dt);
+        self.st.set(state.st);
+        self.vram.restore(&state.rows);
+        self.keys.set_mask(state.keys);
+        self.keys.restore_latches(state.latches);
+        self.executed = state.executed;
+        if let Some((seed, position)) = state.rng {
+            let mut rng = ChaCha12Rng::from_seed(seed);
+            rng.set_word_pos(position);
+            self.rng = Some(rng);
+        }
+        self.status = Status::Running;
+    }
+
     /// Reads from memory the next instruction and points to the next one
     fn fetch(&mut self, address: Address) -> Result<OpCode, Exception> {
         let big_byte = self.ram.read(address)? as u16;
@@ -91,6 +533,124 @@ impl OitoCore {
         Ok((big_byte << 8) | small_byte)
     }
 
+    /// Loads into Vx the key awaited by Fx0A. Returns false if it's still waiting.
+    /// With the release quirk the key is returned once released, otherwise once pressed.
+    pub(crate) fn wait_key(&mut self, x: RegIndex) -> bool {
+        let key = if self.quirks.wait_release {
+            self.keys.take_key_released()
+        } else {
+            self.keys.take_key_pressed()
+        };
+        match key {
+            Some(key) => {
+                self.cpu.load_to_v(x, key as Byte);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the first limit of the execution reached, if any
+    fn reached_limit(&mut self) -> Option<Limit> {
+        if let Some(instructions) = self.limits.instructions {
+            if self.executed >= instructions {
+                return Some(Limit::Instructions);
+            }
+        }
+        #[cfg(feature = "std")]
+        if let Some(time) = self.limits.time {
+            let started = *self.started.get_or_insert_with(Instant::now);
+            if started.elapsed() >= time {
+                return Some(Limit::Time);
+            }
+        }
+        None
+    }
+
+    /// Writes the content of an instruction in memory applying the protection of the regions, if any
+    fn write(&mut self, address: Address, content: &[Byte]) -> Result<(), Exception> {
+        #[cfg(feature = "alloc")]
+        if !self.protected.is_empty() {
+            return self.write_protected(address, content);
+        }
+        self.store(address, content);
+        Ok(())
+    }
+
+    /// Writes the content of an instruction in memory skipping or rejecting the protected addresses.
+    /// Writes raising an exception are discarded completely, the rest only skip the protected addresses.
+    #[cfg(feature = "alloc")]
+    fn write_protected(&mut self, address: Address, content: &[Byte]) -> Result<(), Exception> {
+        let targets = || (address..).zip(content.iter().copied());
+        if let Some((target, _)) =
+            targets().find(|(target, _)| self.protection(*target) == Some(WriteAction::Raise))
+        {
+            return Err(Exception::ProtectedWrite(target));
+        }
+        for (target, byte) in targets() {
+            match self.protection(target) {
+                None => self.store(target, &[byte]),
+                Some(WriteAction::Log) => log::warn!(
+                    "write of {byte:#04X} into protected memory at {target:#05X} discarded"
+                ),
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the action to take with the writes into the address, if protected
+    #[cfg(feature = "alloc")]
+    fn protection(&self, address: Address) -> Option<WriteAction> {
+        self.protected
+            .iter()
+            .find(|protection| protection.region.contains(address, &self.rom))
+            .map(|protection| protection.action)
+    }
+
+    /// Writes the content in memory discarding any compiled code overwritten by it
+    fn store(&mut self, address: Address, content: &[Byte]) {
+        if self.debugger.watches(address, content.len()) {
+            for (target, new) in (address..).zip(content.iter().copied()) {
+                let old = self.ram.read(target).unwrap_or_default();
+                self.debugger.change(target, old, new);
+            }
+        }
+        self.ram.load(address, content);
+        #[cfg(feature = "alloc")]
+        if let Some(blocks) = self.blocks.as_mut() {
+            blocks.invalidate(address, content.len());
+        }
+    }
+
+    /// Checks the instruction at the pc with the sanitizer, if enabled, before its execution
+    #[cfg(feature = "alloc")]
+    fn sanitize(&mut self, pc: Address, instruction: Instruction) {
+        let Some(sanitizer) = self.sanitizer.as_mut() else {
+            return;
+        };
+        let i = self.cpu.i();
+        let range = |len: Address| i..i.saturating_add(len);
+        sanitizer.execute(pc);
+
+        use Instruction::*;
+        match instruction {
+            RET if self.stack.depth() == 0 => sanitizer.report(pc, Issue::EmptyStackReturn),
+            SYS(address) | JP(address) | CALL(address) => sanitizer.jump(pc, address),
+            JPr(address) => sanitizer.jump(pc, self.cpu.v(0).get() as Address + address),
+            ADDrr { x: 0xF, .. }
+            | SUB { x: 0xF, .. }
+            | SUBN { x: 0xF, .. }
+            | SHR(0xF)
+            | SHL(0xF) => sanitizer.report(pc, Issue::FlagOperand),
+            DRW { n, .. } => sanitizer.draw(pc, range(n as Address)),
+            LDrm(_) => sanitizer.write(pc, range(3)),
+            LDvm(x) => sanitizer.write(pc, range(x as Address + 1)),
+            LDmv(x) => sanitizer.read(pc, range(x as Address + 1)),
+            _ => {}
+        }
+    }
+
     /// Executes the provided instruction
     fn execute(&mut self, instruction: Instruction) -> Result<(), Exception> {
         use Instruction::*;
@@ -100,6 +660,7 @@ impl OitoCore {
                 let address = self.stack.pop()?;
                 self.cpu.point_at(address);
             }
+            EXIT => self.cpu.decrease(), // stay at the exit instruction
             SYS(address) => {
                 self.cpu.point_at(address);
             }
@@ -143,41 +704,25 @@ impl OitoCore {
             }
             LDi(address) => self.cpu.set_i(address),
             JPr(address) => self.cpu.point_at(self.cpu.v(0).get() as Address + address),
-            RND { x, byte } => self.cpu.load_to_v(x, byte & random::<Byte>()),
-            DRW { x, y, n } => {
-                let x = self.cpu.v(x).get();
-                let y = self.cpu.v(y).get();
-
-                let mut swapped = false;
-                for i in 0..n {
-                    let address = self.cpu.i() + i as Address;
-                    let pixels = self.ram.read(address)?;
-                    for j in 0..BYTE_SIZE {
-                        if (pixels & (Byte::MOST_SIGNIFICANT_BIT >> j)) != 0 {
-                            let x = (x + j) as usize;
-                            let y = (y + i) as usize;
-                            swapped |= self.vram.get(x, y);
-                            self.vram.paint(x, y);
-                        }
-                    }
-                }
-                self.cpu.set_flag(swapped as Byte);
+            RND { x, byte } => {
+                let random = self.random();
+                self.cpu.load_to_v(x, byte & random)
             }
+            DRW { x, y, n } => self.draw(x, y, n)?,
             SKP(x) => {
-                if self.keys[self.cpu.v(x).get()] {
+                if self.keys.take_key(self.cpu.v(x).get()) {
                     self.cpu.increase();
                 }
             }
             SKNP(x) => {
-                if !self.keys[self.cpu.v(x).get()] {
+                if !self.keys.take_key(self.cpu.v(x).get()) {
                     self.cpu.increase();
                 }
             }
             LDdr(x) => self.cpu.load_to_v(x, self.dt.get()),
             LDkr(x) => {
-                match self.keys.get_key_pressed() {
-                    Some(k) => self.cpu.load_to_v(x, k as Byte),
-                    None => self.cpu.decrease(), // simulate loop pointing to the same instruction
+                if !self.wait_key(x) {
+                    self.cpu.decrease(); // simulate loop pointing to the same instruction
                 }
             }
             LDrd(x) => self.dt.set(self.cpu.v(x).get()),
@@ -191,32 +736,69 @@ impl OitoCore {
                 let sprite_address = fontset::location(character);
                 self.cpu.set_i(sprite_address);
             }
-            LDrm(x) => {
-                let binary = self.cpu.v(x).get();
-                let (h, t, u) = (binary / 100, (binary % 100) / 10, binary % 10);
-
-                self.ram.load(self.cpu.i(), &[h]);
-                self.ram.load(self.cpu.i() + 1, &[t]);
-                self.ram.load(self.cpu.i() + 2, &[u]);
-            }
-            LDvm(x) => {
-                let start = self.cpu.i();
-                for i in 0..=x {
-                    let address = start + i as Address;
-                    let content = self.cpu.v(i as u8).get();
-                    self.ram.load(address, &[content]);
-                }
-            }
-            LDmv(x) => {
-                let start = self.cpu.i();
-                for i in 0..=x {
-                    let content = self.ram.read(start + i as Address)?;
-                    self.cpu.load_to_v(i, content);
-                }
+            LDhi(x) => {
+                let character = self.cpu.v(x).get();
+                self.cpu.set_i(fontset::big_location(character));
             }
+            LDrm(x) => self.store_bcd(x)?,
+            LDvm(x) => self.store_registers(x)?,
+            LDmv(x) => self.load_registers(x)?,
+        }
+        Ok(())
+    }
+
+    /// Draws the n-byte sprite pointed by I at the position of Vx and Vy, flagging the collisions in VF
+    fn draw(&mut self, x: RegIndex, y: RegIndex, n: Byte) -> Result<(), Exception> {
+        let x = self.cpu.v(x).get() as usize;
+        let y = self.cpu.v(y).get() as usize;
+
+        let mut collision = false;
+        for i in 0..n {
+            let sprite = self.ram.read(self.cpu.i() + i as Address)?;
+            collision |= self.vram.draw(x, y + i as usize, sprite);
         }
+        self.cpu.set_flag(collision as Byte);
         Ok(())
     }
+
+    /// Writes the decimal digits of Vx into the memory pointed by I
+    fn store_bcd(&mut self, x: RegIndex) -> Result<(), Exception> {
+        let binary = self.cpu.v(x).get();
+        let (h, t, u) = (binary / 100, (binary % 100) / 10, binary % 10);
+
+        self.write(self.cpu.i(), &[h, t, u])
+    }
+
+    /// Writes the registers from V0 to Vx into the memory pointed by I
+    fn store_registers(&mut self, x: RegIndex) -> Result<(), Exception> {
+        let mut content = [0; 16];
+        for (i, byte) in content.iter_mut().enumerate().take(x as usize + 1) {
+            *byte = self.cpu.v(i as RegIndex).get();
+        }
+        self.write(self.cpu.i(), &content[..=x as usize])
+    }
+
+    /// Reads the registers from V0 to Vx from the memory pointed by I
+    fn load_registers(&mut self, x: RegIndex) -> Result<(), Exception> {
+        let start = self.cpu.i();
+        for i in 0..=x {
+            let content = self.ram.read(start + i as Address)?;
+            self.cpu.load_to_v(i, content);
+        }
+        Ok(())
+    }
+
+    /// Returns a random byte, from the seeded generator if any
+    fn random(&mut self) -> Byte {
+        #[cfg(feature = "std")]
+        if self.rng.is_none() {
+            return random();
+        }
+        // without the entropy of the system the unseeded runs use the seed zero
+        self.rng
+            .get_or_insert_with(|| ChaCha12Rng::seed_from_u64(0))
+            .gen()
+    }
 }
 
 impl Default for OitoCore {
@@ -231,6 +813,22 @@ impl Default for OitoCore {
             dt: Default::default(),
             st: Default::default(),
             keys: Default::default(),
+            #[cfg(feature = "alloc")]
+            blocks: None,
+            status: Status::default(),
+            limits: Limits::default(),
+            executed: 0,
+            #[cfg(feature = "std")]
+            started: None,
+            #[cfg(feature = "alloc")]
+            sanitizer: None,
+            #[cfg(feature = "alloc")]
+            protected: Vec::new(),
+            #[cfg(feature = "alloc")]
+            rom: 0..0,
+            quirks: Quirks::default(),
+            debugger: Debugger::default(),
+            rng: None,
         }
     }
 }
@@ -239,9 +837,50 @@ impl Default for OitoCore {
 mod instructions_test;
 
 #[cfg(test)]
+#[allow(clippy::needless_update)] // the fields of the settings depend on the features
 mod api_test {
+    use alloc::{vec, vec::Vec};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
     use super::OitoCore;
-    use crate::{cpu::Cpu, key::Key, Address};
+
+    use super::{Break, Halt, Limit, Register, State, Status};
+    #[cfg(feature = "alloc")]
+    use super::{Diagnostic, Issue};
+    #[cfg(feature = "alloc")]
+    use crate::{
+        config::{Backend, Protection, Region, WriteAction},
+        exception::Exception,
+        key::KeyEvent,
+    };
+    use crate::{
+        config::{Config, Limits, Quirks},
+        cpu::Cpu,
+        damage::Damage,
+        fontset::Font,
+        instruction::Instruction,
+        key::Key,
+        Address, SCREEN_HEIGHT, SCREEN_WIDTH,
+    };
+
+    /// Returns a core with the recompiler backend, or with the interpreter if it's not available
+    fn recompiled() -> OitoCore {
+        OitoCore::with_config(Config {
+            #[cfg(feature = "alloc")]
+            backend: Backend::Recompiler,
+            ..Default::default()
+        })
+    }
+
+    fn assert_same_state(interpreted: &OitoCore, recompiled: &OitoCore) {
+        assert_eq!(interpreted.cpu.pc(), recompiled.cpu.pc());
+        assert_eq!(interpreted.cpu.i(), recompiled.cpu.i());
+        for x in 0..16 {
+            assert_eq!(interpreted.cpu.v(x), recompiled.cpu.v(x));
+        }
+        assert_eq!(interpreted.frame_rows(), recompiled.frame_rows());
+    }
 
     #[test]
     fn new() {
@@ -250,16 +889,47 @@ mod api_test {
         assert_eq!(0xF0, oito.ram.read(0x0).unwrap());
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn with_config() {
+        assert!(OitoCore::new().blocks.is_none());
+
+        let oito = recompiled();
+        assert!(oito.blocks.is_some());
+        assert_eq!(0xF0, oito.ram.read(0x0).unwrap());
+    }
+
+    #[test]
+    fn font() {
+        let oito = OitoCore::with_config(Config {
+            font: Font::Vip,
+            ..Default::default()
+        });
+        assert_eq!(0x60, oito.ram.read(0x5).unwrap());
+        assert_eq!(0x00, oito.ram.read(0x50).unwrap());
+
+        let mut oito = OitoCore::with_config(Config {
+            font: Font::Schip,
+            ..Default::default()
+        });
+        assert_eq!(0x20, oito.ram.read(0x5).unwrap());
+        assert_eq!(0x18, oito.ram.read(0x5A).unwrap());
+        oito.load(&[0x60, 0x01, 0xF0, 0x30]);
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        assert_eq!(0x5A, oito.registers().i);
+    }
+
     #[test]
     fn load() {
         let mut oito = OitoCore::new();
         let data = [0x30, 0x25, 0x31, 0x27, 0x0E, 0x00];
 
         oito.load(&data);
-        for i in 0..data.len() {
+        for (i, byte) in data.iter().enumerate() {
             assert_eq!(
                 oito.ram.read(Cpu::STARTING_ADDRESS + i as Address).unwrap(),
-                data[i]
+                *byte
             );
         }
     }
@@ -276,6 +946,352 @@ mod api_test {
         assert_eq!(*oito.cpu.v(3), 0xA2);
     }
 
+    #[test]
+    fn tick_recompiled() {
+        let program = [
+            0x60, 0x05, // V0 = 0x05
+            0x61, 0x01, // V1 = 0x01
+            0xF0, 0x29, // I = sprite[V0]
+            0xD1, 0x15, // draw *I at V1, V1
+            0x80, 0x14, // V0 += V1
+            0x30, 0x0A, // skip if V0 == 0x0A
+            0x12, 0x04, // goto 0x204
+            0x00, 0xE0, // clear
+            0x12, 0x10, // goto 0x210
+        ];
+        let mut interpreted = OitoCore::new();
+        let mut recompiled = recompiled();
+        interpreted.load(&program);
+        recompiled.load(&program);
+
+        for _ in 0..30 {
+            interpreted.tick().unwrap();
+            recompiled.tick().unwrap();
+            assert_same_state(&interpreted, &recompiled);
+        }
+    }
+
+    #[test]
+    fn tick_recompiled_self_modifying() {
+        let program = [
+            0xA2, 0x08, // I = 0x208
+            0x60, 0x63, // V0 = 0x63
+            0xF0, 0x55, // store V0 at I, turning the next instruction into 0x6300
+            0x12, 0x08, // goto 0x208
+            0x61, 0x01, // V1 = 0x01, overwritten into V3 = 0x01
+            0x12, 0x0A, // goto 0x20A
+        ];
+        let mut interpreted = OitoCore::new();
+        let mut recompiled = recompiled();
+        interpreted.load(&program);
+        recompiled.load(&program);
+        // compile the block before the code is modified
+        recompiled.tick().unwrap();
+        recompiled.cpu.point_at(Cpu::STARTING_ADDRESS + 8);
+        recompiled.tick().unwrap();
+        recompiled.cpu.point_at(Cpu::STARTING_ADDRESS);
+        recompiled.cpu.load_to_v(1, 0);
+
+        for _ in 0..6 {
+            interpreted.tick().unwrap();
+            recompiled.tick().unwrap();
+        }
+        assert_same_state(&interpreted, &recompiled);
+        assert_eq!(*recompiled.cpu.v(3), 0x01);
+        assert_eq!(*recompiled.cpu.v(1), 0x00);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn run_recompiled() {
+        let rom = include_bytes!("../../rom_loader/test/test_opcode.ch8");
+        let config = || Config {
+            seed: Some(0x0170),
+            ..Default::default()
+        };
+        let mut interpreted = OitoCore::with_config(config());
+        let mut recompiled = OitoCore::with_config(Config {
+            backend: Backend::Recompiler,
+            ..config()
+        });
+        interpreted.load(rom);
+        recompiled.load(rom);
+
+        for _ in 0..100 {
+            for _ in 0..10 {
+                interpreted.tick().unwrap();
+            }
+            recompiled.run(10).unwrap();
+            interpreted.frame_tick();
+            recompiled.frame_tick();
+            assert_eq!(interpreted.save_state(), recompiled.save_state());
+            assert_eq!(interpreted.status(), recompiled.status());
+        }
+        assert_eq!(Status::Halted(Halt::JumpToSelf), recompiled.status());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn run_stops() {
+        let program = [0x70, 0x01, 0x70, 0x01, 0x70, 0x01, 0x12, 0x00];
+        let mut oito = recompiled();
+        oito.load(&program);
+        oito.add_breakpoint(0x204);
+
+        oito.run(10).unwrap();
+        assert_eq!(Status::Break(Break::Breakpoint(0x204)), oito.status());
+        assert_eq!(2, oito.instructions());
+        // The next run resumes
+        oito.run(2).unwrap();
+        assert_eq!(0x03, oito.registers().v[0]);
+        assert_eq!(4, oito.instructions());
+
+        let mut oito = OitoCore::with_config(Config {
+            backend: Backend::Recompiler,
+            limits: Limits {
+                instructions: Some(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        oito.load(&program);
+        oito.run(10).unwrap();
+        assert_eq!(Status::LimitReached(Limit::Instructions), oito.status());
+        assert_eq!(5, oito.instructions());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn run_key_event() {
+        let mut oito = recompiled();
+        // Counts in V1 the loops run with the key of V0 pressed
+        oito.load(&[0xE0, 0xA1, 0x71, 0x01, 0x12, 0x00]);
+        oito.key_event(KeyEvent::release(Key::Zero, 6));
+        oito.key_event(KeyEvent::press(Key::Zero, 2));
+
+        oito.run(12).unwrap();
+        assert_eq!(12, oito.instructions());
+        assert_eq!(0x02, oito.registers().v[1]);
+    }
+
+    #[test]
+    fn status_jump_to_self() {
+        let mut oito = OitoCore::new();
+        oito.load(&[0x60, 0x01, 0x12, 0x02]);
+
+        oito.tick().unwrap();
+        assert_eq!(Status::Running, oito.status());
+        oito.tick().unwrap();
+        assert_eq!(Status::Halted(Halt::JumpToSelf), oito.status());
+        assert!(oito.status().is_stopped());
+    }
+
+    #[test]
+    fn status_exit() {
+        let mut oito = recompiled();
+        oito.load(&[0x00, 0xFD, 0x60, 0x01]);
+
+        oito.tick().unwrap();
+        assert_eq!(Status::Halted(Halt::Exit), oito.status());
+        // Stopped emulators don't run anything else
+        oito.tick().unwrap();
+        assert_eq!(0x200, oito.registers().pc);
+        assert_eq!(0x00, oito.registers().v[0]);
+    }
+
+    #[test]
+    fn load_after_halt() {
+        let mut oito = recompiled();
+        oito.load(&[0x60, 0x01, 0x12, 0x02]);
+        oito.run(3).unwrap();
+        assert_eq!(Status::Halted(Halt::JumpToSelf), oito.status());
+
+        oito.load(&[0x61, 0x02, 0x00, 0xFD]);
+        assert_eq!(Status::Running, oito.status());
+        assert_eq!(0, oito.instructions());
+        oito.tick().unwrap();
+        assert_eq!(0x202, oito.registers().pc);
+        assert_eq!(0x02, oito.registers().v[1]);
+        oito.tick().unwrap();
+        assert_eq!(Status::Halted(Halt::Exit), oito.status());
+
+        let mut oito = OitoCore::with_config(Config {
+            limits: Limits {
+                instructions: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        oito.load(&[0x70, 0x01, 0x12, 0x00]);
+        oito.run(2).unwrap();
+        assert_eq!(Status::LimitReached(Limit::Instructions), oito.status());
+
+        oito.load(&[0x70, 0x01, 0x12, 0x00]);
+        oito.tick().unwrap();
+        assert_eq!(Status::Running, oito.status());
+        assert_eq!(0x01, oito.registers().v[0]);
+    }
+
+    #[test]
+    fn status_waiting_key() {
+        let mut oito = OitoCore::new();
+        oito.load(&[0xF0, 0x0A, 0x60, 0x01]);
+
+        oito.tick().unwrap();
+        assert_eq!(Status::WaitingKey, oito.status());
+        assert!(!oito.status().is_stopped());
+
+        oito.key_press(Key::Five);
+        oito.tick().unwrap();
+        assert_eq!(Status::Running, oito.status());
+    }
+
+    #[test]
+    fn limits() {
+        let mut oito = OitoCore::with_config(Config {
+            limits: Limits {
+                instructions: Some(3),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        oito.load(&[0x70, 0x01, 0x12, 0x00]);
+
+        for _ in 0..5 {
+            oito.tick().unwrap();
+        }
+        assert_eq!(Status::LimitReached(Limit::Instructions), oito.status());
+        assert_eq!(0x02, oito.registers().v[0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn limit_time() {
+        let mut oito = OitoCore::with_config(Config {
+            limits: Limits {
+                time: Some(Duration::ZERO),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        oito.load(&[0x70, 0x01, 0x12, 0x00]);
+
+        oito.tick().unwrap();
+        assert_eq!(Status::LimitReached(Limit::Time), oito.status());
+        assert_eq!(0x00, oito.registers().v[0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sanitizer() {
+        let program = [
+            0xA1, 0x00, // I = 0x100
+            0xF0, 0x55, // store V0 at I, in the reserved memory
+            0xA2, 0x00, // I = 0x200
+            0xF0, 0x55, // store V0 at I, over the first instruction
+            0x8F, 0x04, // VF += V0, overwritten by the carry
+            0xA3, 0x00, // I = 0x300
+            0xF0, 0x65, // load V0 from I, never written
+            0xD0, 0x01, // draw *I at V0, V0
+            0x12, 0x13, // goto 0x213
+        ];
+        let mut oito = OitoCore::with_config(Config {
+            sanitize: true,
+            ..Default::default()
+        });
+        oito.load(&program);
+        for _ in 0..9 {
+            oito.tick().unwrap();
+        }
+
+        let diagnostic = |pc, issue| Diagnostic { pc, issue };
+        assert_eq!(
+            &[
+                diagnostic(0x202, Issue::ReservedWrite(0x100)),
+                diagnostic(0x206, Issue::CodeOverwrite(0x200)),
+                diagnostic(0x208, Issue::FlagOperand),
+                diagnostic(0x20C, Issue::UninitializedRead(0x300)),
+                diagnostic(0x20E, Issue::UninitializedRead(0x300)),
+                diagnostic(0x20E, Issue::SpriteOutOfRom(0x300)),
+                diagnostic(0x210, Issue::OddJump(0x213)),
+            ],
+            oito.diagnostics()
+        );
+        assert_eq!(7, oito.take_diagnostics().len());
+
+        oito.tick().unwrap();
+        assert_eq!(
+            vec![
+                diagnostic(0x213, Issue::UninitializedRead(0x213)),
+                diagnostic(0x213, Issue::UninitializedRead(0x214)),
+            ],
+            oito.take_diagnostics()
+        );
+
+        // The execution continues until an exception is raised
+        let mut oito = OitoCore::with_config(Config {
+            sanitize: true,
+            ..Default::default()
+        });
+        oito.load(&[0x00, 0xEE]);
+        assert!(oito.tick().is_err());
+        assert_eq!(
+            &[diagnostic(0x200, Issue::EmptyStackReturn)],
+            oito.diagnostics()
+        );
+
+        // Disabled by default
+        let mut oito = OitoCore::new();
+        oito.load(&program);
+        for _ in 0..9 {
+            oito.tick().unwrap();
+        }
+        assert!(oito.diagnostics().is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn protected() {
+        let program = [
+            0x60, 0x2A, // V0 = 0x2A
+            0xA0, 0x00, // I = 0x000
+            0xF0, 0x55, // store V0 at I, into the font
+            0xA2, 0x02, // I = 0x202
+            0xF0, 0x55, // store V0 at I, into the ROM
+            0xA3, 0x00, // I = 0x300
+            0xF0, 0x55, // store V0 at I
+        ];
+        let config = |action| Config {
+            protected: vec![
+                Protection {
+                    region: Region::Font,
+                    action,
+                },
+                Protection {
+                    region: Region::Rom,
+                    action: WriteAction::Ignore,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut oito = OitoCore::with_config(config(WriteAction::Log));
+        oito.load(&program);
+        for _ in 0..7 {
+            oito.tick().unwrap();
+        }
+        assert_eq!(0xF0, oito.ram.read(0x000).unwrap());
+        assert_eq!(0xA0, oito.ram.read(0x202).unwrap());
+        assert_eq!(0x2A, oito.ram.read(0x300).unwrap());
+
+        let mut oito = OitoCore::with_config(config(WriteAction::Raise));
+        oito.load(&program);
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        assert_eq!(Err(Exception::ProtectedWrite(0x000)), oito.tick());
+        assert_eq!(0xF0, oito.ram.read(0x000).unwrap());
+    }
+
     #[test]
     fn frame_tick() {
         let mut oito = OitoCore::default();
@@ -292,27 +1308,313 @@ mod api_test {
         let mut oito = OitoCore::default();
         oito.vram.paint(1, 1);
 
-        let buffer = oito.frame_buffer();
+        let buffer: Vec<bool> = oito.frame_buffer().collect();
+        assert_eq!(SCREEN_WIDTH * SCREEN_HEIGHT, buffer.len());
         assert!(!buffer[0]);
         assert!(buffer[65]);
     }
 
+    #[test]
+    fn frame_rows() {
+        let mut oito = OitoCore::default();
+        oito.vram.paint(1, 1);
+
+        let rows = oito.frame_rows();
+        assert_eq!(0, rows[0]);
+        assert_eq!(0x4000_0000_0000_0000, rows[1]);
+    }
+
+    #[test]
+    fn take_damage() {
+        let mut oito = OitoCore::new();
+        assert!(!oito.display_changed());
+        oito.load(&[0xD0, 0x05]); // draw sprite '0' at (V0, V0)
+        assert_eq!(Damage::FULL, oito.take_damage());
+
+        oito.tick().unwrap();
+        assert!(oito.display_changed());
+        let damage = oito.take_damage();
+        assert_eq!(vec![0, 1, 2, 3, 4], damage.rows().collect::<Vec<_>>());
+        assert!(!oito.display_changed());
+    }
+
+    #[test]
+    fn registers() {
+        let mut oito = OitoCore::new();
+        oito.load(&[0x63, 0x2A, 0xA1, 0x23, 0xF3, 0x15, 0x24, 0x00]);
+        for _ in 0..4 {
+            oito.tick().unwrap();
+        }
+
+        let registers = oito.registers();
+        assert_eq!(0x400, registers.pc);
+        assert_eq!(0x123, registers.i);
+        assert_eq!(0x2A, registers.v[3]);
+        assert_eq!(1, registers.sp);
+        assert_eq!(0x2A, registers.dt);
+        assert_eq!(0x00, registers.st);
+    }
+
     #[test]
     fn press_key() {
         let mut oito = OitoCore::default();
 
         oito.key_press(Key::Five);
-        assert_eq!(oito.keys.get_key_pressed().unwrap(), 5);
+        assert_eq!(0x0020, oito.keys());
     }
 
     #[test]
     fn release_key() {
         let mut oito = OitoCore::default();
         oito.key_press(Key::Five);
-        assert_eq!(oito.keys.get_key_pressed().unwrap(), 5);
+        assert_eq!(0x0020, oito.keys());
 
         oito.key_release(Key::Five);
-        assert!(oito.keys.get_key_pressed().is_none());
+        assert_eq!(0x0000, oito.keys());
+    }
+
+    #[test]
+    fn set_keys() {
+        let mut oito = OitoCore::default();
+        oito.key_press(Key::Five);
+
+        oito.set_keys(0x8001);
+        assert_eq!(0x8001, oito.keys());
+        assert!(oito.keys[0x0] && oito.keys[0xF] && !oito.keys[0x5]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn key_event() {
+        let mut oito = OitoCore::new();
+        // Counts in V1 the loops run with the key of V0 pressed
+        oito.load(&[0xE0, 0xA1, 0x71, 0x01, 0x12, 0x00]);
+        oito.key_event(KeyEvent::release(Key::Zero, 6));
+        oito.key_event(KeyEvent::press(Key::Zero, 2));
+
+        for _ in 0..3 {
+            oito.tick().unwrap();
+        }
+        assert_eq!(3, oito.instructions());
+        assert_eq!(0x00, oito.registers().v[1]);
+        for _ in 0..9 {
+            oito.tick().unwrap();
+        }
+        assert_eq!(0x02, oito.registers().v[1]);
+        assert_eq!(0x0000, oito.keys());
+    }
+
+    #[test]
+    fn key_tap() {
+        let mut oito = OitoCore::new();
+        // Skips the clear of V0 if the key of V0 is pressed
+        oito.load(&[0x60, 0x07, 0xE0, 0x9E, 0x60, 0x00, 0x12, 0x06]);
+        oito.tick().unwrap();
+        // Tap shorter than an instruction
+        oito.key_press(Key::Seven);
+        oito.key_release(Key::Seven);
+
+        for _ in 0..3 {
+            oito.tick().unwrap();
+        }
+        assert_eq!(0x07, oito.registers().v[0]);
+    }
+
+    #[test]
+    fn wait_release() {
+        let mut oito = OitoCore::with_config(Config {
+            quirks: Quirks { wait_release: true },
+            ..Default::default()
+        });
+        oito.load(&[0xF0, 0x0A, 0x12, 0x02]);
+
+        oito.key_press(Key::Three);
+        oito.tick().unwrap();
+        assert_eq!(Status::WaitingKey, oito.status());
+        oito.key_release(Key::Three);
+        oito.tick().unwrap();
+        assert_eq!(0x3, oito.registers().v[0]);
+        assert_eq!(0x202, oito.registers().pc);
+    }
+
+    #[test]
+    fn breakpoint() {
+        let mut oito = OitoCore::new();
+        oito.load(&[0x70, 0x01, 0x70, 0x01, 0x12, 0x00]);
+        oito.add_breakpoint(0x202);
+
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        assert_eq!(Status::Break(Break::Breakpoint(0x202)), oito.status());
+        assert_eq!(1, oito.instructions());
+        // The next tick resumes
+        oito.tick().unwrap();
+        assert_eq!(0x02, oito.registers().v[0]);
+        // The steps ignore the breakpoints
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        oito.step().unwrap();
+        assert_eq!(0x204, oito.registers().pc);
+
+        #[cfg(feature = "alloc")]
+        assert_eq!(vec![0x202], oito.breakpoints());
+        assert!(oito.remove_breakpoint(0x202));
+        assert!(!oito.remove_breakpoint(0x202));
+    }
+
+    #[test]
+    fn watchpoint() {
+        let mut oito = OitoCore::new();
+        // Stores V0 at 0x300 twice
+        oito.load(&[0xA3, 0x00, 0x60, 0x05, 0xF0, 0x55, 0xF0, 0x55]);
+        oito.add_watchpoint(0x300);
+
+        for _ in 0..3 {
+            oito.tick().unwrap();
+        }
+        let watched = Break::Watchpoint {
+            address: 0x300,
+            old: 0x00,
+            new: 0x05,
+        };
+        assert_eq!(Status::Break(watched), oito.status());
+        // Writes of the same value are not changes
+        oito.tick().unwrap();
+        assert_eq!(Status::Running, oito.status());
+        #[cfg(feature = "alloc")]
+        assert_eq!(vec![0x300], oito.watchpoints());
+    }
+
+    #[test]
+    fn memory() {
+        let mut oito = OitoCore::default();
+        oito.poke(0x300, &[0x12, 0x34]).unwrap();
+
+        assert_eq!(&[0x12, 0x34, 0x00], oito.memory(0x300, 3).unwrap());
+        assert_eq!(
+            (0x1234, Some(Instruction::JP(0x234))),
+            oito.instruction_at(0x300).unwrap()
+        );
+        assert!(oito.memory(0xFFF, 2).is_err());
+        assert!(oito.poke(0xFFF, &[0, 0]).is_err());
+    }
+
+    #[test]
+    fn set_register() {
+        let mut oito = OitoCore::default();
+        oito.set_register(Register::V(3), 0x10);
+        oito.set_register(Register::I, 0x300);
+        oito.set_register(Register::Pc, 0x2A4);
+        oito.set_register(Register::St, 0x104);
+
+        let registers = oito.registers();
+        assert_eq!(0x10, registers.v[3]);
+        assert_eq!(0x300, registers.i);
+        assert_eq!(0x2A4, registers.pc);
+        assert_eq!(0x04, registers.st);
+    }
+
+    #[test]
+    fn save_state() {
+        // LD V0, 0x07; LD I, 0x300; LD [I], V0; CALL 0x20A; JP 0x208; DRW V0, V0, 1; RET
+        let rom = [
+            0x60, 0x07, 0xA3, 0x00, 0xF0, 0x55, 0x22, 0x0A, 0x12, 0x08, 0xD0, 0x01, 0x00, 0xEE,
+        ];
+        let mut oito = OitoCore::new();
+        oito.load(&rom);
+        for _ in 0..5 {
+            oito.tick().unwrap();
+        }
+        oito.key_press(Key::A);
+        let state = oito.save_state();
+
+        let mut restored = OitoCore::new();
+        restored.load_state(&State::try_from(state.to_bytes().as_slice()).unwrap());
+        assert_eq!(oito.registers(), restored.registers());
+        assert!(oito.frame_buffer().eq(restored.frame_buffer()));
+        assert_eq!(oito.memory(0, 4096), restored.memory(0, 4096));
+        assert_eq!(oito.keys(), restored.keys());
+        assert_eq!(5, restored.instructions());
+        assert!(!restored.take_damage().is_empty());
+
+        // Both continue equally, returning from the subroutine
+        oito.tick().unwrap();
+        restored.tick().unwrap();
+        assert_eq!(0x208, restored.registers().pc);
+        assert_eq!(oito.registers(), restored.registers());
+        assert_eq!(state, {
+            restored.load_state(&state);
+            restored.save_state()
+        });
+    }
+
+    #[test]
+    fn save_state_random_and_latches() {
+        // LD VA, 0x0A; RND V0, 0xFF; SKP VA; RND V1, 0xFF; RND V2, 0xFF
+        let rom = [0x6A, 0x0A, 0xC0, 0xFF, 0xEA, 0x9E, 0xC1, 0xFF, 0xC2, 0xFF];
+        let seeded = || {
+            OitoCore::with_config(Config {
+                seed: Some(7),
+                ..Default::default()
+            })
+        };
+        let mut oito = seeded();
+        oito.load(&rom);
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        oito.key_press(Key::A);
+        oito.key_release(Key::A);
+        let state = oito.save_state();
+
+        let mut restored = seeded();
+        restored.load_state(&State::try_from(state.to_bytes().as_slice()).unwrap());
+        for _ in 0..2 {
+            oito.tick().unwrap();
+            restored.tick().unwrap();
+        }
+        // The tap is still latched and the random numbers continue the sequence
+        assert_eq!(0x20A, restored.registers().pc);
+        assert_eq!(oito.registers(), restored.registers());
+    }
+
+    #[test]
+    fn load_state_recompiled() {
+        let mut oito = recompiled();
+        oito.load(&[0x70, 0x01, 0x12, 0x00]);
+        oito.tick().unwrap();
+        let state = oito.save_state();
+
+        // The compiled code of the overwritten memory is discarded
+        oito.poke(0x200, &[0x70, 0x05]).unwrap();
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        assert_eq!(6, oito.registers().v[0]);
+        oito.load_state(&state);
+        oito.tick().unwrap();
+        oito.tick().unwrap();
+        assert_eq!(2, oito.registers().v[0]);
+    }
+
+    #[test]
+    fn seed() {
+        // RND V0, 0xFF; RND V1, 0xFF; ...; RND V7, 0xFF
+        let rom: Vec<u8> = (0..8).flat_map(|x| [0xC0 | x, 0xFF]).collect();
+        let run = |seed| {
+            let mut oito = OitoCore::with_config(Config {
+                seed,
+                ..Default::default()
+            });
+            oito.load(&rom);
+            for _ in 0..8 {
+                oito.tick().unwrap();
+            }
+            oito.registers().v
+        };
+        assert_eq!(run(Some(7)), run(Some(7)));
+        assert_ne!(run(Some(7)), run(Some(8)));
+        // Without the entropy of the system the unseeded runs use the seed zero
+        #[cfg(not(feature = "std"))]
+        assert_eq!(run(Some(0)), run(None));
     }
 
     #[test]
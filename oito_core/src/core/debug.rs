@@ -0,0 +1,231 @@
+use core::{fmt::Display, str::FromStr};
+
+use crate::{ram::RAM_SIZE, Address, Byte, ParseError, RegIndex};
+
+/// Bits of each word of the address sets
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Registers and timers that debuggers can modify
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    /// V-Register
+    V(RegIndex),
+    /// I-Register
+    I,
+    /// Program Counter
+    Pc,
+    /// Delay timer
+    Dt,
+    /// Sound timer
+    St,
+}
+
+impl FromStr for Register {
+    type Err = ParseError;
+
+    /// Parses a register, ignoring the case: `v0` to `vf`, `i`, `pc`, `dt` or `st`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            ("i", Register::I),
+            ("pc", Register::Pc),
+            ("dt", Register::Dt),
+            ("st", Register::St),
+        ]
+        .into_iter()
+        .find(|(name, _)| s.eq_ignore_ascii_case(name))
+        .map(|(_, register)| register)
+        .or_else(|| {
+            s.strip_prefix(['v', 'V'])
+                .filter(|x| x.len() == 1)
+                .and_then(|x| RegIndex::from_str_radix(x, 16).ok())
+                .map(Register::V)
+        })
+        .ok_or_else(|| parse_error!("invalid register: {s}"))
+    }
+}
+
+/// Reason of a debugger to stop the execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Break {
+    /// The execution reached the address, the instruction on it has not been run yet
+    Breakpoint(Address),
+    /// The last instruction changed the content of the address
+    Watchpoint {
+        address: Address,
+        old: Byte,
+        new: Byte,
+    },
+}
+
+impl Display for Break {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Break::Breakpoint(address) => write!(f, "breakpoint at {address:#05X}"),
+            Break::Watchpoint { address, old, new } => {
+                write!(f, "watchpoint at {address:#05X}: {old:#04X} -> {new:#04X}")
+            }
+        }
+    }
+}
+
+/// Set of addresses of the memory, one bit for each of them
+#[derive(Debug)]
+pub(crate) struct AddressSet([u64; RAM_SIZE / WORD_BITS]);
+
+impl AddressSet {
+    /// Adds the address to the set, the addresses out of the memory are ignored.
+    /// Returns false if it was already present.
+    pub fn insert(&mut self, address: Address) -> bool {
+        let Some((word, bit)) = position(address) else {
+            return false;
+        };
+        let present = self.0[word] & bit != 0;
+        self.0[word] |= bit;
+        !present
+    }
+
+    /// Removes the address from the set. Returns false if it wasn't present.
+    pub fn remove(&mut self, address: Address) -> bool {
+        let present = self.contains(address);
+        if let Some((word, bit)) = position(address) {
+            self.0[word] &= !bit;
+        }
+        present
+    }
+
+    /// Returns true if the address is in the set
+    pub fn contains(&self, address: Address) -> bool {
+        position(address).is_some_and(|(word, bit)| self.0[word] & bit != 0)
+    }
+
+    /// Returns true if any address of the range starting at the address is in the set
+    pub fn intersects(&self, address: Address, len: usize) -> bool {
+        let end = (address as usize).saturating_add(len).min(RAM_SIZE);
+        (address as usize..end).any(|address| self.contains(address as Address))
+    }
+
+    /// Returns true if there are no addresses in the set
+    #[cfg(feature = "alloc")]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// Returns the addresses in the set, sorted
+    #[cfg(feature = "alloc")]
+    pub fn iter(&self) -> impl Iterator<Item = Address> + '_ {
+        (0..RAM_SIZE as Address).filter(|address| self.contains(*address))
+    }
+}
+
+impl Default for AddressSet {
+    fn default() -> Self {
+        Self([0; RAM_SIZE / WORD_BITS])
+    }
+}
+
+/// Returns the word and the bit of the address in the sets, if it's part of the memory
+fn position(address: Address) -> Option<(usize, u64)> {
+    let address = address as usize;
+    (address < RAM_SIZE).then(|| (address / WORD_BITS, 1 << (address % WORD_BITS)))
+}
+
+/// Breakpoints and watchpoints set by a debugger
+#[derive(Debug, Default)]
+pub(crate) struct Debugger {
+    pub breakpoints: AddressSet,
+    pub watchpoints: AddressSet,
+    /// First watched change made by the running instruction
+    watched: Option<Break>,
+}
+
+impl Debugger {
+    /// Returns true if the execution should stop before running the address
+    pub fn breaks_at(&self, address: Address) -> bool {
+        self.breakpoints.contains(address)
+    }
+
+    /// Returns true if any of the addresses in the range is watched
+    pub fn watches(&self, address: Address, len: usize) -> bool {
+        self.watchpoints.intersects(address, len)
+    }
+
+    /// Records the change of a watched address, if no other was recorded
+    pub fn change(&mut self, address: Address, old: Byte, new: Byte) {
+        if old != new && self.watchpoints.contains(address) && self.watched.is_none() {
+            self.watched = Some(Break::Watchpoint { address, old, new });
+        }
+    }
+
+    /// Returns the first watched change since the last call
+    pub fn take_watched(&mut self) -> Option<Break> {
+        self.watched.take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "alloc")]
+    use alloc::{vec, vec::Vec};
+
+    use super::{AddressSet, Break, Debugger, Register};
+
+    #[test]
+    fn register_from_str() {
+        assert_eq!(Ok(Register::V(0x3)), "v3".parse());
+        assert_eq!(Ok(Register::V(0xF)), "VF".parse());
+        assert_eq!(Ok(Register::Pc), "pc".parse());
+        assert!("v10".parse::<Register>().is_err());
+        assert!("x".parse::<Register>().is_err());
+    }
+
+    #[test]
+    fn address_set() {
+        let mut set = AddressSet::default();
+        #[cfg(feature = "alloc")]
+        assert!(set.is_empty());
+        assert!(set.insert(0x200));
+        assert!(!set.insert(0x200));
+        assert!(set.insert(0xFFF));
+        assert!(!set.insert(0x1000));
+        assert!(set.contains(0x200));
+        assert!(!set.contains(0x201));
+        #[cfg(feature = "alloc")]
+        assert_eq!(vec![0x200, 0xFFF], set.iter().collect::<Vec<_>>());
+
+        assert!(set.intersects(0x1FF, 2));
+        assert!(!set.intersects(0x1FE, 2));
+        assert!(set.intersects(0xFFE, usize::MAX));
+
+        assert!(set.remove(0x200));
+        assert!(!set.remove(0x200));
+        assert!(!set.remove(0x1000));
+        assert!(!set.contains(0x200));
+        assert!(set.remove(0xFFF));
+        #[cfg(feature = "alloc")]
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn watches() {
+        let mut debugger = Debugger::default();
+        debugger.watchpoints.insert(0x300);
+
+        assert!(debugger.watches(0x2FE, 3));
+        assert!(!debugger.watches(0x2FE, 2));
+
+        debugger.change(0x300, 1, 1);
+        assert_eq!(None, debugger.take_watched());
+        debugger.change(0x301, 1, 2);
+        assert_eq!(None, debugger.take_watched());
+        debugger.change(0x300, 1, 2);
+        debugger.change(0x300, 2, 3);
+        assert_eq!(
+            Some(Break::Watchpoint {
+                address: 0x300,
+                old: 1,
+                new: 2
+            }),
+            debugger.take_watched()
+        );
+    }
+}
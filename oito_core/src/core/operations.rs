@@ -1,5 +1,6 @@
 use crate::RegIndex;
 
+#[derive(Clone, Copy, Debug)]
 pub enum ArithOp<T> {
     Add(RegIndex, T),
     CheckedAdd(RegIndex, RegIndex),
@@ -7,6 +8,7 @@ pub enum ArithOp<T> {
     SubN(RegIndex, RegIndex),
 }
 
+#[derive(Clone, Copy, Debug)]
 pub enum BitOp {
     And(RegIndex, RegIndex),
     Or(RegIndex, RegIndex),
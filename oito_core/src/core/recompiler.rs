@@ -0,0 +1,407 @@
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use core::fmt::Debug;
+
+use crate::core::operations::{ArithOp, BitOp};
+use crate::{exception::Exception, fontset, instruction::Instruction, ram::Ram, Address, OpCode};
+
+use super::{OitoCore, Status};
+
+/// Size in memory of each instruction
+const INSTRUCTION_SIZE: Address = 2;
+/// Maximum number of instructions compiled in a single block
+const MAX_BLOCK_LENGTH: usize = 64;
+/// Maximum number of bytes of memory a single block can span
+const MAX_BLOCK_SIZE: Address = MAX_BLOCK_LENGTH as Address * INSTRUCTION_SIZE;
+
+/// Instruction compiled into a closure over its operands, run without decoding or dispatching it again
+type Op = Box<dyn Fn(&mut OitoCore) -> Result<(), Exception> + Send + Sync>;
+
+/// Straight-line run of instructions compiled into operations.
+/// It ends with a change of the control flow or with a write into memory, which could overwrite the block.
+pub struct Block {
+    /// Address of the first instruction of the block
+    start: Address,
+    /// Decoded instructions of the block
+    instructions: Vec<Instruction>,
+    /// Compiled operation of each instruction
+    ops: Vec<Op>,
+}
+
+impl Block {
+    /// Compiles the instructions stored in memory starting at the specified address
+    fn compile(start: Address, ram: &Ram) -> Result<Self, Exception> {
+        let mut instructions = Vec::new();
+        let mut address = start;
+        while instructions.len() < MAX_BLOCK_LENGTH {
+            let instruction = match fetch(ram, address).and_then(Instruction::try_from) {
+                Ok(instruction) => instruction,
+                // the faulty instruction will be reported when it's actually reached
+                Err(_) if !instructions.is_empty() => break,
+                Err(e) => return Err(e),
+            };
+            instructions.push(instruction);
+            if instruction.is_terminator() || writes_memory(instruction) {
+                break;
+            }
+            address += INSTRUCTION_SIZE;
+        }
+        let ops = instructions.iter().copied().map(compile).collect();
+        Ok(Self {
+            start,
+            instructions,
+            ops,
+        })
+    }
+
+    /// Returns the number of instructions of the block
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns the address following the last instruction of the block
+    pub fn end(&self) -> Address {
+        self.address_of(self.len())
+    }
+
+    /// Returns the number of bytes of memory spanned by the block
+    pub fn size(&self) -> usize {
+        self.len() * INSTRUCTION_SIZE as usize
+    }
+
+    /// Returns the address of the instruction at the specified position of the block
+    fn address_of(&self, index: usize) -> Address {
+        self.start + index as Address * INSTRUCTION_SIZE
+    }
+
+    /// Returns the instruction at the specified position of the block
+    pub fn instruction(&self, index: usize) -> Instruction {
+        self.instructions[index]
+    }
+
+    /// Runs the compiled instruction at the specified position of the block
+    pub fn run_op(&self, index: usize, oito: &mut OitoCore) -> Result<(), Exception> {
+        (self.ops[index])(oito)
+    }
+
+    /// Runs every instruction of the block, leaving the emulator as running them one by one would.
+    /// Only the last instruction can change the control flow, so the pc is just set before it.
+    pub fn run(&self, oito: &mut OitoCore) -> Result<(), Exception> {
+        let last = self.len() - 1;
+        for (index, op) in self.ops[..last].iter().enumerate() {
+            if let Err(e) = op(oito) {
+                oito.cpu.point_at(self.address_of(index + 1));
+                oito.executed += index as u64;
+                if index > 0 {
+                    oito.status = Status::Running;
+                }
+                return Err(e);
+            }
+        }
+        oito.cpu.point_at(self.end());
+        oito.executed += last as u64;
+        if last > 0 {
+            oito.status = Status::Running;
+        }
+        self.run_op(last, oito)?;
+        oito.executed += 1;
+        oito.status = oito.status_after(self.instructions[last], self.address_of(last));
+        Ok(())
+    }
+}
+
+impl Debug for Block {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Block")
+            .field("start", &self.start)
+            .field("instructions", &self.instructions)
+            .finish()
+    }
+}
+
+/// Cache of the compiled blocks indexed by their entry address
+#[derive(Default)]
+pub struct BlockCache {
+    /// Compiled blocks
+    blocks: BTreeMap<Address, Arc<Block>>,
+    /// Block being run one instruction at a time and position of its next instruction
+    cursor: Option<(Arc<Block>, usize)>,
+}
+
+impl BlockCache {
+    /// Returns the block starting at the specified address, compiling and caching it if needed
+    pub fn block(&mut self, pc: Address, ram: &Ram) -> Result<Arc<Block>, Exception> {
+        if let Some(block) = self.blocks.get(&pc) {
+            return Ok(block.clone());
+        }
+        let block = Arc::new(Block::compile(pc, ram)?);
+        self.blocks.insert(pc, block.clone());
+        Ok(block)
+    }
+
+    /// Returns the block and the position in it of the instruction at the specified address,
+    /// to run it on its own. The continuation of the running block is taken from the cursor,
+    /// any other address enters the block starting at it.
+    pub fn next(&mut self, pc: Address, ram: &Ram) -> Result<(Arc<Block>, usize), Exception> {
+        let (block, index) = match self.cursor.take() {
+            Some((block, index)) if index < block.len() && block.address_of(index) == pc => {
+                (block, index)
+            }
+            _ => (self.block(pc, ram)?, 0),
+        };
+        self.cursor = Some((block.clone(), index + 1));
+        Ok((block, index))
+    }
+
+    /// Discards every compiled block overlapping the specified memory range
+    pub fn invalidate(&mut self, start: Address, length: usize) {
+        let end = start.saturating_add(length as Address);
+        let overlaps = |block: &Block| block.start < end && block.end() > start;
+        let stale: Vec<Address> = self
+            .blocks
+            .range(start.saturating_sub(MAX_BLOCK_SIZE)..end)
+            .filter(|(_, block)| overlaps(block))
+            .map(|(entry, _)| *entry)
+            .collect();
+        for entry in stale {
+            self.blocks.remove(&entry);
+        }
+        if matches!(&self.cursor, Some((block, _)) if overlaps(block)) {
+            self.cursor = None;
+        }
+    }
+}
+
+impl Debug for BlockCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BlockCache")
+            .field("blocks", &self.blocks.len())
+            .finish()
+    }
+}
+
+/// Returns true if the instruction writes into memory
+fn writes_memory(instruction: Instruction) -> bool {
+    matches!(instruction, Instruction::LDrm(_) | Instruction::LDvm(_))
+}
+
+/// Compiles the instruction into an operation over its operands.
+/// The instructions changing the control flow only end the blocks, so they are run by the interpreter.
+fn compile(instruction: Instruction) -> Op {
+    use Instruction::*;
+    match instruction {
+        CLS => Box::new(|oito| {
+            oito.vram.clear();
+            Ok(())
+        }),
+        LDbr { x, byte } => Box::new(move |oito| {
+            oito.cpu.load_to_v(x, byte);
+            Ok(())
+        }),
+        ADDbr { x, byte } => arith(ArithOp::Add(x, byte)),
+        LDrr { x, y } => Box::new(move |oito| {
+            oito.cpu.load_to_v(x, oito.cpu.v(y).get());
+            Ok(())
+        }),
+        OR { x, y } => bit(BitOp::Or(x, y)),
+        AND { x, y } => bit(BitOp::And(x, y)),
+        XOR { x, y } => bit(BitOp::Xor(x, y)),
+        ADDrr { x, y } => arith(ArithOp::CheckedAdd(x, y)),
+        SUB { x, y } => arith(ArithOp::Sub(x, y)),
+        SHR(x) => bit(BitOp::ShiftRight(x)),
+        SUBN { x, y } => arith(ArithOp::SubN(x, y)),
+        SHL(x) => bit(BitOp::ShiftLeft(x)),
+        LDi(address) => Box::new(move |oito| {
+            oito.cpu.set_i(address);
+            Ok(())
+        }),
+        RND { x, byte } => Box::new(move |oito| {
+            let random = oito.random();
+            oito.cpu.load_to_v(x, byte & random);
+            Ok(())
+        }),
+        DRW { x, y, n } => Box::new(move |oito| oito.draw(x, y, n)),
+        LDdr(x) => Box::new(move |oito| {
+            oito.cpu.load_to_v(x, oito.dt.get());
+            Ok(())
+        }),
+        LDrd(x) => Box::new(move |oito| {
+            oito.dt.set(oito.cpu.v(x).get());
+            Ok(())
+        }),
+        LDrs(x) => Box::new(move |oito| {
+            oito.st.set(oito.cpu.v(x).get());
+            Ok(())
+        }),
+        ADDri(x) => Box::new(move |oito| {
+            let address = oito.cpu.i().wrapping_add(oito.cpu.v(x).get() as Address);
+            oito.cpu.set_i(address);
+            Ok(())
+        }),
+        LDmi(x) => Box::new(move |oito| {
+            oito.cpu.set_i(fontset::location(oito.cpu.v(x).get()));
+            Ok(())
+        }),
+        LDhi(x) => Box::new(move |oito| {
+            oito.cpu.set_i(fontset::big_location(oito.cpu.v(x).get()));
+            Ok(())
+        }),
+        LDrm(x) => Box::new(move |oito| oito.store_bcd(x)),
+        LDvm(x) => Box::new(move |oito| oito.store_registers(x)),
+        LDmv(x) => Box::new(move |oito| oito.load_registers(x)),
+        _ => Box::new(move |oito| oito.execute(instruction)),
+    }
+}
+
+/// Compiles an arithmetic operation between registers or with a byte
+fn arith(operation: ArithOp<u8>) -> Op {
+    Box::new(move |oito| {
+        oito.cpu.arith_op(operation);
+        Ok(())
+    })
+}
+
+/// Compiles a bitwise operation between registers
+fn bit(operation: BitOp) -> Op {
+    Box::new(move |oito| {
+        oito.cpu.bit_op(operation);
+        Ok(())
+    })
+}
+
+/// Reads from memory the opcode stored at the specified address
+fn fetch(ram: &Ram, address: Address) -> Result<OpCode, Exception> {
+    let big_byte = ram.read(address)? as OpCode;
+    let small_byte = ram.read(address + 1)? as OpCode;
+    Ok((big_byte << 8) | small_byte)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::OitoCore;
+    use crate::{cpu::Cpu, exception::Exception, instruction::Instruction, ram::Ram};
+
+    use super::{Block, BlockCache, MAX_BLOCK_LENGTH};
+
+    const PROGRAM: [u8; 8] = [
+        0x61, 0x02, // V1 = 0x02
+        0x71, 0x01, // V1 += 0x01
+        0x12, 0x00, // goto 0x200
+        0x62, 0x03, // V2 = 0x03
+    ];
+
+    fn ram_with_program() -> Ram {
+        let mut ram = Ram::default();
+        ram.load(0x200, &PROGRAM);
+        ram
+    }
+
+    #[test]
+    fn compile() {
+        let block = Block::compile(0x200, &ram_with_program()).unwrap();
+
+        assert_eq!(0x200, block.start);
+        assert_eq!(0x206, block.end());
+        assert_eq!(6, block.size());
+        assert_eq!(Instruction::JP(0x200), block.instruction(2));
+    }
+
+    #[test]
+    fn compile_until_max_length() {
+        let mut ram = Ram::default();
+        ram.load(0x200, &[0x61; MAX_BLOCK_LENGTH * 4]);
+
+        let block = Block::compile(0x200, &ram).unwrap();
+        assert_eq!(MAX_BLOCK_LENGTH, block.len());
+    }
+
+    #[test]
+    fn compile_until_wrong_opcode() {
+        let mut ram = ram_with_program();
+        ram.load(0x200, &[0x61, 0x02, 0xFF, 0xFF]);
+
+        let block = Block::compile(0x200, &ram).unwrap();
+        assert_eq!(1, block.len());
+        assert_eq!(
+            Exception::WrongOpCode(0xFFFF),
+            Block::compile(0x202, &ram).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn compile_until_write() {
+        let mut ram = Ram::default();
+        // V0 = 0x01, [I] = V0, V1 = 0x02
+        ram.load(0x200, &[0x60, 0x01, 0xF0, 0x55, 0x61, 0x02]);
+
+        let block = Block::compile(0x200, &ram).unwrap();
+        assert_eq!(2, block.len());
+        assert_eq!(Instruction::LDvm(0), block.instruction(1));
+    }
+
+    #[test]
+    fn run() {
+        let mut oito = OitoCore::new();
+        oito.load(&PROGRAM);
+        let block = Block::compile(Cpu::STARTING_ADDRESS, &oito.ram).unwrap();
+
+        block.run(&mut oito).unwrap();
+        assert_eq!(0x03, oito.cpu.v(1).get());
+        assert_eq!(0x200, oito.cpu.pc());
+        assert_eq!(3, oito.instructions());
+    }
+
+    #[test]
+    fn run_until_exception() {
+        let mut oito = OitoCore::new();
+        // V1 = 0x02, I = 0xFFF, V0..V1 = [I], V1 = 0x03
+        oito.load(&[0x61, 0x02, 0xAF, 0xFF, 0xF1, 0x65, 0x61, 0x03]);
+        let block = Block::compile(Cpu::STARTING_ADDRESS, &oito.ram).unwrap();
+
+        assert!(block.run(&mut oito).is_err());
+        assert_eq!(0x02, oito.cpu.v(1).get());
+        assert_eq!(0x206, oito.cpu.pc());
+        assert_eq!(2, oito.instructions());
+    }
+
+    #[test]
+    fn next() {
+        let ram = ram_with_program();
+        let mut cache = BlockCache::default();
+
+        let (block, index) = cache.next(0x200, &ram).unwrap();
+        assert_eq!(
+            Instruction::LDbr { x: 1, byte: 0x02 },
+            block.instruction(index)
+        );
+        let (block, index) = cache.next(0x202, &ram).unwrap();
+        assert_eq!(
+            Instruction::ADDbr { x: 1, byte: 0x01 },
+            block.instruction(index)
+        );
+        assert_eq!((0x200, 1), (block.start, index));
+        // jumping into the middle of a block compiles a new one
+        let (block, index) = cache.next(0x202, &ram).unwrap();
+        assert_eq!((0x202, 0), (block.start, index));
+        assert_eq!(2, cache.blocks.len());
+    }
+
+    #[test]
+    fn invalidate() {
+        let ram = ram_with_program();
+        let mut cache = BlockCache::default();
+        cache.next(0x200, &ram).unwrap();
+        cache.next(0x206, &ram).unwrap();
+
+        cache.invalidate(0x1F0, 0x10);
+        assert_eq!(2, cache.blocks.len());
+
+        cache.next(0x200, &ram).unwrap();
+        cache.invalidate(0x204, 1);
+        assert!(!cache.blocks.contains_key(&0x200));
+        assert!(cache.blocks.contains_key(&0x206));
+        // the cursor pointing to an invalidated block triggers a new compilation
+        assert!(cache.cursor.is_none());
+        let (block, _) = cache.next(0x202, &ram).unwrap();
+        assert_eq!(0x202, block.start);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{Address, Byte};
+
+/// Snapshot of the registers and timers of the emulator
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Registers {
+    /// Program Counter
+    pub pc: Address,
+    /// I-Register
+    pub i: Address,
+    /// V-Registers
+    pub v: [Byte; 16],
+    /// Number of addresses in the stack
+    pub sp: usize,
+    /// Delay timer
+    pub dt: Byte,
+    /// Sound timer
+    pub st: Byte,
+}
@@ -0,0 +1,289 @@
+use crate::exception::Exception;
+use crate::instruction::Instruction;
+use crate::key::Key;
+use crate::{fontset, Address, Byte, Pixel, RegIndex, Rom};
+
+use super::{Halt, OitoCore, Status};
+
+/// Minimal set of operations used by the ROMs translated to native code ahead of time.
+/// The state lives in an [OitoCore], so any instruction that couldn't be translated can fall
+/// back to the interpreter with [Runtime::step].
+#[derive(Debug)]
+pub struct Runtime {
+    /// Emulator holding the memory, display, timers and keys
+    core: OitoCore,
+}
+
+impl Runtime {
+    /// Returns a new runtime with the preloaded sprites ready to be used
+    pub fn new() -> Self {
+        Self {
+            core: OitoCore::new(),
+        }
+    }
+
+    /// Loads the ROM content into memory, used by the interpreter fallback
+    pub fn load(&mut self, data: Rom) {
+        self.core.load(data);
+    }
+
+    /// Runs a single instruction with the interpreter
+    pub fn step(&mut self) -> Result<(), Exception> {
+        self.core.tick()
+    }
+
+    /// Checks that the memory starting at the address still holds the specified code.
+    /// Used to detect when the translated code has been overwritten by the ROM.
+    pub fn verify(&self, address: Address, code: &[Byte]) -> bool {
+        code.iter()
+            .enumerate()
+            .all(|(i, byte)| self.core.ram.read(address + i as Address) == Ok(*byte))
+    }
+
+    /// Returns the address currently pointed by the Program Counter
+    #[inline]
+    pub fn pc(&self) -> Address {
+        self.core.cpu.pc()
+    }
+
+    /// Points the Program Counter to the specified address
+    #[inline]
+    pub fn jump(&mut self, address: Address) {
+        self.core.cpu.point_at(address);
+    }
+
+    /// Pushes the return address into the stack and jumps to the subroutine
+    pub fn call(&mut self, ret: Address, address: Address) -> Result<(), Exception> {
+        self.core.stack.push(ret)?;
+        self.jump(address);
+        Ok(())
+    }
+
+    /// Jumps to the address at the top of the stack
+    pub fn ret(&mut self) -> Result<(), Exception> {
+        let address = self.core.stack.pop()?;
+        self.jump(address);
+        Ok(())
+    }
+
+    /// Returns the value of the specified V-Register
+    #[inline]
+    pub fn v(&self, x: RegIndex) -> Byte {
+        self.core.cpu.v(x).get()
+    }
+
+    /// Loads the value into the specified V-Register
+    #[inline]
+    pub fn set_v(&mut self, x: RegIndex, value: Byte) {
+        self.core.cpu.load_to_v(x, value);
+    }
+
+    /// Loads the value into the flag register
+    #[inline]
+    pub fn set_flag(&mut self, value: Byte) {
+        self.core.cpu.set_flag(value);
+    }
+
+    /// Returns the address stored in the I-Register
+    #[inline]
+    pub fn i(&self) -> Address {
+        self.core.cpu.i()
+    }
+
+    /// Loads the address into the I-Register
+    #[inline]
+    pub fn set_i(&mut self, address: Address) {
+        self.core.cpu.set_i(address);
+    }
+
+    /// Returns a random byte
+    #[inline]
+    pub fn random(&mut self) -> Byte {
+        self.core.random()
+    }
+
+    /// Returns the current count of the delay timer
+    #[inline]
+    pub fn dt(&self) -> Byte {
+        self.core.dt.get()
+    }
+
+    /// Sets the count of the delay timer
+    #[inline]
+    pub fn set_dt(&mut self, value: Byte) {
+        self.core.dt.set(value);
+    }
+
+    /// Sets the count of the sound timer
+    #[inline]
+    pub fn set_st(&mut self, value: Byte) {
+        self.core.st.set(value);
+    }
+
+    /// Returns true if the key matching the value of Vx is pressed
+    #[inline]
+    pub fn key(&mut self, x: RegIndex) -> bool {
+        let value = self.v(x);
+        self.core.keys.take_key(value)
+    }
+
+    /// Loads the pressed key into Vx. Returns false if there's no key pressed.
+    pub fn wait_key(&mut self, x: RegIndex) -> bool {
+        self.core.wait_key(x)
+    }
+
+    /// Points the I-Register to the font sprite of the character stored in Vx
+    pub fn font(&mut self, x: RegIndex) {
+        self.set_i(fontset::location(self.v(x)));
+    }
+
+    /// Points the I-Register to the big font sprite of the character stored in Vx
+    pub fn big_font(&mut self, x: RegIndex) {
+        self.set_i(fontset::big_location(self.v(x)));
+    }
+
+    /// Clears the screen
+    pub fn cls(&mut self) {
+        self.core.vram.clear();
+    }
+
+    /// Draws the n-byte sprite pointed by I at (Vx, Vy)
+    pub fn draw(&mut self, x: RegIndex, y: RegIndex, n: Byte) -> Result<(), Exception> {
+        self.core.execute(Instruction::DRW { x, y, n })
+    }
+
+    /// Stores the BCD representation of Vx in memory starting at I
+    pub fn bcd(&mut self, x: RegIndex) -> Result<(), Exception> {
+        self.core.execute(Instruction::LDrm(x))
+    }
+
+    /// Stores the registers [V0, Vx] in memory starting at I
+    pub fn store(&mut self, x: RegIndex) -> Result<(), Exception> {
+        self.core.execute(Instruction::LDvm(x))
+    }
+
+    /// Reads the registers [V0, Vx] from memory starting at I
+    pub fn restore(&mut self, x: RegIndex) -> Result<(), Exception> {
+        self.core.execute(Instruction::LDmv(x))
+    }
+
+    /// Stops the emulator at the exit instruction of the specified address
+    pub fn exit(&mut self, address: Address) {
+        self.jump(address);
+        self.core.status = Status::Halted(Halt::Exit);
+    }
+
+    /// Returns the execution state, stopped after an exit or a halt detected by the interpreter
+    pub fn status(&self) -> Status {
+        self.core.status()
+    }
+
+    /// Perfoms a frame-tied tick
+    pub fn frame_tick(&mut self) {
+        self.core.frame_tick();
+    }
+
+    /// Returns the pixels of the next frame to draw, row by row
+    pub fn frame_buffer(&self) -> impl ExactSizeIterator<Item = Pixel> + Clone + '_ {
+        self.core.frame_buffer()
+    }
+
+    /// Returns true if the sound should be played
+    pub fn sound(&self) -> bool {
+        self.core.sound()
+    }
+
+    /// Emmulates the pressing of the desired key
+    pub fn key_press(&mut self, key: Key) {
+        self.core.key_press(key);
+    }
+
+    /// Emmulates the release of the desired key
+    pub fn key_release(&mut self, key: Key) {
+        self.core.key_release(key);
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{Halt, Status},
+        cpu::Cpu,
+        key::Key,
+    };
+
+    use super::Runtime;
+
+    #[test]
+    fn step() {
+        let mut rt = Runtime::new();
+        rt.load(&[0x63, 0xA2]);
+
+        rt.step().unwrap();
+        assert_eq!(Cpu::STARTING_ADDRESS + 2, rt.pc());
+        assert_eq!(0xA2, rt.v(3));
+    }
+
+    #[test]
+    fn verify() {
+        let mut rt = Runtime::new();
+        rt.load(&[0x63, 0xA2, 0x00, 0xE0]);
+
+        assert!(rt.verify(Cpu::STARTING_ADDRESS, &[0x63, 0xA2, 0x00, 0xE0]));
+        assert!(!rt.verify(Cpu::STARTING_ADDRESS, &[0x63, 0xA3]));
+    }
+
+    #[test]
+    fn call_and_ret() {
+        let mut rt = Runtime::new();
+
+        rt.call(0x202, 0x300).unwrap();
+        assert_eq!(0x300, rt.pc());
+
+        rt.ret().unwrap();
+        assert_eq!(0x202, rt.pc());
+    }
+
+    #[test]
+    fn exit() {
+        let mut rt = Runtime::new();
+        rt.load(&[0x60, 0x01]);
+
+        rt.exit(0x200);
+        assert_eq!(Status::Halted(Halt::Exit), rt.status());
+        // The interpreter fallback doesn't run anything after the exit
+        rt.step().unwrap();
+        assert_eq!(0x00, rt.v(0));
+    }
+
+    #[test]
+    fn wait_key() {
+        let mut rt = Runtime::new();
+        assert!(!rt.wait_key(0));
+
+        rt.key_press(Key::Seven);
+        assert!(rt.wait_key(0));
+        assert_eq!(7, rt.v(0));
+        assert!(rt.key(0));
+    }
+
+    #[test]
+    fn font_and_draw() {
+        let mut rt = Runtime::new();
+        rt.set_v(0, 0x1);
+
+        rt.font(0);
+        assert_eq!(5, rt.i());
+
+        rt.draw(1, 1, 5).unwrap();
+        assert_eq!(Some(true), rt.frame_buffer().nth(2));
+        rt.cls();
+        assert_eq!(Some(false), rt.frame_buffer().nth(2));
+    }
+}
@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+use core::{fmt::Display, ops::Range};
+
+use crate::{ram::RAM_SIZE, Address};
+
+/// Bug of the running ROM detected by the sanitizer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Address of the instruction causing the issue
+    pub pc: Address,
+    /// Detected issue
+    pub issue: Issue,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#05X}: {}", self.pc, self.issue)
+    }
+}
+
+/// Kinds of bugs detected by the sanitizer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// Read of an address never written by the ROM, its loading or the font
+    UninitializedRead(Address),
+    /// Write into the interpreter and font area, below the start of the ROM
+    ReservedWrite(Address),
+    /// Write over an instruction already executed
+    CodeOverwrite(Address),
+    /// Jump or call to an odd address
+    OddJump(Address),
+    /// Sprite drawn from an address out of the ROM and the font
+    SpriteOutOfRom(Address),
+    /// Return with the stack empty
+    EmptyStackReturn,
+    /// VF used as the destination of an operation that overwrites it with the flag
+    FlagOperand,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Issue::UninitializedRead(address) => {
+                write!(f, "read of uninitialized memory at {address:#05X}")
+            }
+            Issue::ReservedWrite(address) => {
+                write!(f, "write into reserved memory at {address:#05X}")
+            }
+            Issue::CodeOverwrite(address) => {
+                write!(f, "write over executed code at {address:#05X}")
+            }
+            Issue::OddJump(address) => write!(f, "jump to odd address {address:#05X}"),
+            Issue::SpriteOutOfRom(address) => {
+                write!(f, "sprite drawn out of the ROM at {address:#05X}")
+            }
+            Issue::EmptyStackReturn => write!(f, "return with the stack empty"),
+            Issue::FlagOperand => write!(f, "VF result overwritten by the flag"),
+        }
+    }
+}
+
+/// Tracker of the memory usage reporting the suspicious behaviour of the ROM
+#[derive(Debug)]
+pub(crate) struct Sanitizer {
+    /// Addresses written by the ROM, its loading or the font
+    written: [bool; RAM_SIZE],
+    /// Addresses of the instructions already executed
+    executed: [bool; RAM_SIZE],
+    /// Memory where the font is loaded
+    font: Range<Address>,
+    /// Memory where the ROM is loaded
+    rom: Range<Address>,
+    /// Issues detected, each one reported once per instruction
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Sanitizer {
+    /// Start of the memory available for the ROMs
+    const RESERVED_END: Address = 0x200;
+
+    /// Tracks the memory where the font was loaded
+    pub fn load_font(&mut self, start: Address, len: usize) {
+        self.font = start..start + len as Address;
+        Self::mark(&mut self.written, self.font.clone());
+    }
+
+    /// Tracks the memory where the ROM was loaded
+    pub fn load_rom(&mut self, start: Address, len: usize) {
+        self.rom = start..start + len as Address;
+        Self::mark(&mut self.written, self.rom.clone());
+    }
+
+    /// Tracks the execution of the instruction at the address, reading it
+    pub fn execute(&mut self, pc: Address) {
+        self.read(pc, pc..pc + 2);
+        Self::mark(&mut self.executed, pc..pc + 2);
+    }
+
+    /// Checks the read of the memory by the instruction at the pc
+    pub fn read(&mut self, pc: Address, range: Range<Address>) {
+        for address in range {
+            if !Self::get(&self.written, address) {
+                self.report(pc, Issue::UninitializedRead(address));
+            }
+        }
+    }
+
+    /// Checks the write of the memory by the instruction at the pc
+    pub fn write(&mut self, pc: Address, range: Range<Address>) {
+        for address in range.clone() {
+            if address < Self::RESERVED_END {
+                self.report(pc, Issue::ReservedWrite(address));
+            } else if Self::get(&self.executed, address) {
+                self.report(pc, Issue::CodeOverwrite(address));
+            }
+        }
+        Self::mark(&mut self.written, range);
+    }
+
+    /// Checks the drawing of the sprite in the memory range by the instruction at the pc
+    pub fn draw(&mut self, pc: Address, range: Range<Address>) {
+        self.read(pc, range.clone());
+        for address in range {
+            if !self.font.contains(&address) && !self.rom.contains(&address) {
+                self.report(pc, Issue::SpriteOutOfRom(address));
+            }
+        }
+    }
+
+    /// Checks the destination of a jump of the instruction at the pc
+    pub fn jump(&mut self, pc: Address, address: Address) {
+        if !address.is_multiple_of(2) {
+            self.report(pc, Issue::OddJump(address));
+        }
+    }
+
+    /// Stores the issue unless it was already reported for the same instruction
+    pub fn report(&mut self, pc: Address, issue: Issue) {
+        let diagnostic = Diagnostic { pc, issue };
+        if !self.diagnostics.contains(&diagnostic) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Returns the issues detected
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns the issues detected and forgets them
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        core::mem::take(&mut self.diagnostics)
+    }
+
+    /// Returns the mark of the address, out of memory addresses are never marked
+    fn get(marks: &[bool; RAM_SIZE], address: Address) -> bool {
+        marks.get(address as usize).copied().unwrap_or(false)
+    }
+
+    /// Marks the addresses in the range, ignoring the ones out of memory
+    fn mark(marks: &mut [bool; RAM_SIZE], range: Range<Address>) {
+        let start = (range.start as usize).min(RAM_SIZE);
+        let end = (range.end as usize).min(RAM_SIZE);
+        marks[start..end].fill(true);
+    }
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self {
+            written: [false; RAM_SIZE],
+            executed: [false; RAM_SIZE],
+            font: 0..0,
+            rom: 0..0,
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use super::{Diagnostic, Issue, Sanitizer};
+
+    #[test]
+    fn read() {
+        let mut sanitizer = Sanitizer::default();
+        sanitizer.load_rom(0x200, 4);
+
+        sanitizer.read(0x200, 0x202..0x206);
+        assert_eq!(
+            &[
+                Diagnostic {
+                    pc: 0x200,
+                    issue: Issue::UninitializedRead(0x204)
+                },
+                Diagnostic {
+                    pc: 0x200,
+                    issue: Issue::UninitializedRead(0x205)
+                }
+            ],
+            sanitizer.diagnostics()
+        );
+    }
+
+    #[test]
+    fn write() {
+        let mut sanitizer = Sanitizer::default();
+        sanitizer.load_rom(0x200, 4);
+        sanitizer.execute(0x200);
+
+        sanitizer.write(0x202, 0x1FF..0x202);
+        let issues: Vec<_> = sanitizer.diagnostics().iter().map(|d| d.issue).collect();
+        assert_eq!(
+            vec![
+                Issue::ReservedWrite(0x1FF),
+                Issue::CodeOverwrite(0x200),
+                Issue::CodeOverwrite(0x201)
+            ],
+            issues
+        );
+
+        // Written memory can be read
+        sanitizer.take_diagnostics();
+        sanitizer.read(0x202, 0x1FF..0x200);
+        assert!(sanitizer.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn draw() {
+        let mut sanitizer = Sanitizer::default();
+        sanitizer.load_font(0x000, 80);
+        sanitizer.load_rom(0x200, 4);
+        sanitizer.write(0x200, 0x300..0x301);
+
+        sanitizer.draw(0x200, 0x000..0x005);
+        sanitizer.draw(0x200, 0x202..0x204);
+        assert!(sanitizer.diagnostics().is_empty());
+
+        sanitizer.draw(0x200, 0x300..0x301);
+        assert_eq!(
+            vec![Issue::SpriteOutOfRom(0x300)],
+            sanitizer
+                .take_diagnostics()
+                .iter()
+                .map(|d| d.issue)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn jump() {
+        let mut sanitizer = Sanitizer::default();
+
+        sanitizer.jump(0x200, 0x204);
+        assert!(sanitizer.diagnostics().is_empty());
+        sanitizer.jump(0x200, 0x205);
+        assert_eq!(Issue::OddJump(0x205), sanitizer.diagnostics()[0].issue);
+    }
+
+    #[test]
+    fn report() {
+        let mut sanitizer = Sanitizer::default();
+
+        sanitizer.report(0x200, Issue::FlagOperand);
+        sanitizer.report(0x200, Issue::FlagOperand);
+        sanitizer.report(0x202, Issue::FlagOperand);
+        assert_eq!(2, sanitizer.diagnostics().len());
+        assert_eq!(2, sanitizer.take_diagnostics().len());
+        assert!(sanitizer.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn display() {
+        let diagnostic = Diagnostic {
+            pc: 0x20A,
+            issue: Issue::OddJump(0x301),
+        };
+        assert_eq!("0x20A: jump to odd address 0x301", diagnostic.to_string());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{exception::Exception, key::Latches, ram::RAM_SIZE, Address, Byte, Row, SCREEN_HEIGHT};
+
+/// Prefix of the serialized states
+const MAGIC: &[u8; 4] = b"OITO";
+/// Version of the layout of the serialized states
+const VERSION: Byte = 2;
+/// Maximum number of addresses in the stack
+const STACK_SIZE: usize = 16;
+/// Length of the serialized states
+const LEN: usize = MAGIC.len()
+    + 1 // version
+    + RAM_SIZE
+    + 16 // V-Registers
+    + 2 * 2 // I and PC
+    + 1 + STACK_SIZE * 2 // depth and content of the stack
+    + 2 // timers
+    + SCREEN_HEIGHT * 8 // rows
+    + 2 // keys
+    + 3 * 2 + 1 // latched presses, releases and expiring presses, and wait for a release
+    + 8 // instructions
+    + 1 + 32 + 16; // seed and position of the random numbers, if seeded
+
+/// Snapshot of the whole machine, restorable in an emulator with the same settings.
+/// It includes the latched key taps, the wait of `Fx0A` and the position of the seeded random numbers.
+/// The configuration, the breakpoints, the sanitizer and the queued key events are not part of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct State {
+    pub(crate) ram: [Byte; RAM_SIZE],
+    pub(crate) v: [Byte; 16],
+    pub(crate) i: Address,
+    pub(crate) pc: Address,
+    pub(crate) stack: [Address; STACK_SIZE],
+    pub(crate) depth: usize,
+    pub(crate) dt: Byte,
+    pub(crate) st: Byte,
+    pub(crate) rows: [Row; SCREEN_HEIGHT],
+    pub(crate) keys: u16,
+    pub(crate) latches: Latches,
+    pub(crate) executed: u64,
+    pub(crate) rng: Option<([u8; 32], u128)>,
+}
+
+impl State {
+    /// Length of the states serialized with [to_bytes](State::to_bytes)
+    pub const LEN: usize = LEN;
+
+    /// Returns the state serialized in a binary format, with the multibyte values in big endian
+    pub fn to_bytes(&self) -> [u8; LEN] {
+        let mut bytes = [0; LEN];
+        let mut writer = Writer(&mut bytes);
+        writer.put(MAGIC);
+        writer.put(&[VERSION]);
+        writer.put(&self.ram);
+        writer.put(&self.v);
+        writer.put(&self.i.to_be_bytes());
+        writer.put(&self.pc.to_be_bytes());
+        writer.put(&[self.depth as u8]);
+        for address in self.stack {
+            writer.put(&address.to_be_bytes());
+        }
+        writer.put(&[self.dt, self.st]);
+        for row in self.rows {
+            writer.put(&row.to_be_bytes());
+        }
+        writer.put(&self.keys.to_be_bytes());
+        writer.put(&self.latches.pressed.to_be_bytes());
+        writer.put(&self.latches.released.to_be_bytes());
+        writer.put(&self.latches.expiring.to_be_bytes());
+        writer.put(&[self.latches.waiting_release as u8]);
+        writer.put(&self.executed.to_be_bytes());
+        let (seed, position) = self.rng.unwrap_or_default();
+        writer.put(&[self.rng.is_some() as u8]);
+        writer.put(&seed);
+        writer.put(&position.to_be_bytes());
+        bytes
+    }
+
+    /// Returns the addresses in the stack, from the bottom to the top
+    pub(crate) fn stack(&self) -> &[Address] {
+        &self.stack[..self.depth]
+    }
+}
+
+impl TryFrom<&[u8]> for State {
+    type Error = Exception;
+
+    /// Returns the state serialized with [to_bytes](State::to_bytes)
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != LEN || &bytes[..MAGIC.len()] != MAGIC || bytes[MAGIC.len()] != VERSION {
+            return Err(Exception::InvalidState);
+        }
+        let mut reader = Reader(&bytes[MAGIC.len() + 1..]);
+        let ram = reader.array();
+        let mut v = [0; 16];
+        v.copy_from_slice(reader.take(16));
+        let i = Address::from_be_bytes(reader.array());
+        let pc = Address::from_be_bytes(reader.array());
+        let depth = reader.take(1)[0] as usize;
+        if depth >= STACK_SIZE {
+            return Err(Exception::InvalidState);
+        }
+        let mut stack = [0; STACK_SIZE];
+        for address in stack.iter_mut() {
+            *address = Address::from_be_bytes(reader.array());
+        }
+        stack[depth..].fill(0);
+        let [dt, st] = reader.array();
+        let mut rows = [0; SCREEN_HEIGHT];
+        for row in rows.iter_mut() {
+            *row = Row::from_be_bytes(reader.array());
+        }
+        let keys = u16::from_be_bytes(reader.array());
+        let latches = Latches {
+            pressed: u16::from_be_bytes(reader.array()),
+            released: u16::from_be_bytes(reader.array()),
+            expiring: u16::from_be_bytes(reader.array()),
+            waiting_release: reader.flag()?,
+        };
+        let executed = u64::from_be_bytes(reader.array());
+        let seeded = reader.flag()?;
+        let seed = reader.array();
+        let position = u128::from_be_bytes(reader.array());
+        Ok(Self {
+            ram,
+            v,
+            i,
+            pc,
+            stack,
+            depth,
+            dt,
+            st,
+            rows,
+            keys,
+            latches,
+            executed,
+            rng: seeded.then_some((seed, position)),
+        })
+    }
+}
+
+/// Cursor over the bytes being serialized, the length is known beforehand
+struct Writer<'a>(&'a mut [u8]);
+
+impl Writer<'_> {
+    /// Writes the bytes next
+    fn put(&mut self, bytes: &[u8]) {
+        let (target, rest) = core::mem::take(&mut self.0).split_at_mut(bytes.len());
+        target.copy_from_slice(bytes);
+        self.0 = rest;
+    }
+}
+
+/// Cursor over the serialized bytes, the length is checked before reading
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Returns the next bytes
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        taken
+    }
+
+    /// Returns the next bytes as an array
+    fn array<const N: usize>(&mut self) -> [u8; N] {
+        self.take(N).try_into().unwrap()
+    }
+
+    /// Returns the next byte as a boolean, failing if it's neither zero nor one
+    fn flag(&mut self) -> Result<bool, Exception> {
+        match self.take(1)[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Exception::InvalidState),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{exception::Exception, key::Latches, ram::RAM_SIZE, SCREEN_HEIGHT};
+
+    use super::{State, LEN, STACK_SIZE};
+
+    fn state() -> State {
+        let mut rows = [0; SCREEN_HEIGHT];
+        rows[3] = 0xF000_0000_0000_0001;
+        let mut ram = [0; RAM_SIZE];
+        for (i, byte) in ram.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut stack = [0; STACK_SIZE];
+        stack[..2].copy_from_slice(&[0x204, 0x208]);
+        State {
+            ram,
+            v: [7; 16],
+            i: 0x300,
+            pc: 0x20A,
+            stack,
+            depth: 2,
+            dt: 3,
+            st: 4,
+            rows,
+            keys: 0x0400,
+            latches: Latches {
+                pressed: 0x0002,
+                released: 0x0400,
+                expiring: 0x0002,
+                waiting_release: true,
+            },
+            executed: 1234,
+            rng: Some(([9; 32], 77)),
+        }
+    }
+
+    #[test]
+    fn bytes() {
+        let bytes = state().to_bytes();
+        assert_eq!(LEN, bytes.len());
+        assert_eq!(b"OITO", &bytes[..4]);
+        assert_eq!(Ok(state()), State::try_from(bytes.as_slice()));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let bytes = state().to_bytes();
+        assert_eq!(Err(Exception::InvalidState), State::try_from(&bytes[1..]));
+        let mut wrong_version = bytes;
+        wrong_version[4] = 0;
+        assert_eq!(
+            Err(Exception::InvalidState),
+            State::try_from(wrong_version.as_slice())
+        );
+        let mut overflowed = bytes;
+        overflowed[5 + RAM_SIZE + 16 + 4] = 16;
+        assert_eq!(
+            Err(Exception::InvalidState),
+            State::try_from(overflowed.as_slice())
+        );
+        let mut wrong_flag = bytes;
+        wrong_flag[LEN - 49] = 2;
+        assert_eq!(
+            Err(Exception::InvalidState),
+            State::try_from(wrong_flag.as_slice())
+        );
+    }
+}
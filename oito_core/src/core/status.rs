@@ -0,0 +1,74 @@
+use core::fmt::Display;
+
+use super::debug::Break;
+
+/// Execution state of the emulator after the last tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Status {
+    /// Running the ROM
+    #[default]
+    Running,
+    /// Waiting for a key press to continue. Without an input source the ROM is halted.
+    WaitingKey,
+    /// Stopped by the ROM, it won't run anything else
+    Halted(Halt),
+    /// Stopped after reaching one of the configured limits
+    LimitReached(Limit),
+    /// Stopped by a breakpoint or watchpoint. The next tick resumes the execution.
+    Break(Break),
+}
+
+impl Status {
+    /// Returns true if the emulator won't run more instructions
+    pub fn is_stopped(&self) -> bool {
+        matches!(self, Status::Halted(_) | Status::LimitReached(_))
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Status::Running => write!(f, "running"),
+            Status::WaitingKey => write!(f, "waiting key"),
+            Status::Halted(Halt::JumpToSelf) => write!(f, "halted jumping to itself"),
+            Status::Halted(Halt::Exit) => write!(f, "halted by exit"),
+            Status::LimitReached(Limit::Instructions) => write!(f, "instruction limit reached"),
+            Status::LimitReached(Limit::Time) => write!(f, "time limit reached"),
+            Status::Break(stop) => write!(f, "stopped by {stop}"),
+        }
+    }
+}
+
+/// Ways of the ROMs to stop running
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Halt {
+    /// Jumping to the same instruction forever, usually at the end of the test ROMs
+    JumpToSelf,
+    /// Running the SCHIP exit instruction
+    Exit,
+}
+
+/// Execution limits that can be configured
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// Maximum number of instructions
+    Instructions,
+    /// Maximum running time
+    Time,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::debug::Break;
+
+    use super::{Halt, Limit, Status};
+
+    #[test]
+    fn is_stopped() {
+        assert!(!Status::Running.is_stopped());
+        assert!(!Status::WaitingKey.is_stopped());
+        assert!(Status::Halted(Halt::Exit).is_stopped());
+        assert!(Status::LimitReached(Limit::Time).is_stopped());
+        assert!(!Status::Break(Break::Breakpoint(0x200)).is_stopped());
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{BitAnd, BitOr, BitXor, Shl, Shr};
+use core::ops::{BitAnd, BitOr, BitXor, Shl, Shr};
 
 use num_traits::{
     ops::overflowing::{OverflowingAdd, OverflowingSub},
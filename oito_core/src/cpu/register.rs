@@ -1,4 +1,4 @@
-use std::fmt::{Debug, LowerHex};
+use core::fmt::{Debug, LowerHex};
 
 use crate::{Address, Byte};
 
@@ -41,7 +41,7 @@ impl Default for VRegister {
 }
 
 impl<T: LowerHex> Debug for Register<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_fmt(format_args!("Register: [{:#x}]", &self.0))
     }
 }
@@ -54,6 +54,8 @@ impl<T: PartialEq> PartialEq<T> for Register<T> {
 
 #[cfg(test)]
 mod test {
+    use alloc::format;
+
     use crate::cpu::register::{IRegister, Register};
 
     use super::VRegister;
@@ -92,6 +94,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::op_ref)]
     fn eq_reg() {
         let vx = IRegister::default();
         let mut vy = Register(1u16);
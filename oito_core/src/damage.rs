@@ -0,0 +1,153 @@
+use crate::{PackedRow, Row, SCREEN_HEIGHT};
+
+/// Mask with one bit per row of the screen, the least significant bit is the top row
+type RowMask = u64;
+
+// The damaged rows must fit in the mask
+const _: () = assert!(SCREEN_HEIGHT <= RowMask::BITS as usize);
+
+/// Rectangle of the screen in pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    /// Leftmost column
+    pub x: usize,
+    /// Top row
+    pub y: usize,
+    /// Number of columns
+    pub width: usize,
+    /// Number of rows
+    pub height: usize,
+}
+
+/// Region of the screen changed by the executed instructions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Damage<R: PackedRow = Row> {
+    /// Changed rows, one bit per row with the top row as the least significant bit
+    rows: RowMask,
+    /// Changed columns, packed like the rows of the frame buffer
+    columns: R,
+}
+
+impl Damage {
+    /// Damage covering the whole screen
+    pub const FULL: Self = Self::full();
+}
+
+impl<R: PackedRow> Damage<R> {
+    /// Returns the damage covering the whole screen
+    pub(crate) const fn full() -> Self {
+        Self {
+            rows: RowMask::MAX >> (RowMask::BITS as usize - SCREEN_HEIGHT),
+            columns: R::FULL,
+        }
+    }
+
+    /// Returns true if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Returns true if the specified row changed
+    pub fn contains_row(&self, y: usize) -> bool {
+        self.rows & (1 << y) != 0
+    }
+
+    /// Returns the indices of the changed rows
+    pub fn rows(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..SCREEN_HEIGHT).filter(|y| self.contains_row(*y))
+    }
+
+    /// Returns the smallest rectangle containing every changed pixel, if any
+    pub fn bounds(&self) -> Option<Rect> {
+        if self.is_empty() {
+            return None;
+        }
+        let top = self.rows.trailing_zeros() as usize;
+        let bottom = (RowMask::BITS - 1 - self.rows.leading_zeros()) as usize;
+        let left = self.columns.first_lit()?;
+        let right = self.columns.last_lit()?;
+        Some(Rect {
+            x: left,
+            y: top,
+            width: right - left + 1,
+            height: bottom - top + 1,
+        })
+    }
+
+    /// Marks the columns of the specified row as changed
+    pub(crate) fn mark(&mut self, y: usize, columns: R) {
+        if columns != R::EMPTY {
+            self.rows |= 1 << y;
+            self.columns |= columns;
+        }
+    }
+}
+
+impl<R: PackedRow> Default for Damage<R> {
+    fn default() -> Self {
+        Self {
+            rows: 0,
+            columns: R::EMPTY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{vec, vec::Vec};
+
+    use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+    use super::{Damage, Rect};
+
+    #[test]
+    fn mark() {
+        let mut damage: Damage = Damage::default();
+        assert!(damage.is_empty());
+
+        damage.mark(3, 0);
+        assert!(damage.is_empty());
+
+        damage.mark(3, 0x0F00_0000_0000_0000);
+        assert!(!damage.is_empty());
+        assert!(damage.contains_row(3));
+        assert!(!damage.contains_row(4));
+    }
+
+    #[test]
+    fn rows() {
+        let mut damage: Damage = Damage::default();
+        damage.mark(7, 1);
+        damage.mark(2, 1);
+
+        assert_eq!(vec![2, 7], damage.rows().collect::<Vec<_>>());
+        assert_eq!(SCREEN_HEIGHT, Damage::FULL.rows().count());
+    }
+
+    #[test]
+    fn bounds() {
+        let mut damage: Damage = Damage::default();
+        assert!(damage.bounds().is_none());
+
+        damage.mark(3, 0x0F00_0000_0000_0000);
+        damage.mark(5, 0x0000_0000_0000_FF00);
+        assert_eq!(
+            Some(Rect {
+                x: 4,
+                y: 3,
+                width: 52,
+                height: 3
+            }),
+            damage.bounds()
+        );
+        assert_eq!(
+            Some(Rect {
+                x: 0,
+                y: 0,
+                width: SCREEN_WIDTH,
+                height: SCREEN_HEIGHT
+            }),
+            Damage::FULL.bounds()
+        );
+    }
+}
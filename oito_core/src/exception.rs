@@ -10,6 +10,10 @@ pub enum Exception {
     StackOverflow,
     #[error("Stack underflow")]
     StackUnderflow,
+    #[error("Write into protected memory: {0:04x}")]
+    ProtectedWrite(Address),
     #[error("Wrong OpCode: {0:04x}")]
     WrongOpCode(OpCode),
+    #[error("Invalid saved state")]
+    InvalidState,
 }
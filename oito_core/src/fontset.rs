@@ -1,9 +1,22 @@
-use crate::{Address, Byte};
+use core::str::FromStr;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::{Address, Byte, ParseError};
 
 const FONT_BYTE_LENGTH: usize = 5;
+const BIG_FONT_BYTE_LENGTH: usize = 10;
 const FONT_NUMBER: usize = 16;
 const FONTSET_SIZE: usize = FONT_BYTE_LENGTH * FONT_NUMBER;
+/// Size of the sixteen big sprites of the SCHIP fonts
+pub const BIG_FONTSET_SIZE: usize = BIG_FONT_BYTE_LENGTH * FONT_NUMBER;
+/// Address where the big font is loaded, right after the small one
+pub const BIG_FONT_ADDRESS: Address = FONTSET_SIZE as Address;
+/// End of the memory reserved to the fonts
+pub const FONTS_END: Address = BIG_FONT_ADDRESS + BIG_FONTSET_SIZE as Address;
 
+/// Font used by most of the modern interpreters
 pub const FONTSET: [u8; FONTSET_SIZE] = [
     0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
     0x20, 0x60, 0x20, 0x20, 0x70, // 1
@@ -23,6 +36,245 @@ pub const FONTSET: [u8; FONTSET_SIZE] = [
     0xF0, 0x80, 0xF0, 0x80, 0x80, // F
 ];
 
+/// Font of the COSMAC VIP interpreter
+const VIP_FONTSET: [u8; FONTSET_SIZE] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x60, 0x20, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0xA0, 0xA0, 0xF0, 0x20, 0x20, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x10, 0x10, 0x10, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xF0, 0x50, 0x70, 0x50, 0xF0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xF0, 0x50, 0x50, 0x50, 0xF0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+/// Font of the DREAM 6800 interpreter
+const DREAM_6800_FONTSET: [u8; FONTSET_SIZE] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0, // 0
+    0x40, 0x40, 0x40, 0x40, 0x40, // 1
+    0xE0, 0x20, 0xE0, 0x80, 0xE0, // 2
+    0xE0, 0x20, 0xE0, 0x20, 0xE0, // 3
+    0x80, 0xA0, 0xA0, 0xE0, 0x20, // 4
+    0xE0, 0x80, 0xE0, 0x20, 0xE0, // 5
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0, // 6
+    0xE0, 0x20, 0x20, 0x20, 0x20, // 7
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0, // 8
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0, // 9
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0xC0, 0xA0, 0xE0, 0xA0, 0xC0, // B
+    0xE0, 0x80, 0x80, 0x80, 0xE0, // C
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0, // D
+    0xE0, 0x80, 0xE0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+/// Font of the ETI-660 interpreter
+const ETI_660_FONTSET: [u8; FONTSET_SIZE] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0, // 0
+    0x20, 0x20, 0x20, 0x20, 0x20, // 1
+    0xE0, 0x20, 0xE0, 0x80, 0xE0, // 2
+    0xE0, 0x20, 0xE0, 0x20, 0xE0, // 3
+    0xA0, 0xA0, 0xE0, 0x20, 0x20, // 4
+    0xE0, 0x80, 0xE0, 0x20, 0xE0, // 5
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0, // 6
+    0xE0, 0x20, 0x20, 0x20, 0x20, // 7
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0, // 8
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0, // 9
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0x80, 0x80, 0xE0, 0xA0, 0xE0, // B
+    0xE0, 0x80, 0x80, 0x80, 0xE0, // C
+    0x20, 0x20, 0xE0, 0xA0, 0xE0, // D
+    0xE0, 0x80, 0xE0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+/// Font of the Fish'n'Chips interpreter
+const FISH_N_CHIPS_FONTSET: [u8; FONTSET_SIZE] = [
+    0x60, 0xA0, 0xA0, 0xA0, 0xC0, // 0
+    0x40, 0xC0, 0x40, 0x40, 0xE0, // 1
+    0xC0, 0x20, 0x40, 0x80, 0xE0, // 2
+    0xC0, 0x20, 0x40, 0x20, 0xC0, // 3
+    0x20, 0xA0, 0xE0, 0x20, 0x20, // 4
+    0xE0, 0x80, 0xC0, 0x20, 0xC0, // 5
+    0x40, 0x80, 0xC0, 0xA0, 0x40, // 6
+    0xE0, 0x20, 0x60, 0x40, 0x40, // 7
+    0x40, 0xA0, 0x40, 0xA0, 0x40, // 8
+    0x40, 0xA0, 0x60, 0x20, 0x40, // 9
+    0x40, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0xC0, 0xA0, 0xC0, 0xA0, 0xC0, // B
+    0x60, 0x80, 0x80, 0x80, 0x60, // C
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0, // D
+    0xE0, 0x80, 0xC0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+/// Big digits of the SCHIP interpreter
+const SCHIP_BIG_FONTSET: [u8; BIG_FONTSET_SIZE] = [
+    0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+    0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+    0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+    0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+    0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+    0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+    0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+    0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, // F
+];
+
+/// Sprites of the hexadecimal digits loaded at the start of the memory
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Font {
+    /// Font used by most of the modern interpreters
+    #[default]
+    Modern,
+    /// Font of the COSMAC VIP
+    Vip,
+    /// Font of the DREAM 6800
+    Dream6800,
+    /// Font of the ETI-660
+    Eti660,
+    /// Font of Fish'n'Chips
+    FishNChips,
+    /// Modern font with the big digits of SCHIP
+    Schip,
+    /// Custom sixteen small sprites of five bytes and, optionally, sixteen big sprites of ten bytes
+    #[cfg(feature = "alloc")]
+    Custom {
+        small: Box<[Byte; FONTSET_SIZE]>,
+        big: Option<Box<[Byte; BIG_FONTSET_SIZE]>>,
+    },
+}
+
+impl Font {
+    /// Returns a custom font with the small sprites followed, optionally, by the big ones.
+    /// Returns None if the content doesn't have the size of the small font or of both fonts.
+    #[cfg(feature = "alloc")]
+    pub fn from_bytes(content: &[Byte]) -> Option<Self> {
+        let (small, big) = match content.len() {
+            FONTSET_SIZE => (content, None),
+            size if size == FONTSET_SIZE + BIG_FONTSET_SIZE => {
+                let (small, big) = content.split_at(FONTSET_SIZE);
+                (small, Some(Box::new(big.try_into().ok()?)))
+            }
+            _ => return None,
+        };
+        Some(Font::Custom {
+            small: Box::new(small.try_into().ok()?),
+            big,
+        })
+    }
+
+    /// Returns the small sprites of the font
+    pub fn small(&self) -> &[Byte] {
+        match self {
+            Font::Modern | Font::Schip => &FONTSET,
+            Font::Vip => &VIP_FONTSET,
+            Font::Dream6800 => &DREAM_6800_FONTSET,
+            Font::Eti660 => &ETI_660_FONTSET,
+            Font::FishNChips => &FISH_N_CHIPS_FONTSET,
+            #[cfg(feature = "alloc")]
+            Font::Custom { small, .. } => small.as_slice(),
+        }
+    }
+
+    /// Returns the big sprites of the font, if it has them
+    pub fn big(&self) -> Option<&[Byte]> {
+        match self {
+            Font::Schip => Some(&SCHIP_BIG_FONTSET),
+            #[cfg(feature = "alloc")]
+            Font::Custom { big, .. } => big.as_deref().map(|big| big.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Font {
+    type Err = ParseError;
+
+    /// Parses a built-in font: `modern`, `vip`, `dream6800`, `eti660`, `fishnchips` or `schip`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "modern" => Ok(Font::Modern),
+            "vip" => Ok(Font::Vip),
+            "dream6800" => Ok(Font::Dream6800),
+            "eti660" => Ok(Font::Eti660),
+            "fishnchips" => Ok(Font::FishNChips),
+            "schip" => Ok(Font::Schip),
+            _ => Err(parse_error!("invalid font: {s}")),
+        }
+    }
+}
+
+/// Returns the address of the small sprite of the character
 pub fn location(character: Byte) -> Address {
-    (character * FONT_BYTE_LENGTH as Byte) as Address
+    (character as Address & 0xF) * FONT_BYTE_LENGTH as Address
+}
+
+/// Returns the address of the big sprite of the character
+pub fn big_location(character: Byte) -> Address {
+    BIG_FONT_ADDRESS + (character as Address & 0xF) * BIG_FONT_BYTE_LENGTH as Address
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Font, FONTSET, SCHIP_BIG_FONTSET};
+    #[cfg(feature = "alloc")]
+    use super::{BIG_FONTSET_SIZE, FONTSET_SIZE};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn from_bytes() {
+        let small = [0x11; FONTSET_SIZE];
+        let big = [0x22; BIG_FONTSET_SIZE];
+
+        let font = Font::from_bytes(&small).unwrap();
+        assert_eq!(&small, font.small());
+        assert!(font.big().is_none());
+
+        let font = Font::from_bytes(&[small.as_slice(), big.as_slice()].concat()).unwrap();
+        assert_eq!(&small, font.small());
+        assert_eq!(Some(big.as_slice()), font.big());
+
+        assert!(Font::from_bytes(&[0x11; FONTSET_SIZE + 1]).is_none());
+    }
+
+    #[test]
+    fn sprites() {
+        assert_eq!(&FONTSET, Font::default().small());
+        assert!(Font::default().big().is_none());
+        assert_eq!(&FONTSET, Font::Schip.small());
+        assert_eq!(Some(SCHIP_BIG_FONTSET.as_slice()), Font::Schip.big());
+        assert_eq!(&[0x60, 0x20, 0x20, 0x20, 0x70], &Font::Vip.small()[5..10]);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Font::Modern), "modern".parse());
+        assert_eq!(Ok(Font::Dream6800), "dream6800".parse());
+        assert_eq!(Ok(Font::FishNChips), "fishnchips".parse());
+        assert!("chip48".parse::<Font>().is_err());
+    }
+
+    #[test]
+    fn location() {
+        assert_eq!(0x00, super::location(0x0));
+        assert_eq!(0x4B, super::location(0xF));
+        assert_eq!(0x50, super::big_location(0x0));
+        assert_eq!(0xE6, super::big_location(0xF));
+    }
 }
@@ -7,12 +7,14 @@ const ADDRESS_MASK: u16 = 0x0FFF;
 const BYTE_MASK: u16 = 0x00FF;
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Instruction {
     /// 00E0 - Clear screen: `cls`
     CLS,
     /// 00EE - Return from subroutine: `return;`
     RET,
+    /// 00FD - Exit the interpreter (SCHIP): `exit()`
+    EXIT,
     /// 0nnn - SYS jump to address. Legacy call.
     SYS(Address),
     /// 1nnn - Jump to address: `goto nnn`
@@ -73,6 +75,8 @@ pub enum Instruction {
     ADDri(RegIndex),
     /// Fx29 - Load sprite loaded at Vx memory location into I: `I = sprite_addr[Vx]`
     LDmi(RegIndex),
+    /// Fx30 - Load big sprite loaded at Vx memory location into I, from SCHIP: `I = big_sprite_addr[Vx]`
+    LDhi(RegIndex),
     /// Fx33 - Store BCD representation of Vx in memory locations I, I+1, and I+2
     LDrm(RegIndex),
     /// Fx55 - Store registers [V0, Vx] in memory starting at I: `reg_dump(Vx, &I)`
@@ -81,6 +85,29 @@ pub enum Instruction {
     LDmv(RegIndex),
 }
 
+impl Instruction {
+    /// Returns true if the instruction can move the Program Counter somewhere else than the
+    /// following instruction
+    pub fn is_terminator(&self) -> bool {
+        use Instruction::*;
+        matches!(
+            self,
+            RET | EXIT
+                | SYS(_)
+                | JP(_)
+                | CALL(_)
+                | SErb { .. }
+                | SNErb { .. }
+                | SErr { .. }
+                | SNErr { .. }
+                | JPr(_)
+                | SKP(_)
+                | SKNP(_)
+                | LDkr(_)
+        )
+    }
+}
+
 impl TryFrom<OpCode> for Instruction {
     type Error = Exception;
 
@@ -89,6 +116,7 @@ impl TryFrom<OpCode> for Instruction {
         match split(value) {
             (0x0, 0x0, 0xE, 0x0) => Ok(CLS),
             (0x0, 0x0, 0xE, 0xE) => Ok(RET),
+            (0x0, 0x0, 0xF, 0xD) => Ok(EXIT),
             (0x0, ..) => Ok(SYS(value & ADDRESS_MASK)),
             (0x1, ..) => Ok(JP(value & ADDRESS_MASK)),
             (0x2, ..) => Ok(CALL(value & ADDRESS_MASK)),
@@ -165,6 +193,7 @@ impl TryFrom<OpCode> for Instruction {
             (0xF, vx, 0x1, 0x8) => Ok(LDrs(vx as RegIndex)),
             (0xF, vx, 0x1, 0xE) => Ok(ADDri(vx as RegIndex)),
             (0xF, vx, 0x2, 0x9) => Ok(LDmi(vx as RegIndex)),
+            (0xF, vx, 0x3, 0x0) => Ok(LDhi(vx as RegIndex)),
             (0xF, vx, 0x3, 0x3) => Ok(LDrm(vx as RegIndex)),
             (0xF, vx, 0x5, 0x5) => Ok(LDvm(vx as RegIndex)),
             (0xF, vx, 0x6, 0x5) => Ok(LDmv(vx as RegIndex)),
@@ -193,6 +222,7 @@ mod test {
     fn try_from() {
         assert_eq!(Instruction::CLS, Instruction::try_from(0x00E0).unwrap());
         assert_eq!(Instruction::RET, Instruction::try_from(0x00EE).unwrap());
+        assert_eq!(Instruction::EXIT, Instruction::try_from(0x00FD).unwrap());
         assert_eq!(
             Instruction::SYS(0xC0A),
             Instruction::try_from(0x0C0A).unwrap()
@@ -286,6 +316,7 @@ mod test {
             Instruction::try_from(0xF71E).unwrap()
         );
         assert_eq!(Instruction::LDmi(8), Instruction::try_from(0xF829).unwrap());
+        assert_eq!(Instruction::LDhi(8), Instruction::try_from(0xF830).unwrap());
         assert_eq!(
             Instruction::LDrm(10),
             Instruction::try_from(0xFA33).unwrap()
@@ -304,6 +335,16 @@ mod test {
         );
     }
 
+    #[test]
+    fn is_terminator() {
+        assert!(Instruction::JP(0x200).is_terminator());
+        assert!(Instruction::SKP(0).is_terminator());
+        assert!(Instruction::LDkr(0).is_terminator());
+        assert!(Instruction::EXIT.is_terminator());
+        assert!(!Instruction::CLS.is_terminator());
+        assert!(!Instruction::DRW { x: 0, y: 0, n: 1 }.is_terminator());
+    }
+
     #[test]
     fn split() {
         assert_eq!((0x2, 0xA, 0x9, 0x0), super::split(0x2A90))
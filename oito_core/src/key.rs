@@ -1,8 +1,13 @@
-pub(crate) use keymap::KeyMap;
+use crate::ParseError;
 
+pub use event::KeyEvent;
+pub(crate) use keymap::{KeyMap, Latches};
+
+mod event;
 mod keymap;
 
 /// Keys in the Chip8 system
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Key {
     Zero,
     One,
@@ -24,8 +29,57 @@ pub enum Key {
 
 impl Key {
     pub const SIZE: usize = 16;
+    /// Every key sorted by value
+    pub const ALL: [Key; Key::SIZE] = [
+        Key::Zero,
+        Key::One,
+        Key::Two,
+        Key::Three,
+        Key::Four,
+        Key::Five,
+        Key::Six,
+        Key::Seven,
+        Key::Eight,
+        Key::Nine,
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+    ];
+
+    /// Returns an iterator over every key sorted by value
+    pub fn iter() -> impl Iterator<Item = Key> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns the bit of the key in the masks of the keys state, bit N is the key of value N
+    pub fn mask(self) -> u16 {
+        1 << u8::from(self)
+    }
 }
 
+impl TryFrom<u8> for Key {
+    type Error = ParseError;
+
+    /// Returns the key with the specified hexadecimal value
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(value as usize)
+            .copied()
+            .ok_or_else(|| parse_error!("invalid key: {value:#X}"))
+    }
+}
+
+impl From<Key> for u8 {
+    fn from(key: Key) -> Self {
+        let index: usize = key.into();
+        index as u8
+    }
+}
+
+#[allow(clippy::from_over_into)]
 impl Into<usize> for Key {
     fn into(self) -> usize {
         use Key::*;
@@ -49,3 +103,31 @@ impl Into<usize> for Key {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Key;
+
+    #[test]
+    fn try_from() {
+        assert_eq!(Ok(Key::Zero), Key::try_from(0x0));
+        assert_eq!(Ok(Key::A), Key::try_from(0xA));
+        assert_eq!(Ok(Key::F), Key::try_from(0xF));
+        assert!(Key::try_from(0x10).is_err());
+    }
+
+    #[test]
+    fn iter() {
+        assert_eq!(Key::SIZE, Key::iter().count());
+        for (value, key) in Key::iter().enumerate() {
+            assert_eq!(value as u8, u8::from(key));
+        }
+    }
+
+    #[test]
+    fn mask() {
+        assert_eq!(0x0001, Key::Zero.mask());
+        assert_eq!(0x0400, Key::A.mask());
+        assert_eq!(0x8000, Key::F.mask());
+    }
+}
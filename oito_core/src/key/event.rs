@@ -0,0 +1,32 @@
+use super::Key;
+
+/// Press or release of a key, applied when the emulator reaches the specified instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    /// Key pressed or released
+    pub key: Key,
+    /// True if the key is pressed, false if it's released
+    pub pressed: bool,
+    /// Number of instructions run by the emulator when the event happens
+    pub time: u64,
+}
+
+impl KeyEvent {
+    /// Returns the press of the key at the specified time
+    pub fn press(key: Key, time: u64) -> Self {
+        Self {
+            key,
+            pressed: true,
+            time,
+        }
+    }
+
+    /// Returns the release of the key at the specified time
+    pub fn release(key: Key, time: u64) -> Self {
+        Self {
+            key,
+            pressed: false,
+            time,
+        }
+    }
+}
@@ -1,38 +1,172 @@
-use std::{fmt::Debug, ops::Index};
+use core::{fmt::Debug, ops::Index};
 
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+
+#[cfg(feature = "alloc")]
+use crate::key::KeyEvent;
 use crate::{key::Key, Byte};
 
-/// Mapping of the keys and their state as pressed or not pressed
+/// Mapping of the keys and their state as pressed or not pressed.
+/// Presses and releases are latched until an instruction observes them,
+/// so the taps shorter than the instructions checking the keys are not lost.
+/// The presses not observed expire at the second frame after them, so old taps don't fire much later.
 pub struct KeyMap {
     key_pressed: [bool; Key::SIZE],
+    /// Mask of the keys pressed since an instruction last checked them
+    pressed: u16,
+    /// Mask of the keys released since an instruction last checked them
+    released: u16,
+    /// Mask of the presses latched before the last frame, forgotten at the next one
+    expiring: u16,
+    /// True while an instruction is waiting for a key release
+    waiting_release: bool,
+    /// Events to apply, sorted by time
+    #[cfg(feature = "alloc")]
+    events: VecDeque<KeyEvent>,
+}
+
+/// Key changes latched by the [KeyMap] until an instruction observes them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Latches {
+    /// Mask of the keys pressed since an instruction last checked them
+    pub pressed: u16,
+    /// Mask of the keys released since an instruction last checked them
+    pub released: u16,
+    /// Mask of the presses latched before the last frame, forgotten at the next one
+    pub expiring: u16,
+    /// True while an instruction is waiting for a key release
+    pub waiting_release: bool,
 }
 
 impl KeyMap {
-    /// Returns the first key pressed if any
-    pub fn get_key_pressed(&self) -> Option<usize> {
-        for (i, key) in self.key_pressed.into_iter().enumerate() {
-            if key {
-                return Some(i);
-            }
+    /// Returns the first key pressed or tapped since the last check, if any, and forgets its tap
+    pub fn take_key_pressed(&mut self) -> Option<usize> {
+        let key = lowest(self.pressed | self.mask()).map(|key| key as usize)?;
+        self.pressed &= !(1 << key);
+        Some(key)
+    }
+
+    /// Returns the first key released since the wait for a release started, if any.
+    /// The first call starts the wait, forgetting the previous releases.
+    pub fn take_key_released(&mut self) -> Option<usize> {
+        if !self.waiting_release {
+            self.waiting_release = true;
+            self.released = 0;
         }
-        None
+        let key = lowest(self.released).map(|key| key as usize)?;
+        self.released &= !(1 << key);
+        self.waiting_release = false;
+        Some(key)
+    }
+
+    /// Returns true if the key is pressed or was tapped since the last check, forgetting its tap
+    pub fn take_key(&mut self, index: Byte) -> bool {
+        let index = index as usize % Key::SIZE;
+        let tapped = self.pressed & (1 << index) != 0;
+        self.pressed &= !(1 << index);
+        tapped || self.key_pressed[index]
     }
 
     /// Marks the specified key as pressed
     pub fn press_key(&mut self, key: Key) {
         let index: usize = key.into();
+        if !self.key_pressed[index] {
+            self.pressed |= key.mask();
+        }
         self.key_pressed[index] = true;
     }
 
     /// Marks the specified key as not pressed
     pub fn release_key(&mut self, key: Key) {
         let index: usize = key.into();
+        if self.key_pressed[index] {
+            self.released |= key.mask();
+        }
         self.key_pressed[index] = false;
     }
+
+    /// Returns the state of every key as a mask, bit N is set if the key of value N is pressed
+    pub fn mask(&self) -> u16 {
+        Key::iter()
+            .filter(|key| self.key_pressed[u8::from(*key) as usize])
+            .fold(0, |mask, key| mask | key.mask())
+    }
+
+    /// Presses the keys set in the mask and releases the rest
+    pub fn set_mask(&mut self, mask: u16) {
+        for key in Key::iter() {
+            if mask & key.mask() != 0 {
+                self.press_key(key);
+            } else {
+                self.release_key(key);
+            }
+        }
+    }
+
+    /// Forgets the presses latched before the last frame and starts expiring the rest, called on each frame
+    pub fn expire_taps(&mut self) {
+        self.pressed &= !self.expiring;
+        self.expiring = self.pressed;
+    }
+
+    /// Returns the latched presses and releases not checked yet, and if a release is being awaited
+    pub fn latches(&self) -> Latches {
+        Latches {
+            pressed: self.pressed,
+            released: self.released,
+            expiring: self.expiring,
+            waiting_release: self.waiting_release,
+        }
+    }
+
+    /// Replaces the latched presses and releases and the wait for a release
+    pub fn restore_latches(&mut self, latches: Latches) {
+        self.pressed = latches.pressed;
+        self.released = latches.released;
+        self.expiring = latches.expiring;
+        self.waiting_release = latches.waiting_release;
+    }
+
+    /// Queues the event to apply it when its time is reached
+    #[cfg(feature = "alloc")]
+    pub fn queue(&mut self, event: KeyEvent) {
+        let position = self
+            .events
+            .partition_point(|queued| queued.time <= event.time);
+        self.events.insert(position, event);
+    }
+
+    /// Returns the time of the next queued event, if any
+    #[cfg(feature = "alloc")]
+    pub fn next_event(&self) -> Option<u64> {
+        self.events.front().map(|event| event.time)
+    }
+
+    /// Applies the queued events happening up to the specified time
+    #[cfg(feature = "alloc")]
+    pub fn process(&mut self, time: u64) {
+        while let Some(event) = self.events.front().copied() {
+            if event.time > time {
+                break;
+            }
+            self.events.pop_front();
+            if event.pressed {
+                self.press_key(event.key);
+            } else {
+                self.release_key(event.key);
+            }
+        }
+    }
+}
+
+/// Returns the value of the lowest key set in the mask
+fn lowest(mask: u16) -> Option<u32> {
+    (mask != 0).then(|| mask.trailing_zeros())
 }
 
 impl Debug for KeyMap {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "KeyMap: [ ").unwrap();
         for (i, state) in self.key_pressed.iter().enumerate() {
             write!(f, "{i}: {}, ", if *state { "X" } else { "-" }).unwrap();
@@ -45,6 +179,12 @@ impl Default for KeyMap {
     fn default() -> Self {
         Self {
             key_pressed: [false; Key::SIZE],
+            pressed: 0,
+            released: 0,
+            expiring: 0,
+            waiting_release: false,
+            #[cfg(feature = "alloc")]
+            events: VecDeque::new(),
         }
     }
 }
@@ -60,18 +200,75 @@ impl Index<Byte> for KeyMap {
 #[cfg(test)]
 mod test {
     use crate::key::Key;
+    #[cfg(feature = "alloc")]
+    use crate::key::KeyEvent;
 
     use super::KeyMap;
 
     #[test]
-    fn get_key_pressed() {
+    fn take_key_pressed() {
+        let mut map = KeyMap::default();
+        assert!(map.take_key_pressed().is_none());
+
+        // Taps are latched until taken
+        map.press_key(Key::Seven);
+        map.release_key(Key::Seven);
+        assert_eq!(Some(7), map.take_key_pressed());
+        assert!(map.take_key_pressed().is_none());
+
+        // Held keys are returned until released
+        map.press_key(Key::Three);
+        assert_eq!(Some(3), map.take_key_pressed());
+        assert_eq!(Some(3), map.take_key_pressed());
+    }
+
+    #[test]
+    fn take_key_released() {
+        let mut map = KeyMap::default();
+        // Releases before the wait are ignored
+        map.press_key(Key::Two);
+        map.release_key(Key::Two);
+        map.press_key(Key::Nine);
+        assert!(map.take_key_released().is_none());
+
+        map.release_key(Key::Nine);
+        assert_eq!(Some(9), map.take_key_released());
+        assert!(map.take_key_released().is_none());
+    }
+
+    #[test]
+    fn take_key() {
         let mut map = KeyMap::default();
-        // No key pressed
-        assert!(map.get_key_pressed().is_none());
-        // Key pressed, get lowest
-        map.key_pressed[5] = true;
-        map.key_pressed[2] = true;
-        assert_eq!(2, map.get_key_pressed().unwrap());
+        map.press_key(Key::A);
+        map.release_key(Key::A);
+
+        assert!(map.take_key(0xA));
+        assert!(!map.take_key(0xA));
+        map.press_key(Key::A);
+        assert!(map.take_key(0xA));
+        assert!(map.take_key(0xA));
+    }
+
+    #[test]
+    fn expire_taps() {
+        let mut map = KeyMap::default();
+        map.press_key(Key::A);
+        map.release_key(Key::A);
+        map.expire_taps();
+        assert!(map.take_key(0xA));
+
+        // Taps not observed are forgotten at the second frame
+        map.press_key(Key::A);
+        map.release_key(Key::A);
+        map.expire_taps();
+        map.expire_taps();
+        assert!(!map.take_key(0xA));
+
+        // Held keys don't expire
+        map.press_key(Key::B);
+        map.expire_taps();
+        map.expire_taps();
+        assert!(map.take_key(0xB));
     }
 
     #[test]
@@ -95,6 +292,36 @@ mod test {
         assert!(map.key_pressed[5]);
     }
 
+    #[test]
+    fn mask() {
+        let mut map = KeyMap::default();
+        map.set_mask(0b1000_0100_0000_0001);
+
+        assert_eq!(0b1000_0100_0000_0001, map.mask());
+        assert!(map[0x0] && map[0xA] && map[0xF]);
+        map.set_mask(0x0002);
+        assert_eq!(0x0002, map.mask());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn process() {
+        let mut map = KeyMap::default();
+        map.queue(KeyEvent::release(Key::One, 20));
+        map.queue(KeyEvent::press(Key::One, 10));
+        map.queue(KeyEvent::press(Key::Two, 10));
+        assert_eq!(Some(10), map.next_event());
+
+        map.process(9);
+        assert_eq!(0x0000, map.mask());
+        map.process(10);
+        assert_eq!(0x0006, map.mask());
+        assert_eq!(Some(20), map.next_event());
+        map.process(30);
+        assert_eq!(0x0004, map.mask());
+        assert_eq!(None, map.next_event());
+    }
+
     #[test]
     fn index() {
         let mut map = KeyMap::default();
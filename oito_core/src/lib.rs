@@ -1,11 +1,34 @@
+//! Core of the Oito Chip-8 emulator. It's `no_std` without the default `std` feature,
+//! and doesn't allocate without the `alloc` feature either.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// the tests allocate in every configuration
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+
+/// Returns the [ParseError] describing the invalid input, only formatted with the `alloc` feature
+macro_rules! parse_error {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "alloc")]
+        let error = alloc::format!($($arg)*);
+        #[cfg(not(feature = "alloc"))]
+        let error = $crate::ParseError;
+        error
+    }};
+}
+
+pub mod action;
+pub mod config;
 pub mod core;
+pub mod damage;
+pub mod exception;
+pub mod fontset;
+pub mod instruction;
 pub mod key;
-
-pub(crate) mod exception;
-pub(crate) mod fontset;
+pub mod video;
 
 mod cpu;
-mod instruction;
 mod ram;
 mod stack;
 mod timer;
@@ -21,8 +44,26 @@ pub type Byte = u8;
 pub type RegIndex = u8;
 /// Specification of the type to represent the pixels that will be drawn in the buffer
 pub type Pixel = bool;
+/// Specification of the type holding a packed row of pixels of the screen, the leftmost pixel is the most significant bit
+pub type Row = u64;
 /// Specification of the type representing the ROMs to run
 pub type Rom<'a> = &'a [u8];
+/// Error of the parsing of settings and values: the description of the invalid input with the `alloc` feature,
+/// nothing without it
+#[cfg(feature = "alloc")]
+pub type ParseError = alloc::string::String;
+/// Error of the parsing of settings and values: the description of the invalid input with the `alloc` feature,
+/// nothing without it
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError;
+
+#[cfg(not(feature = "alloc"))]
+impl ::core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "invalid input")
+    }
+}
 
 /// Types that can be masked
 pub trait BitMask {
@@ -35,8 +76,67 @@ impl BitMask for Byte {
     const MOST_SIGNIFICANT_BIT: Self = 0b10000000;
 }
 
+/// Unsigned integers holding a packed row of pixels, one per bit with the leftmost pixel as the most significant bit.
+/// The width of the screen is the width of its rows, so higher resolutions just pick wider rows.
+pub trait PackedRow:
+    Copy
+    + Eq
+    + ::core::fmt::Debug
+    + ::core::ops::BitAnd<Output = Self>
+    + ::core::ops::BitOrAssign
+    + ::core::ops::BitXorAssign
+{
+    /// Number of pixels of the row
+    const WIDTH: usize;
+    /// Row without any pixel lit
+    const EMPTY: Self;
+    /// Row with every pixel lit
+    const FULL: Self;
+
+    /// Returns the row with the pixels of the sprite starting at the column.
+    /// The pixels overflowing the row are placed counting the overflow from the start.
+    fn sprite(sprite: Byte, x: usize) -> Self;
+
+    /// Returns true if the pixel at the column, wrapped to the row, is lit
+    fn is_lit(self, x: usize) -> bool;
+
+    /// Returns the column of the leftmost lit pixel, if any
+    fn first_lit(self) -> Option<usize>;
+
+    /// Returns the column of the rightmost lit pixel, if any
+    fn last_lit(self) -> Option<usize>;
+}
+
+macro_rules! packed_row {
+    ($($row:ty),*) => {$(
+        impl PackedRow for $row {
+            const WIDTH: usize = <$row>::BITS as usize;
+            const EMPTY: Self = 0;
+            const FULL: Self = <$row>::MAX;
+
+            fn sprite(sprite: Byte, x: usize) -> Self {
+                ((sprite as Self) << (Self::BITS - Byte::BITS)).rotate_right((x % Self::WIDTH) as u32)
+            }
+
+            fn is_lit(self, x: usize) -> bool {
+                self & (1 << (Self::WIDTH - 1 - x % Self::WIDTH)) != 0
+            }
+
+            fn first_lit(self) -> Option<usize> {
+                (self != 0).then(|| self.leading_zeros() as usize)
+            }
+
+            fn last_lit(self) -> Option<usize> {
+                (self != 0).then(|| Self::WIDTH - 1 - self.trailing_zeros() as usize)
+            }
+        }
+    )*};
+}
+
+packed_row!(u64, u128);
+
 /// Width of the emmulated screen
-pub const SCREEN_WIDTH: usize = 64;
+pub const SCREEN_WIDTH: usize = Row::WIDTH;
 
 /// Height of the emmulated screen
 pub const SCREEN_HEIGHT: usize = 32;
@@ -1,9 +1,9 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
 
 use crate::{exception::Exception, Address, Byte};
 
 /// 4KB of RAM
-const RAM_SIZE: usize = 4096;
+pub(crate) const RAM_SIZE: usize = 4096;
 const EMPTY_MEM: Byte = 0;
 
 /// Simmulated RAM
@@ -29,6 +29,15 @@ impl Ram {
         }
     }
 
+    /// Returns the content of the memory starting at the specified address
+    pub fn slice(&self, address: Address, len: usize) -> Result<&[Byte], Exception> {
+        let start = address as usize;
+        match start.checked_add(len) {
+            Some(end) if end <= RAM_SIZE => Ok(&self.memory[start..end]),
+            _ => Err(Exception::SegmentationFault(address)),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn set(&mut self, address: Address, value: Byte) {
         self.memory[address as usize] = value;
@@ -36,7 +45,7 @@ impl Ram {
 }
 
 impl Debug for Ram {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for i in 0..16 {
             for j in 0..(RAM_SIZE / 16) {
                 write!(f, "{:?}", self.memory[i * 16 + j]).unwrap();
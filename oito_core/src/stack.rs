@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
 
 use crate::{exception::Exception, Address};
 
@@ -38,6 +38,23 @@ impl Stack {
         }
     }
 
+    /// Returns the number of addresses in the stack
+    pub fn depth(&self) -> usize {
+        self.pointer as usize
+    }
+
+    /// Returns the addresses in the stack, from the bottom to the top
+    pub fn addresses(&self) -> &[Address] {
+        &self.content[..self.pointer as usize]
+    }
+
+    /// Replaces the content of the stack with the addresses, from the bottom to the top
+    pub fn restore(&mut self, addresses: &[Address]) {
+        self.content = [EMPTY; STACK_SIZE];
+        self.content[..addresses.len()].copy_from_slice(addresses);
+        self.pointer = addresses.len() as Address;
+    }
+
     #[cfg(test)]
     pub(crate) fn peek(&self) -> Option<Address> {
         if self.pointer == 0 {
@@ -58,7 +75,7 @@ impl Default for Stack {
 }
 
 impl Debug for Stack {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Stack")
             .field("top", &self.content[self.pointer as usize])
             .finish()
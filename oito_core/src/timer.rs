@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
 
 /// Abstraction of both timers of the Chip8 CPU
 #[derive(Default)]
@@ -27,7 +27,7 @@ impl Timer {
 }
 
 impl Debug for Timer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Timer: [{}]", &self.count)
     }
 }
@@ -0,0 +1,89 @@
+pub use converter::FrameConverter;
+pub use filter::{Filter, FrameFilter};
+pub use palette::{Palette, PixelFormat};
+pub use upscaler::Upscaler;
+
+use core::{fmt::Display, str::FromStr};
+
+use crate::ParseError;
+
+mod converter;
+mod filter;
+mod palette;
+mod upscaler;
+
+/// Color made of its red, green and blue components
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Returns the color between this one and the other specified by the amount,
+    /// being zero this color and the maximum the other one
+    pub fn mix(self, other: Rgb, amount: u8) -> Rgb {
+        let mix = |from: u8, to: u8| {
+            let (from, to, amount) = (from as i32, to as i32, amount as i32);
+            (from + (to - from) * amount / u8::MAX as i32) as u8
+        };
+        Rgb(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = ParseError;
+
+    /// Parses a color in hexadecimal RGB format, like `#FF8000`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if hex.len() != 6 {
+            return Err(parse_error!("invalid color: {s}"));
+        }
+        let component = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+                .ok_or_else(|| parse_error!("invalid color: {s}"))
+        };
+        Ok(Rgb(component(0)?, component(2)?, component(4)?))
+    }
+}
+
+impl Display for Rgb {
+    /// Formats the color in hexadecimal RGB format, like `#ff8000`
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use super::Rgb;
+
+    #[test]
+    fn mix() {
+        let black = Rgb(0, 0, 0);
+        let orange = Rgb(0xFF, 0x80, 0x00);
+
+        assert_eq!(black, black.mix(orange, 0));
+        assert_eq!(orange, black.mix(orange, 0xFF));
+        assert_eq!(Rgb(0x80, 0x40, 0x00), black.mix(orange, 0x80));
+        assert_eq!(Rgb(0x80, 0x41, 0x00), orange.mix(black, 0x7F));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Rgb(0xFF, 0x80, 0x00)), "#FF8000".parse());
+        assert_eq!(Ok(Rgb(0x22, 0x00, 0xdd)), "2200dd".parse());
+        assert!("#FF80".parse::<Rgb>().is_err());
+        assert!("#GG8000".parse::<Rgb>().is_err());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("#ff8000", Rgb(0xFF, 0x80, 0x00).to_string());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{PackedRow, Row, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+use super::{palette::MAX_COLORS, FrameFilter, Palette, PixelFormat, Upscaler};
+
+/// Largest number of bytes of a pixel in any format
+const MAX_BYTES_PER_PIXEL: usize = 4;
+
+/// Converts the frames into colored images, enlarged with the selected upscaler
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameConverter {
+    /// Colors of each value of the pixels
+    palette: Palette,
+    /// Layout of the pixels of the images
+    format: PixelFormat,
+    /// Algorithm to enlarge the frames
+    upscaler: Upscaler,
+}
+
+impl FrameConverter {
+    /// Returns a new converter with the specified palette, pixel format and upscaler
+    pub fn new(palette: Palette, format: PixelFormat, upscaler: Upscaler) -> Self {
+        Self {
+            palette,
+            format,
+            upscaler,
+        }
+    }
+
+    /// Returns the width in pixels of the converted images
+    pub fn width(&self) -> usize {
+        SCREEN_WIDTH * self.upscaler.factor()
+    }
+
+    /// Returns the height in pixels of the converted images
+    pub fn height(&self) -> usize {
+        SCREEN_HEIGHT * self.upscaler.factor()
+    }
+
+    /// Returns the number of bytes of each row of the converted images
+    pub fn pitch(&self) -> usize {
+        self.width() * self.format.bytes_per_pixel()
+    }
+
+    /// Returns the minimum size of the buffers receiving the converted images
+    pub fn buffer_size(&self) -> usize {
+        self.pitch() * self.height()
+    }
+
+    /// Converts the frame, given as the packed rows of `frame_rows`, into an image written into the specified buffer.
+    ///
+    /// # Panics
+    /// If the buffer is smaller than the `buffer_size`
+    pub fn convert(&self, rows: &[Row], buffer: &mut [u8]) {
+        self.render(
+            |i| rows[i / SCREEN_WIDTH].is_lit(i % SCREEN_WIDTH) as u8,
+            buffer,
+        );
+    }
+
+    /// Converts a frame made of palette indices, one per pixel, into an image written into the specified buffer.
+    ///
+    /// # Panics
+    /// If the buffer is smaller than the `buffer_size`
+    pub fn convert_indexed(&self, frame: &[u8], buffer: &mut [u8]) {
+        self.render(|i| frame[i], buffer);
+    }
+
+    /// Converts the intensities of the filtered frame into an image written into the specified buffer.
+    /// Each intensity is painted with the color of the index given by its upper four bits,
+    /// as laid out by `Palette::gradient`.
+    ///
+    /// # Panics
+    /// If the buffer is smaller than the `buffer_size`
+    pub fn convert_filtered(&self, filter: &FrameFilter, buffer: &mut [u8]) {
+        let intensity = filter.intensity();
+        self.render(|i| intensity[i] >> 4, buffer);
+    }
+
+    /// Writes the image of the frame with the palette index of each pixel given by its position
+    fn render(&self, index: impl Fn(usize) -> u8, buffer: &mut [u8]) {
+        assert!(
+            buffer.len() >= self.buffer_size(),
+            "the image buffer needs at least {} bytes",
+            self.buffer_size()
+        );
+        let bytes_per_pixel = self.format.bytes_per_pixel();
+        let mut colors = [[0; MAX_BYTES_PER_PIXEL]; MAX_COLORS];
+        for (i, color) in colors.iter_mut().enumerate() {
+            self.format
+                .encode(self.palette.color(i), &mut color[..bytes_per_pixel]);
+        }
+
+        // The pixels out of the screen repeat the closest edge
+        let at = |x: usize, y: usize, dx: isize, dy: isize| {
+            let x = x.saturating_add_signed(dx).min(SCREEN_WIDTH - 1);
+            let y = y.saturating_add_signed(dy).min(SCREEN_HEIGHT - 1);
+            index(y * SCREEN_WIDTH + x)
+        };
+        let factor = self.upscaler.factor();
+        let pitch = self.pitch();
+        for y in 0..SCREEN_HEIGHT {
+            for x in 0..SCREEN_WIDTH {
+                let block = match self.upscaler {
+                    Upscaler::Nearest(_) => [at(x, y, 0, 0); 9],
+                    upscaler => upscaler.expand(&[
+                        at(x, y, -1, -1),
+                        at(x, y, 0, -1),
+                        at(x, y, 1, -1),
+                        at(x, y, -1, 0),
+                        at(x, y, 0, 0),
+                        at(x, y, 1, 0),
+                        at(x, y, -1, 1),
+                        at(x, y, 0, 1),
+                        at(x, y, 1, 1),
+                    ]),
+                };
+                for dy in 0..factor {
+                    let row = (y * factor + dy) * pitch;
+                    for dx in 0..factor {
+                        let value = match self.upscaler {
+                            Upscaler::Nearest(_) => block[0],
+                            _ => block[dy * factor + dx],
+                        };
+                        let start = row + (x * factor + dx) * bytes_per_pixel;
+                        buffer[start..start + bytes_per_pixel].copy_from_slice(
+                            &colors[value as usize % MAX_COLORS][..bytes_per_pixel],
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use crate::{
+        video::{Filter, FrameFilter, Palette, PixelFormat, Rgb, Upscaler},
+        SCREEN_HEIGHT, SCREEN_WIDTH,
+    };
+
+    use super::FrameConverter;
+
+    const RED: Rgb = Rgb(0xFF, 0x00, 0x00);
+    const BLUE: Rgb = Rgb(0x00, 0x00, 0xFF);
+
+    #[test]
+    fn sizes() {
+        let converter =
+            FrameConverter::new(Palette::default(), PixelFormat::Rgb565, Upscaler::Scale3x);
+
+        assert_eq!(SCREEN_WIDTH * 3, converter.width());
+        assert_eq!(SCREEN_HEIGHT * 3, converter.height());
+        assert_eq!(SCREEN_WIDTH * 3 * 2, converter.pitch());
+        assert_eq!(
+            SCREEN_WIDTH * SCREEN_HEIGHT * 9 * 2,
+            converter.buffer_size()
+        );
+    }
+
+    #[test]
+    fn convert() {
+        let converter = FrameConverter::new(
+            Palette::mono(RED, BLUE),
+            PixelFormat::Rgba8888,
+            Upscaler::Nearest(2),
+        );
+        let mut rows = [0; SCREEN_HEIGHT];
+        rows[0] = 0x4000_0000_0000_0000;
+        let mut buffer = vec![0; converter.buffer_size()];
+
+        converter.convert(&rows, &mut buffer);
+        assert_eq!([0xFF, 0x00, 0x00, 0xFF], buffer[0..4]);
+        assert_eq!([0xFF, 0x00, 0x00, 0xFF], buffer[4..8]);
+        assert_eq!([0x00, 0x00, 0xFF, 0xFF], buffer[8..12]);
+        assert_eq!([0x00, 0x00, 0xFF, 0xFF], buffer[12..16]);
+        // Second row of the enlarged first pixel row
+        let pitch = converter.pitch();
+        assert_eq!([0x00, 0x00, 0xFF, 0xFF], buffer[pitch + 8..pitch + 12]);
+    }
+
+    #[test]
+    fn convert_indexed() {
+        let palette = Palette::new(&[RED, BLUE, RED, BLUE]).unwrap();
+        let converter = FrameConverter::new(palette, PixelFormat::Rgb565, Upscaler::default());
+        let mut frame = [0; SCREEN_WIDTH * SCREEN_HEIGHT];
+        frame[0] = 3;
+        let mut buffer = vec![0; converter.buffer_size()];
+
+        converter.convert_indexed(&frame, &mut buffer);
+        assert_eq!([0x1F, 0x00, 0x00, 0xF8], buffer[0..4]);
+    }
+
+    #[test]
+    fn convert_filtered() {
+        let converter = FrameConverter::new(
+            Palette::gradient(RED, BLUE),
+            PixelFormat::Rgba8888,
+            Upscaler::default(),
+        );
+        let mut filter = FrameFilter::new(Filter::Phosphor { decay: 0x80 });
+        let mut rows = [0; SCREEN_HEIGHT];
+        rows[0] = 0xC000_0000_0000_0000;
+        filter.apply(&rows);
+        rows[0] = 0x4000_0000_0000_0000;
+        filter.apply(&rows);
+        let mut buffer = vec![0; converter.buffer_size()];
+
+        converter.convert_filtered(&filter, &mut buffer);
+        assert_eq!([0x88, 0x00, 0x77, 0xFF], buffer[0..4]);
+        assert_eq!([0x00, 0x00, 0xFF, 0xFF], buffer[4..8]);
+        assert_eq!([0xFF, 0x00, 0x00, 0xFF], buffer[8..12]);
+    }
+
+    #[test]
+    fn convert_scale2x() {
+        let converter = FrameConverter::new(
+            Palette::mono(RED, BLUE),
+            PixelFormat::Rgb565,
+            Upscaler::Scale2x,
+        );
+        // Diagonal line from (0, 1) to (1, 0)
+        let mut rows = [0; SCREEN_HEIGHT];
+        rows[0] = 0x4000_0000_0000_0000;
+        rows[1] = 0x8000_0000_0000_0000;
+        let mut buffer = vec![0; converter.buffer_size()];
+
+        converter.convert(&rows, &mut buffer);
+        let pitch = converter.pitch();
+        let pixel = |x: usize, y: usize| [buffer[y * pitch + x * 2], buffer[y * pitch + x * 2 + 1]];
+        // The bottom right corner of the top left pixel is filled to smooth the diagonal
+        assert_eq!([0x00, 0xF8], pixel(0, 0));
+        assert_eq!([0x1F, 0x00], pixel(1, 1));
+        assert_eq!([0x1F, 0x00], pixel(2, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn convert_small_buffer() {
+        let converter = FrameConverter::default();
+        let mut buffer = vec![0; converter.buffer_size() - 1];
+
+        converter.convert(&[0; SCREEN_HEIGHT], &mut buffer);
+    }
+}
@@ -0,0 +1,229 @@
+use core::str::FromStr;
+
+use crate::{PackedRow, ParseError, Row, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+const SCREEN_SIZE: usize = SCREEN_WIDTH * SCREEN_HEIGHT;
+/// Intensity of the lit pixels
+const LIT: u8 = u8::MAX;
+/// Intensity of the unlit pixels
+const UNLIT: u8 = 0;
+/// Intensity lost each frame by default by the unlit pixels with the phosphor filter
+const DEFAULT_DECAY: u8 = 64;
+/// Number of frames blended by default with the blend filter
+const DEFAULT_BLENDED_FRAMES: usize = 2;
+/// Maximum number of frames that can be blended
+const MAX_BLENDED_FRAMES: usize = 8;
+
+/// Post-processing applied to the frames to reduce the flickering of the XOR drawing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    /// Frames are drawn as they are
+    #[default]
+    None,
+    /// Emulates the persistence of the phosphor screens, the unlit pixels fade out
+    /// losing the specified intensity each frame
+    Phosphor { decay: u8 },
+    /// Draws every pixel lit in any of the specified number of last frames
+    Blend { frames: usize },
+}
+
+impl FromStr for Filter {
+    type Err = ParseError;
+
+    /// Parses a filter with an optional argument, like `none`, `phosphor:32` or `blend:3`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match s.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (s, None),
+        };
+        let invalid = || parse_error!("invalid filter: {s}");
+        match (name, argument) {
+            ("none", None) => Ok(Filter::None),
+            ("phosphor", None) => Ok(Filter::Phosphor {
+                decay: DEFAULT_DECAY,
+            }),
+            ("phosphor", Some(decay)) => match decay.parse() {
+                Ok(decay) if decay > 0 => Ok(Filter::Phosphor { decay }),
+                _ => Err(invalid()),
+            },
+            ("blend", None) => Ok(Filter::Blend {
+                frames: DEFAULT_BLENDED_FRAMES,
+            }),
+            ("blend", Some(frames)) => match frames.parse() {
+                Ok(frames) if (1..=MAX_BLENDED_FRAMES).contains(&frames) => {
+                    Ok(Filter::Blend { frames })
+                }
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Converts the binary frames into intensity buffers applying the selected filter.
+/// The intensity of each pixel goes from zero, unlit, to the maximum, fully lit.
+pub struct FrameFilter {
+    /// Filter to apply
+    filter: Filter,
+    /// Intensity of each pixel after filtering the last frame
+    intensity: [u8; SCREEN_SIZE],
+    /// Last frames received, used to blend them
+    history: [[Row; SCREEN_HEIGHT]; MAX_BLENDED_FRAMES],
+    /// Position of the history to store the next frame
+    next: usize,
+}
+
+impl FrameFilter {
+    /// Returns a new frame filter applying the specified filter
+    pub fn new(filter: Filter) -> Self {
+        let filter = match filter {
+            Filter::Phosphor { decay } => Filter::Phosphor {
+                decay: decay.max(1),
+            },
+            Filter::Blend { frames } => Filter::Blend {
+                frames: frames.clamp(1, MAX_BLENDED_FRAMES),
+            },
+            Filter::None => Filter::None,
+        };
+        Self {
+            filter,
+            intensity: [UNLIT; SCREEN_SIZE],
+            history: [[0; SCREEN_HEIGHT]; MAX_BLENDED_FRAMES],
+            next: 0,
+        }
+    }
+
+    /// Returns the filter applied
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Returns the intensity of each pixel after filtering the last frame
+    pub fn intensity(&self) -> &[u8] {
+        &self.intensity
+    }
+
+    /// Filters the next frame, given as packed rows.
+    /// Returns true if the intensity of any pixel changed.
+    pub fn apply(&mut self, rows: &[Row]) -> bool {
+        let mut lit = [0; SCREEN_HEIGHT];
+        lit.copy_from_slice(rows);
+        if let Filter::Blend { frames } = self.filter {
+            self.history[self.next] = lit;
+            self.next = (self.next + 1) % frames;
+            for (y, row) in lit.iter_mut().enumerate() {
+                *row = self.history[..frames]
+                    .iter()
+                    .fold(0, |acc, frame| acc | frame[y]);
+            }
+        }
+        let decay = match self.filter {
+            Filter::Phosphor { decay } => decay,
+            _ => LIT,
+        };
+
+        let mut changed = false;
+        for (y, row) in lit.iter().enumerate() {
+            for x in 0..SCREEN_WIDTH {
+                let pixel = &mut self.intensity[y * SCREEN_WIDTH + x];
+                let intensity = if row.is_lit(x) {
+                    LIT
+                } else {
+                    pixel.saturating_sub(decay)
+                };
+                changed |= *pixel != intensity;
+                *pixel = intensity;
+            }
+        }
+        changed
+    }
+}
+
+impl Default for FrameFilter {
+    fn default() -> Self {
+        Self::new(Filter::None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Row, SCREEN_HEIGHT};
+
+    use super::{Filter, FrameFilter, LIT, MAX_BLENDED_FRAMES, UNLIT};
+
+    fn frame(lit: bool) -> [Row; SCREEN_HEIGHT] {
+        let mut frame = [0; SCREEN_HEIGHT];
+        if lit {
+            frame[0] = 0x8000_0000_0000_0000;
+        }
+        frame
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Filter::None), "none".parse());
+        assert_eq!(Ok(Filter::Phosphor { decay: 64 }), "phosphor".parse());
+        assert_eq!(Ok(Filter::Phosphor { decay: 10 }), "phosphor:10".parse());
+        assert_eq!(Ok(Filter::Blend { frames: 2 }), "blend".parse());
+        assert_eq!(Ok(Filter::Blend { frames: 4 }), "blend:4".parse());
+        assert!("phosphor:0".parse::<Filter>().is_err());
+        assert!("blend:9".parse::<Filter>().is_err());
+        assert!("none:1".parse::<Filter>().is_err());
+        assert!("crt".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            Filter::Phosphor { decay: 1 },
+            FrameFilter::new(Filter::Phosphor { decay: 0 }).filter()
+        );
+        assert_eq!(
+            Filter::Blend {
+                frames: MAX_BLENDED_FRAMES
+            },
+            FrameFilter::new(Filter::Blend { frames: 20 }).filter()
+        );
+    }
+
+    #[test]
+    fn none() {
+        let mut filter = FrameFilter::default();
+
+        assert!(filter.apply(&frame(true)));
+        assert_eq!(LIT, filter.intensity()[0]);
+        assert!(!filter.apply(&frame(true)));
+
+        assert!(filter.apply(&frame(false)));
+        assert_eq!(UNLIT, filter.intensity()[0]);
+    }
+
+    #[test]
+    fn phosphor() {
+        let mut filter = FrameFilter::new(Filter::Phosphor { decay: 100 });
+        filter.apply(&frame(true));
+
+        assert!(filter.apply(&frame(false)));
+        assert_eq!(155, filter.intensity()[0]);
+        assert!(filter.apply(&frame(false)));
+        assert_eq!(55, filter.intensity()[0]);
+        assert!(filter.apply(&frame(false)));
+        assert_eq!(UNLIT, filter.intensity()[0]);
+        assert!(!filter.apply(&frame(false)));
+        // Lit pixels are restored to the full intensity
+        assert!(filter.apply(&frame(true)));
+        assert_eq!(LIT, filter.intensity()[0]);
+    }
+
+    #[test]
+    fn blend() {
+        let mut filter = FrameFilter::new(Filter::Blend { frames: 3 });
+        filter.apply(&frame(true));
+
+        assert!(!filter.apply(&frame(false)));
+        assert!(!filter.apply(&frame(false)));
+        assert_eq!(LIT, filter.intensity()[0]);
+        assert!(filter.apply(&frame(false)));
+        assert_eq!(UNLIT, filter.intensity()[0]);
+    }
+}
@@ -0,0 +1,164 @@
+use super::Rgb;
+
+/// Maximum number of colors of a palette, the sixteen colors of XO-CHIP
+pub const MAX_COLORS: usize = 16;
+
+/// Colors used to paint each value of the pixels, the first one is the background
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    /// Colors of the palette, only the first `len` are used
+    colors: [Rgb; MAX_COLORS],
+    /// Number of colors of the palette
+    len: usize,
+}
+
+impl Palette {
+    /// Returns a two colors palette with the specified background and foreground
+    pub const fn mono(bg: Rgb, fg: Rgb) -> Self {
+        let mut colors = [bg; MAX_COLORS];
+        colors[1] = fg;
+        Self { colors, len: 2 }
+    }
+
+    /// Returns a sixteen colors palette going from the background to the foreground.
+    /// It allows to paint the intensities of a `FrameFilter` using their upper four bits as index.
+    pub fn gradient(bg: Rgb, fg: Rgb) -> Self {
+        let mut colors = [bg; MAX_COLORS];
+        for (i, color) in colors.iter_mut().enumerate() {
+            *color = bg.mix(fg, (i * u8::MAX as usize / (MAX_COLORS - 1)) as u8);
+        }
+        Self {
+            colors,
+            len: MAX_COLORS,
+        }
+    }
+
+    /// Returns a palette with the specified colors.
+    /// Returns None unless there are two, four or sixteen colors.
+    pub fn new(colors: &[Rgb]) -> Option<Self> {
+        if !matches!(colors.len(), 2 | 4 | MAX_COLORS) {
+            return None;
+        }
+        let mut palette = [Rgb::default(); MAX_COLORS];
+        palette[..colors.len()].copy_from_slice(colors);
+        Some(Self {
+            colors: palette,
+            len: colors.len(),
+        })
+    }
+
+    /// Returns the colors of the palette
+    pub fn colors(&self) -> &[Rgb] {
+        &self.colors[..self.len]
+    }
+
+    /// Returns the color of the specified pixel value, wrapping it if it's out of the palette
+    pub fn color(&self, index: usize) -> Rgb {
+        self.colors[index % self.len]
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::mono(Rgb(0x00, 0x00, 0x00), Rgb(0xFF, 0xFF, 0xFF))
+    }
+}
+
+/// Layout of the pixels of the converted images
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Four bytes per pixel: red, green, blue and alpha, always opaque
+    #[default]
+    Rgba8888,
+    /// Two bytes per pixel in little endian: five bits of red, six of green and five of blue
+    Rgb565,
+    /// Four bytes per pixel holding `0x00RRGGBB` in native endian, the format of libretro
+    Xrgb8888,
+}
+
+impl PixelFormat {
+    /// Returns the number of bytes of each pixel
+    pub const fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgba8888 | PixelFormat::Xrgb8888 => 4,
+            PixelFormat::Rgb565 => 2,
+        }
+    }
+
+    /// Writes the color into the pixel, that must be as long as the bytes of one pixel
+    pub fn encode(&self, color: Rgb, pixel: &mut [u8]) {
+        let Rgb(r, g, b) = color;
+        match self {
+            PixelFormat::Rgba8888 => pixel.copy_from_slice(&[r, g, b, u8::MAX]),
+            PixelFormat::Rgb565 => {
+                let packed = ((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3);
+                pixel.copy_from_slice(&packed.to_le_bytes());
+            }
+            PixelFormat::Xrgb8888 => {
+                let packed = u32::from_be_bytes([0, r, g, b]);
+                pixel.copy_from_slice(&packed.to_ne_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::video::Rgb;
+
+    use super::{Palette, PixelFormat};
+
+    const RED: Rgb = Rgb(0xFF, 0x00, 0x00);
+    const GREEN: Rgb = Rgb(0x00, 0xFF, 0x00);
+    const BLUE: Rgb = Rgb(0x00, 0x00, 0xFF);
+
+    #[test]
+    fn mono() {
+        let palette = Palette::mono(RED, GREEN);
+
+        assert_eq!(&[RED, GREEN], palette.colors());
+        assert_eq!(RED, palette.color(0));
+        assert_eq!(GREEN, palette.color(1));
+        assert_eq!(RED, palette.color(2));
+    }
+
+    #[test]
+    fn gradient() {
+        let palette = Palette::gradient(RED, BLUE);
+
+        assert_eq!(16, palette.colors().len());
+        assert_eq!(RED, palette.color(0));
+        assert_eq!(Rgb(0x88, 0x00, 0x77), palette.color(7));
+        assert_eq!(BLUE, palette.color(15));
+    }
+
+    #[test]
+    fn new() {
+        let palette = Palette::new(&[RED, GREEN, BLUE, RED]).unwrap();
+        assert_eq!(BLUE, palette.color(2));
+        assert_eq!(4, palette.colors().len());
+
+        assert!(Palette::new(&[RED]).is_none());
+        assert!(Palette::new(&[RED, GREEN, BLUE]).is_none());
+        assert!(Palette::new(&[RED; 16]).is_some());
+    }
+
+    #[test]
+    fn encode() {
+        let mut pixel = [0; 4];
+        PixelFormat::Rgba8888.encode(Rgb(0x12, 0x34, 0x56), &mut pixel);
+        assert_eq!([0x12, 0x34, 0x56, 0xFF], pixel);
+
+        let mut pixel = [0; 2];
+        PixelFormat::Rgb565.encode(RED, &mut pixel);
+        assert_eq!([0x00, 0xF8], pixel);
+        PixelFormat::Rgb565.encode(GREEN, &mut pixel);
+        assert_eq!([0xE0, 0x07], pixel);
+        PixelFormat::Rgb565.encode(BLUE, &mut pixel);
+        assert_eq!([0x1F, 0x00], pixel);
+
+        let mut pixel = [0; 4];
+        PixelFormat::Xrgb8888.encode(Rgb(0x12, 0x34, 0x56), &mut pixel);
+        assert_eq!(0x00123456, u32::from_ne_bytes(pixel));
+    }
+}
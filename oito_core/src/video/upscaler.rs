@@ -0,0 +1,155 @@
+use core::str::FromStr;
+
+use crate::ParseError;
+
+/// Algorithm used to enlarge the frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Upscaler {
+    /// Repeats each pixel the specified number of times in both axes
+    Nearest(usize),
+    /// Doubles the size smoothing the diagonal edges
+    Scale2x,
+    /// Triples the size smoothing the diagonal edges
+    Scale3x,
+    /// Eric's Pixel Expansion, the algorithm Scale2x was derived from. It produces the same result.
+    Epx,
+}
+
+impl Upscaler {
+    /// Returns the number of pixels of the enlarged frame for each original pixel in both axes
+    pub fn factor(&self) -> usize {
+        match self {
+            Upscaler::Nearest(factor) => (*factor).max(1),
+            Upscaler::Scale2x | Upscaler::Epx => 2,
+            Upscaler::Scale3x => 3,
+        }
+    }
+
+    /// Returns the pixels replacing the center of the neighbourhood, both given row by row.
+    /// Only the first `factor * factor` returned pixels are meaningful.
+    pub(super) fn expand(&self, neighbourhood: &[u8; 9]) -> [u8; 9] {
+        let [a, b, c, d, e, f, g, h, i] = *neighbourhood;
+        match self {
+            Upscaler::Nearest(_) => [e; 9],
+            Upscaler::Scale2x | Upscaler::Epx => {
+                if b != h && d != f {
+                    [
+                        if d == b { d } else { e },
+                        if b == f { f } else { e },
+                        if d == h { d } else { e },
+                        if h == f { f } else { e },
+                        e,
+                        e,
+                        e,
+                        e,
+                        e,
+                    ]
+                } else {
+                    [e; 9]
+                }
+            }
+            Upscaler::Scale3x => {
+                if b != h && d != f {
+                    [
+                        if d == b { d } else { e },
+                        if (d == b && e != c) || (b == f && e != a) {
+                            b
+                        } else {
+                            e
+                        },
+                        if b == f { f } else { e },
+                        if (d == b && e != g) || (d == h && e != a) {
+                            d
+                        } else {
+                            e
+                        },
+                        e,
+                        if (b == f && e != i) || (h == f && e != c) {
+                            f
+                        } else {
+                            e
+                        },
+                        if d == h { d } else { e },
+                        if (d == h && e != i) || (h == f && e != g) {
+                            h
+                        } else {
+                            e
+                        },
+                        if h == f { f } else { e },
+                    ]
+                } else {
+                    [e; 9]
+                }
+            }
+        }
+    }
+}
+
+impl Default for Upscaler {
+    fn default() -> Self {
+        Upscaler::Nearest(1)
+    }
+}
+
+impl FromStr for Upscaler {
+    type Err = ParseError;
+
+    /// Parses an upscaler, like `nearest:4`, `scale2x`, `scale3x` or `epx`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("nearest", factor)) => match factor.parse() {
+                Ok(factor) if factor > 0 => Ok(Upscaler::Nearest(factor)),
+                _ => Err(parse_error!("invalid upscaler: {s}")),
+            },
+            None if s == "nearest" => Ok(Upscaler::Nearest(1)),
+            None if s == "scale2x" => Ok(Upscaler::Scale2x),
+            None if s == "scale3x" => Ok(Upscaler::Scale3x),
+            None if s == "epx" => Ok(Upscaler::Epx),
+            _ => Err(parse_error!("invalid upscaler: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Upscaler;
+
+    #[test]
+    fn factor() {
+        assert_eq!(1, Upscaler::Nearest(0).factor());
+        assert_eq!(4, Upscaler::Nearest(4).factor());
+        assert_eq!(2, Upscaler::Scale2x.factor());
+        assert_eq!(2, Upscaler::Epx.factor());
+        assert_eq!(3, Upscaler::Scale3x.factor());
+    }
+
+    #[test]
+    fn expand() {
+        // Diagonal edge from the bottom left to the top right
+        #[rustfmt::skip]
+        let edge = [
+            0, 0, 1,
+            0, 0, 1,
+            1, 1, 1,
+        ];
+        assert_eq!([0; 9], Upscaler::Nearest(3).expand(&edge));
+        assert_eq!([0, 0, 0, 1], Upscaler::Scale2x.expand(&edge)[..4]);
+        assert_eq!(Upscaler::Scale2x.expand(&edge), Upscaler::Epx.expand(&edge));
+        assert_eq!([0, 0, 0, 0, 0, 1, 0, 1, 1], Upscaler::Scale3x.expand(&edge));
+        // Solid areas are not smoothed
+        assert_eq!([1, 1, 1, 1], Upscaler::Scale2x.expand(&[1; 9])[..4]);
+        assert_eq!([1; 9], Upscaler::Scale3x.expand(&[1; 9]));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Upscaler::Nearest(1)), "nearest".parse());
+        assert_eq!(Ok(Upscaler::Nearest(5)), "nearest:5".parse());
+        assert_eq!(Ok(Upscaler::Scale2x), "scale2x".parse());
+        assert_eq!(Ok(Upscaler::Scale3x), "scale3x".parse());
+        assert_eq!(Ok(Upscaler::Epx), "epx".parse());
+        assert!("nearest:0".parse::<Upscaler>().is_err());
+        assert!("epx:2".parse::<Upscaler>().is_err());
+        assert!("hq2x".parse::<Upscaler>().is_err());
+    }
+}
@@ -1,63 +1,102 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
 
-use crate::{Pixel, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::{damage::Damage, Byte, PackedRow, Pixel, Row, SCREEN_HEIGHT};
 
-const SCREEN_SIZE: usize = SCREEN_WIDTH * SCREEN_HEIGHT;
-
-/// Representation of the screen to draw
-pub struct VRam {
-    /// Buffer of the current visual content
-    buffer: [Pixel; SCREEN_SIZE],
+/// Representation of the screen to draw, as wide as its packed rows
+pub struct VRam<R: PackedRow = Row> {
+    /// Packed rows of the current visual content, the most significant bit is the leftmost pixel
+    rows: [R; SCREEN_HEIGHT],
+    /// Region changed since the last time it was taken
+    damage: Damage<R>,
 }
 
 impl VRam {
     /// Value representing the a black pixel
+    #[cfg(test)]
     pub const BLACK: bool = false;
-    /// Value representing the a white pixel
-    pub const WHITE: bool = true;
+}
+
+impl<R: PackedRow> VRam<R> {
+    /// Returns the current visual content as one pixel per position, row by row
+    pub fn pixels(&self) -> impl ExactSizeIterator<Item = Pixel> + Clone + '_ {
+        (0..SCREEN_HEIGHT * R::WIDTH).map(|i| self.rows[i / R::WIDTH].is_lit(i % R::WIDTH))
+    }
+
+    /// Returns the current visual content as one packed row per line
+    pub fn rows(&self) -> &[R] {
+        &self.rows
+    }
 
-    pub fn buffer(&self) -> &[Pixel] {
-        &self.buffer
+    /// Returns the region changed since the last time it was taken
+    pub fn damage(&self) -> &Damage<R> {
+        &self.damage
+    }
+
+    /// Returns the region changed since the last time it was taken and starts tracking again
+    pub fn take_damage(&mut self) -> Damage<R> {
+        core::mem::take(&mut self.damage)
+    }
+
+    /// Marks the whole screen as changed, so the next draw repaints it completely
+    pub fn damage_all(&mut self) {
+        self.damage = Damage::full();
     }
 
     /// Clears the current buffered content
     pub fn clear(&mut self) {
-        self.buffer = [Self::BLACK; SCREEN_SIZE];
+        for (y, row) in self.rows.iter().enumerate() {
+            self.damage.mark(y, *row);
+        }
+        self.rows = [R::EMPTY; SCREEN_HEIGHT];
+    }
+
+    /// Replaces the visual content with the packed rows
+    pub fn restore(&mut self, rows: &[R; SCREEN_HEIGHT]) {
+        for (y, row) in rows.iter().enumerate() {
+            let mut changed = self.rows[y];
+            changed ^= *row;
+            self.damage.mark(y, changed);
+            self.rows[y] = *row;
+        }
     }
 
     /// Paints over the pixel.
     /// If the coordinates overflow the screen space, it will be drawn counting the overflow from the start.
     /// If this already painted, it sets the pixel to not painted.
+    #[cfg(test)]
     pub fn paint(&mut self, x: usize, y: usize) {
-        self.buffer[Self::to_index(x, y)] ^= VRam::WHITE;
+        let y = y % SCREEN_HEIGHT;
+        let pixel = R::sprite(0x80, x);
+        self.rows[y] ^= pixel;
+        self.damage.mark(y, pixel);
     }
 
-    /// Returns the content of the pixel at the specified location
-    pub fn get(&mut self, x: usize, y: usize) -> Pixel {
-        self.buffer[Self::to_index(x, y)]
+    /// Draws a row of a sprite starting at the specified location.
+    /// The pixels overflowing the screen space are drawn counting the overflow from the start.
+    /// Returns true if any of the already painted pixels was set to not painted.
+    pub fn draw(&mut self, x: usize, y: usize, sprite: Byte) -> bool {
+        let y = y % SCREEN_HEIGHT;
+        let sprite = R::sprite(sprite, x);
+
+        let collision = self.rows[y] & sprite != R::EMPTY;
+        self.rows[y] ^= sprite;
+        self.damage.mark(y, sprite);
+        collision
     }
 
-    /// Converts the coordinates into the index position in the buffer
-    fn to_index(x: usize, y: usize) -> usize {
-        x % SCREEN_WIDTH + (y % SCREEN_HEIGHT) * SCREEN_WIDTH
+    /// Returns the content of the pixel at the specified location
+    #[cfg(test)]
+    pub fn get(&self, x: usize, y: usize) -> Pixel {
+        self.rows[y % SCREEN_HEIGHT].is_lit(x)
     }
 }
 
-impl Debug for VRam {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<R: PackedRow> Debug for VRam<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "VRAM: ").unwrap();
-        for i in 0..SCREEN_HEIGHT {
-            for j in 0..SCREEN_WIDTH {
-                write!(
-                    f,
-                    "{}",
-                    if self.buffer[i * SCREEN_WIDTH + j] {
-                        "X"
-                    } else {
-                        "_"
-                    }
-                )
-                .unwrap();
+        for row in self.rows {
+            for x in 0..R::WIDTH {
+                write!(f, "{}", if row.is_lit(x) { "X" } else { "_" }).unwrap();
             }
             writeln!(f).unwrap();
         }
@@ -65,25 +104,127 @@ impl Debug for VRam {
     }
 }
 
-impl Default for VRam {
+impl<R: PackedRow> Default for VRam<R> {
     fn default() -> Self {
         Self {
-            buffer: [Self::BLACK; SCREEN_SIZE],
+            rows: [R::EMPTY; SCREEN_HEIGHT],
+            damage: Damage::default(),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{VRam, SCREEN_SIZE};
+    use alloc::{vec, vec::Vec};
+
+    use crate::{damage::Damage, Row, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+    use super::VRam;
 
     #[test]
     fn clear() {
         let mut vram = VRam {
-            buffer: [VRam::WHITE; SCREEN_SIZE],
+            rows: [Row::MAX; SCREEN_HEIGHT],
+            damage: Damage::default(),
         };
 
         vram.clear();
-        assert_eq!([VRam::BLACK; SCREEN_SIZE], vram.buffer);
+        assert_eq!([0; SCREEN_HEIGHT], vram.rows);
+        assert!(vram.pixels().all(|pixel| pixel == VRam::BLACK));
+        assert_eq!(Damage::FULL, vram.take_damage());
+        // Clearing an empty screen changes nothing
+        vram.clear();
+        assert!(vram.damage().is_empty());
+    }
+
+    #[test]
+    fn damage_all() {
+        let mut vram = VRam::default();
+        vram.damage_all();
+        assert_eq!(Damage::FULL, vram.take_damage());
+        assert!(vram.damage().is_empty());
+    }
+
+    #[test]
+    fn take_damage() {
+        let mut vram = VRam::<Row>::default();
+        vram.draw(0, 4, 0x00);
+        assert!(vram.damage().is_empty());
+
+        vram.draw(0, 4, 0x80);
+        vram.paint(0, 6);
+        let damage = vram.take_damage();
+        assert_eq!(vec![4, 6], damage.rows().collect::<Vec<_>>());
+        assert!(vram.damage().is_empty());
+    }
+
+    #[test]
+    fn pixels() {
+        let mut vram = VRam::<Row>::default();
+        vram.paint(1, 2);
+        vram.paint(63, 31);
+
+        let pixels: Vec<bool> = vram.pixels().collect();
+        assert_eq!(SCREEN_WIDTH * SCREEN_HEIGHT, pixels.len());
+        assert!(pixels[2 * 64 + 1]);
+        assert!(pixels[SCREEN_WIDTH * SCREEN_HEIGHT - 1]);
+        assert_eq!(2, pixels.iter().filter(|pixel| **pixel).count());
+    }
+
+    #[test]
+    fn paint() {
+        let mut vram = VRam::<Row>::default();
+
+        vram.paint(1, 2);
+        assert!(vram.get(1, 2));
+        assert_eq!(0x4000_0000_0000_0000, vram.rows[2]);
+
+        vram.paint(65, 34);
+        assert!(!vram.get(1, 2));
+    }
+
+    #[test]
+    fn draw() {
+        let mut vram = VRam::<Row>::default();
+
+        assert!(!vram.draw(4, 1, 0b1100_0011));
+        assert_eq!(0x0C30_0000_0000_0000, vram.rows[1]);
+        assert!(vram.get(4, 1));
+        assert!(!vram.get(6, 1));
+        assert!(vram.get(11, 1));
+        // Collision
+        assert!(vram.draw(10, 1, 0b1000_0000));
+        assert!(!vram.get(10, 1));
+        // No collision
+        assert!(!vram.draw(10, 1, 0b0010_0000));
+    }
+
+    #[test]
+    fn draw_wrapping() {
+        let mut vram = VRam::<Row>::default();
+
+        vram.draw(60, 33, 0xFF);
+        assert_eq!(0xF000_0000_0000_000F, vram.rows[1]);
+        assert!(vram.get(0, 1));
+        assert!(vram.get(63, 1));
+        assert!(!vram.get(4, 1));
+        assert!(vram.get(3, 1));
+        assert!(vram.get(60, 1));
+    }
+
+    #[test]
+    fn draw_wide() {
+        let mut vram = VRam::<u128>::default();
+
+        assert!(!vram.draw(124, 0, 0xFF));
+        assert_eq!(0xF000_0000_0000_0000_0000_0000_0000_000F, vram.rows[0]);
+        assert!(vram.get(0, 0));
+        assert!(vram.get(127, 0));
+        assert!(!vram.get(64, 0));
+        // Wraps around the wider row
+        assert!(vram.draw(128, 0, 0x80));
+        assert_eq!(128 * SCREEN_HEIGHT, vram.pixels().len());
+        let bounds = vram.take_damage().bounds().unwrap();
+        assert_eq!((0, 128), (bounds.x, bounds.width));
     }
 }
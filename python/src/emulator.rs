@@ -0,0 +1,244 @@
+use oito_core::{
+    config::{Backend, Config, Quirks},
+    core::{OitoCore, Register, State, Status},
+    exception::Exception,
+    key::Key,
+    Address, Row,
+};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+
+use crate::{frame::Frame, EmulatorError};
+
+/// Chip-8 emulator running a ROM
+#[pyclass(name = "Oito", module = "oito")]
+pub struct Oito {
+    oito: OitoCore,
+    /// Settings used to rebuild the emulator in the loads and resets
+    config: Config,
+    /// ROM being run
+    rom: Vec<u8>,
+}
+
+#[pymethods]
+impl Oito {
+    /// Returns an emulator running the ROM, if any. The seed makes the random numbers reproducible,
+    /// the recompiler caches the decoded blocks, and the release waiting emulates the Fx0A of the COSMAC VIP.
+    #[new]
+    #[pyo3(signature = (rom=None, *, seed=None, recompiler=false, wait_release=false))]
+    fn new(rom: Option<&[u8]>, seed: Option<u64>, recompiler: bool, wait_release: bool) -> Self {
+        let config = Config {
+            backend: if recompiler {
+                Backend::Recompiler
+            } else {
+                Backend::Interpreter
+            },
+            quirks: Quirks { wait_release },
+            seed,
+            ..Default::default()
+        };
+        let mut oito = Self {
+            oito: OitoCore::with_config(config.clone()),
+            config,
+            rom: Vec::new(),
+        };
+        if let Some(rom) = rom {
+            oito.load(rom);
+        }
+        oito
+    }
+
+    /// Loads the ROM in a new emulator
+    fn load(&mut self, rom: &[u8]) {
+        self.rom = rom.to_vec();
+        self.reset();
+    }
+
+    /// Restarts the ROM in a new emulator built with the same settings
+    fn reset(&mut self) {
+        self.oito = OitoCore::with_config(self.config.clone());
+        self.oito.load(&self.rom);
+    }
+
+    /// Runs an instruction
+    fn tick(&mut self) -> PyResult<()> {
+        self.oito.tick().map_err(emulator)
+    }
+
+    /// Decreases the timers, to call once per frame
+    fn frame_tick(&mut self) {
+        self.oito.frame_tick();
+    }
+
+    /// Runs the frames, stopping before if the emulator stops or hits a breakpoint. Returns the number of frames run.
+    #[pyo3(signature = (frames=1, ticks=10))]
+    fn run(&mut self, py: Python<'_>, frames: usize, ticks: usize) -> PyResult<usize> {
+        py.allow_threads(|| {
+            for frame in 0..frames {
+                for _ in 0..ticks {
+                    self.oito.tick()?;
+                    if self.is_paused() {
+                        return Ok(frame);
+                    }
+                }
+                self.oito.frame_tick();
+            }
+            Ok(frames)
+        })
+        .map_err(emulator)
+    }
+
+    /// Presses the key of the value
+    fn key_press(&mut self, key: u8) -> PyResult<()> {
+        self.oito.key_press(parse_key(key)?);
+        Ok(())
+    }
+
+    /// Releases the key of the value
+    fn key_release(&mut self, key: u8) -> PyResult<()> {
+        self.oito.key_release(parse_key(key)?);
+        Ok(())
+    }
+
+    /// Mask of the keys being pressed, bit N is the key of value N
+    #[getter]
+    fn keys(&self) -> u16 {
+        self.oito.keys()
+    }
+
+    #[setter]
+    fn set_keys(&mut self, mask: u16) {
+        self.oito.set_keys(mask);
+    }
+
+    /// Returns a copy of the current frame
+    fn frame(&self) -> Frame {
+        Frame::new(self.oito.frame_buffer())
+    }
+
+    /// Returns the rows of the current frame packed in integers, the leftmost pixel as the most significant bit
+    fn rows(&self) -> Vec<Row> {
+        self.oito.frame_rows().to_vec()
+    }
+
+    /// Returns the frame drawn with characters
+    fn screen(&self) -> String {
+        self.oito.screen()
+    }
+
+    /// Returns the bytes of the memory starting at the address
+    fn memory<'py>(
+        &self,
+        py: Python<'py>,
+        address: Address,
+        length: usize,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let content = self.oito.memory(address, length).map_err(emulator)?;
+        Ok(PyBytes::new(py, content))
+    }
+
+    /// Writes the bytes in the memory starting at the address
+    fn poke(&mut self, address: Address, content: &[u8]) -> PyResult<()> {
+        self.oito.poke(address, content).map_err(emulator)
+    }
+
+    /// Returns the registers and timers: `v` (list of the sixteen V-Registers), `i`, `pc`, `sp`, `dt` and `st`
+    fn registers<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let registers = self.oito.registers();
+        let dict = PyDict::new(py);
+        dict.set_item("v", registers.v.to_vec())?;
+        dict.set_item("i", registers.i)?;
+        dict.set_item("pc", registers.pc)?;
+        dict.set_item("sp", registers.sp)?;
+        dict.set_item("dt", registers.dt)?;
+        dict.set_item("st", registers.st)?;
+        Ok(dict)
+    }
+
+    /// Sets the value of a register by name: `v0` to `vf`, `i`, `pc`, `dt` or `st`
+    fn set_register(&mut self, register: &str, value: u16) -> PyResult<()> {
+        let register: Register = register.parse().map_err(PyValueError::new_err)?;
+        self.oito.set_register(register, value);
+        Ok(())
+    }
+
+    /// Returns the serialized state of the whole machine
+    fn save_state<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.oito.save_state().to_bytes())
+    }
+
+    /// Restores a state returned by `save_state`
+    fn load_state(&mut self, state: &[u8]) -> PyResult<()> {
+        let state = State::try_from(state).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.oito.load_state(&state);
+        Ok(())
+    }
+
+    /// Description of the execution status
+    #[getter]
+    fn status(&self) -> String {
+        self.oito.status().to_string()
+    }
+
+    /// True if the emulator won't run more instructions
+    #[getter]
+    fn stopped(&self) -> bool {
+        self.oito.status().is_stopped()
+    }
+
+    /// Number of instructions run
+    #[getter]
+    fn instructions(&self) -> u64 {
+        self.oito.instructions()
+    }
+
+    /// True if the sound is playing
+    #[getter]
+    fn sound(&self) -> bool {
+        self.oito.sound()
+    }
+
+    /// Stops the execution before running the instruction at the address
+    fn add_breakpoint(&mut self, address: Address) {
+        self.oito.add_breakpoint(address);
+    }
+
+    /// Removes the breakpoint of the address, returning false if there was none
+    fn remove_breakpoint(&mut self, address: Address) -> bool {
+        self.oito.remove_breakpoint(address)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Oito(status={:?}, instructions={})",
+            self.oito.status().to_string(),
+            self.oito.instructions()
+        )
+    }
+}
+
+impl Oito {
+    /// Returns the emulator
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Returns true if the runs must stop
+    fn is_paused(&self) -> bool {
+        let status = self.oito.status();
+        status.is_stopped() || matches!(status, Status::Break(_))
+    }
+}
+
+/// Returns the key of the value, raising a ValueError if it's not one of the keypad
+fn parse_key(key: u8) -> PyResult<Key> {
+    Key::try_from(key).map_err(PyValueError::new_err)
+}
+
+/// Returns the exception raised by the emulator as a Python one
+fn emulator(e: Exception) -> PyErr {
+    EmulatorError::new_err(e.to_string())
+}
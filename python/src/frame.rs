@@ -0,0 +1,106 @@
+use std::{
+    ffi::{c_int, c_void, CStr},
+    ptr,
+};
+
+use oito_core::{Pixel, SCREEN_HEIGHT, SCREEN_WIDTH};
+use pyo3::{
+    exceptions::{PyBufferError, PyIndexError},
+    ffi,
+    prelude::*,
+};
+
+/// Format of the items of the buffer, unsigned bytes
+const FORMAT: &CStr = c"B";
+
+/// Copy of a frame of the emulator, a read-only buffer of 32 rows of 64 pixels, 1 if drawn and 0 otherwise.
+/// It implements the buffer protocol, so `numpy.asarray(frame)` is a `(32, 64)` array of `uint8` without copies.
+#[pyclass(module = "oito", frozen)]
+pub struct Frame {
+    pixels: Vec<u8>,
+    /// Dimensions of the buffer, kept here to outlive the views
+    shape: [isize; 2],
+    strides: [isize; 2],
+}
+
+impl Frame {
+    /// Returns the copy of the frame buffer
+    pub fn new(buffer: impl Iterator<Item = Pixel>) -> Self {
+        Self {
+            pixels: buffer.map(|pixel| pixel as u8).collect(),
+            shape: [SCREEN_HEIGHT as isize, SCREEN_WIDTH as isize],
+            strides: [SCREEN_WIDTH as isize, 1],
+        }
+    }
+}
+
+#[pymethods]
+impl Frame {
+    #[getter]
+    fn width(&self) -> usize {
+        SCREEN_WIDTH
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        SCREEN_HEIGHT
+    }
+
+    /// Returns true if the pixel is drawn
+    fn pixel(&self, x: usize, y: usize) -> PyResult<bool> {
+        if x >= SCREEN_WIDTH || y >= SCREEN_HEIGHT {
+            return Err(PyIndexError::new_err(format!(
+                "pixel out of the screen: ({x}, {y})"
+            )));
+        }
+        Ok(self.pixels[y * SCREEN_WIDTH + x] != 0)
+    }
+
+    fn __len__(&self) -> usize {
+        SCREEN_HEIGHT
+    }
+
+    /// Exposes the pixels as a two-dimensional buffer of bytes
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("the view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("the frame is read-only"));
+        }
+        let frame = slf.get();
+        (*view).buf = frame.pixels.as_ptr() as *mut c_void;
+        (*view).len = frame.pixels.len() as isize;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            FORMAT.as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            (*view).ndim = 2;
+            (*view).shape = frame.shape.as_ptr() as *mut _;
+        } else {
+            // Consumers not asking for the shape read it as a flat buffer
+            (*view).ndim = 1;
+            (*view).shape = ptr::null_mut();
+        }
+        (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            frame.strides.as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = ptr::null_mut();
+        (*view).obj = slf.into_any().into_ptr();
+        Ok(())
+    }
+
+    /// The pixels are owned by the frame, there is nothing to release with the views
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+}
@@ -0,0 +1,22 @@
+use pyo3::{create_exception, exceptions::PyException, prelude::*};
+
+pub mod emulator;
+pub mod frame;
+
+create_exception!(
+    oito,
+    EmulatorError,
+    PyException,
+    "Exception raised by the emulator while running the ROM"
+);
+
+/// Python module of the OitoCore Chip-8 emulator
+#[pymodule]
+pub fn oito(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<emulator::Oito>()?;
+    m.add_class::<frame::Frame>()?;
+    m.add("EmulatorError", m.py().get_type::<EmulatorError>())?;
+    m.add("SCREEN_WIDTH", oito_core::SCREEN_WIDTH)?;
+    m.add("SCREEN_HEIGHT", oito_core::SCREEN_HEIGHT)?;
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! Runs the Python test suite with the module embedded in the interpreter of the system,
+//! so the bindings are tested without building and installing the wheel.
+
+use oito::oito;
+use pyo3::{prelude::*, types::PyDict};
+
+#[test]
+fn unittest() {
+    pyo3::append_to_inittab!(oito);
+    Python::with_gil(|py| -> PyResult<()> {
+        let tests = concat!(env!("CARGO_MANIFEST_DIR"), "/tests");
+        py.import("sys")?
+            .getattr("path")?
+            .call_method1("insert", (0, tests))?;
+
+        let unittest = py.import("unittest")?;
+        let suite = unittest
+            .getattr("defaultTestLoader")?
+            .call_method1("loadTestsFromName", ("test_oito",))?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("verbosity", 2)?;
+        let result = unittest
+            .call_method("TextTestRunner", (), Some(&kwargs))?
+            .call_method1("run", (suite,))?;
+        assert!(result.call_method0("wasSuccessful")?.extract::<bool>()?);
+        Ok(())
+    })
+    .unwrap();
+}
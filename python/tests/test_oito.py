@@ -0,0 +1,127 @@
+"""Tests of the Python bindings, run with the standard library only.
+
+Run them with `cargo test`, that embeds the module, or with `python -m unittest discover tests`
+after installing the wheel with `maturin develop`.
+"""
+
+import unittest
+
+import oito
+
+# LD V0, 0x07; LD F, V0; DRW V0, V0, 5; LD V1, 0x20; LD DT, V1; JP 0x20A
+ROM = bytes([0x60, 0x07, 0xF0, 0x29, 0xD0, 0x05, 0x61, 0x20, 0xF1, 0x15, 0x12, 0x0A])
+
+# Sprite of the digit 7 drawn at (7, 7)
+SEVEN = [0xF0, 0x10, 0x20, 0x40, 0x40]
+
+
+class TestOito(unittest.TestCase):
+    def test_run(self):
+        emulator = oito.Oito(ROM)
+        self.assertEqual(0, emulator.instructions)
+        emulator.tick()
+        self.assertEqual(1, emulator.instructions)
+
+        self.assertEqual(1, emulator.run(frames=1, ticks=3))
+        self.assertEqual(4, emulator.instructions)
+
+        # Stops once the ROM jumps to itself
+        self.assertEqual(0, emulator.run(frames=100))
+        self.assertTrue(emulator.stopped)
+        self.assertIn("halted", emulator.status)
+
+        emulator.reset()
+        self.assertEqual(0, emulator.instructions)
+        self.assertFalse(emulator.stopped)
+
+    def test_frame(self):
+        emulator = oito.Oito(ROM)
+        emulator.run(frames=1)
+
+        frame = emulator.frame()
+        self.assertEqual((oito.SCREEN_WIDTH, oito.SCREEN_HEIGHT), (frame.width, frame.height))
+        self.assertTrue(frame.pixel(7, 7))
+        self.assertFalse(frame.pixel(0, 0))
+        with self.assertRaises(IndexError):
+            frame.pixel(64, 0)
+
+        view = memoryview(frame)
+        self.assertEqual((32, 64), view.shape)
+        self.assertEqual("B", view.format)
+        self.assertTrue(view.readonly)
+        self.assertEqual(1, view[7, 7])
+        drawn = [sum(view[7 + row, 7 + column] << (7 - column) for column in range(8)) for row in range(5)]
+        self.assertEqual(SEVEN, drawn)
+        self.assertEqual(4, sum(view.tobytes()[7 * 64 : 8 * 64]))
+
+        rows = emulator.rows()
+        self.assertEqual(32, len(rows))
+        self.assertEqual(SEVEN[0] << (64 - 8 - 7), rows[7])
+        self.assertIn("_XXXX_", emulator.screen())
+
+    def test_keys(self):
+        emulator = oito.Oito()
+        emulator.key_press(0xA)
+        self.assertEqual(0x0400, emulator.keys)
+        emulator.key_release(0xA)
+        self.assertEqual(0, emulator.keys)
+        emulator.keys = 0x8001
+        self.assertEqual(0x8001, emulator.keys)
+        with self.assertRaises(ValueError):
+            emulator.key_press(16)
+
+    def test_memory_and_registers(self):
+        emulator = oito.Oito(ROM)
+        self.assertEqual(ROM[:4], emulator.memory(0x200, 4))
+        emulator.poke(0x300, b"\x01\x02")
+        self.assertEqual(b"\x01\x02", emulator.memory(0x300, 2))
+        with self.assertRaises(oito.EmulatorError):
+            emulator.memory(0xFFF, 2)
+
+        emulator.run(frames=1, ticks=2)
+        registers = emulator.registers()
+        self.assertEqual(7, registers["v"][0])
+        self.assertEqual(35, registers["i"])
+        self.assertEqual(0x204, registers["pc"])
+
+        emulator.set_register("VA", 0x42)
+        emulator.set_register("pc", 0x208)
+        self.assertEqual(0x42, emulator.registers()["v"][0xA])
+        self.assertEqual(0x208, emulator.registers()["pc"])
+        with self.assertRaises(ValueError):
+            emulator.set_register("vg", 1)
+
+    def test_state(self):
+        emulator = oito.Oito(ROM)
+        emulator.run(frames=1, ticks=5)
+        state = emulator.save_state()
+        self.assertIsInstance(state, bytes)
+
+        emulator.run(frames=3)
+        self.assertNotEqual(state, emulator.save_state())
+        emulator.load_state(state)
+        self.assertEqual(state, emulator.save_state())
+        # The delay timer was set to 0x20 and decreased once with the end of the frame
+        self.assertEqual(0x1F, emulator.registers()["dt"])
+
+        with self.assertRaises(ValueError):
+            emulator.load_state(b"OITO")
+
+    def test_seed(self):
+        # RND V0, 0xFF; RND V1, 0xFF; JP 0x204
+        rom = bytes([0xC0, 0xFF, 0xC1, 0xFF, 0x12, 0x04])
+        first = oito.Oito(rom, seed=8)
+        second = oito.Oito(rom, seed=8, recompiler=True)
+        first.run()
+        second.run()
+        self.assertEqual(first.registers()["v"][:2], second.registers()["v"][:2])
+
+    def test_exception(self):
+        # RET with the stack empty
+        emulator = oito.Oito(bytes([0x00, 0xEE]))
+        with self.assertRaises(oito.EmulatorError):
+            emulator.tick()
+
+
+if __name__ == "__main__":
+    unittest.main()
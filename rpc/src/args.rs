@@ -0,0 +1,14 @@
+use structopt::StructOpt;
+
+/// Serve a Chip-8 emulator through JSON-RPC 2.0, one message per line
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    /// The ROM file to load, it can be loaded later with the load method
+    pub file: Option<String>,
+    /// Serves the requests on this port of the loopback interface instead of the standard input and output
+    #[structopt(long = "listen", short = "l")]
+    pub listen: Option<u16>,
+    /// Fx0A waits for the key to be released, like the COSMAC VIP
+    #[structopt(long = "wait-release")]
+    pub wait_release: bool,
+}
@@ -0,0 +1,39 @@
+use oito_core::config::{Config, Quirks};
+use rpc::{server, service::Service};
+use std::{
+    error::Error,
+    fs,
+    io::{stdin, stdout},
+    net::{Ipv4Addr, TcpListener},
+};
+use structopt::StructOpt;
+
+mod args;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = args::Args::from_args();
+
+    let rom = match &args.file {
+        Some(file) => fs::read(file)?,
+        None => Vec::new(),
+    };
+    let mut service = Service::new(
+        Config {
+            quirks: Quirks {
+                wait_release: args.wait_release,
+            },
+            ..Default::default()
+        },
+        &rom,
+    );
+
+    match args.listen {
+        Some(port) => {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+            eprintln!("Listening on {}", listener.local_addr()?);
+            server::serve_tcp(&mut service, listener)?;
+        }
+        None => server::serve(&mut service, stdin().lock(), stdout().lock())?,
+    }
+    Ok(())
+}
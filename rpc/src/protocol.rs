@@ -0,0 +1,117 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Version of the protocol, required in every message
+pub const VERSION: &str = "2.0";
+
+/// Codes of the errors defined by the JSON-RPC 2.0 specification
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// Code of the errors raised by the emulator while running
+pub const EMULATOR_ERROR: i64 = -32000;
+
+/// Call of a method. Requests without id are notifications and get no response.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Request {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default, deserialize_with = "present")]
+    pub id: Option<Value>,
+}
+
+/// Deserializes a field that is present, even if null, as some value
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// Result of a call, with either a result or an error
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct Response {
+    pub jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Error>,
+    pub id: Value,
+}
+
+impl Response {
+    /// Returns the response of the call with the outcome of the method
+    pub fn new(id: Value, outcome: Result<Value, Error>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: VERSION,
+            result,
+            error,
+            id,
+        }
+    }
+}
+
+/// Failure of a call
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Error {
+    pub code: i64,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the error of invalid parameters with the message
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{Error, Request, Response, METHOD_NOT_FOUND};
+
+    #[test]
+    fn request() {
+        let request: Request =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "method": "reset", "id": 1})).unwrap();
+        assert_eq!("reset", request.method);
+        assert!(request.params.is_null());
+        assert_eq!(Some(json!(1)), request.id);
+
+        let notification: Request = serde_json::from_value(
+            json!({"jsonrpc": "2.0", "method": "press", "params": {"key": 5}}),
+        )
+        .unwrap();
+        assert_eq!(None, notification.id);
+        let null: Request =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "method": "reset", "id": null}))
+                .unwrap();
+        assert_eq!(Some(json!(null)), null.id);
+    }
+
+    #[test]
+    fn response() {
+        let response = Response::new(json!(1), Ok(json!(null)));
+        assert_eq!(
+            json!({"jsonrpc": "2.0", "result": null, "id": 1}),
+            serde_json::to_value(response).unwrap()
+        );
+        let response = Response::new(json!("a"), Err(Error::new(METHOD_NOT_FOUND, "jump")));
+        assert_eq!(
+            json!({"jsonrpc": "2.0", "error": {"code": -32601, "message": "jump"}, "id": "a"}),
+            serde_json::to_value(response).unwrap()
+        );
+    }
+}
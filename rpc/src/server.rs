@@ -0,0 +1,193 @@
+use std::{
+    io::{self, BufRead, Write},
+    net::TcpListener,
+};
+
+use serde_json::Value;
+
+use crate::{
+    protocol::{Error, Request, Response, INVALID_REQUEST, PARSE_ERROR, VERSION},
+    service::Service,
+};
+
+/// Serves the requests of the reader, one per line, writing a line with the response of each one.
+/// Stops once the reader is closed.
+pub fn serve(
+    service: &mut Service,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle(service, &line) {
+            writeln!(writer, "{response}")?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Serves the connections of the listener one after the other, all of them sharing the emulator.
+/// The errors of a connection, like a reset or a line that isn't UTF-8, are reported and only close it.
+pub fn serve_tcp(service: &mut Service, listener: TcpListener) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let served = stream
+            .and_then(|stream| serve(service, io::BufReader::new(stream.try_clone()?), stream));
+        if let Err(e) = served {
+            eprintln!("Connection closed: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Returns the response to the line, a single request or a batch of them.
+/// Returns none if there is nothing to respond, when the line only has notifications.
+pub fn handle(service: &mut Service, line: &str) -> Option<String> {
+    let response = match serde_json::from_str::<Value>(line) {
+        Ok(Value::Array(batch)) if !batch.is_empty() => {
+            let responses: Vec<Response> = batch
+                .into_iter()
+                .filter_map(|request| call(service, request))
+                .collect();
+            if responses.is_empty() {
+                return None;
+            }
+            serde_json::to_value(responses)
+        }
+        Ok(Value::Array(_)) => serde_json::to_value(failure(INVALID_REQUEST, "empty batch")),
+        Ok(request) => serde_json::to_value(call(service, request)?),
+        Err(e) => serde_json::to_value(failure(PARSE_ERROR, e.to_string())),
+    };
+    Some(response.expect("responses are serializable").to_string())
+}
+
+/// Returns the response to the request, none if it's a notification
+fn call(service: &mut Service, request: Value) -> Option<Response> {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(Response::new(
+                id,
+                Err(Error::new(INVALID_REQUEST, e.to_string())),
+            ))
+        }
+    };
+    if request.jsonrpc != VERSION {
+        return Some(Response::new(
+            id,
+            Err(Error::new(INVALID_REQUEST, "unsupported jsonrpc version")),
+        ));
+    }
+    let outcome = service.call(&request.method, request.params);
+    request.id.map(|id| Response::new(id, outcome))
+}
+
+/// Returns the response of a failure without request id
+fn failure(code: i64, message: impl Into<String>) -> Response {
+    Response::new(Value::Null, Err(Error::new(code, message)))
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::config::Config;
+    use serde_json::{json, Value};
+
+    use crate::service::Service;
+
+    use super::{handle, serve};
+
+    /// LD V0, 0x07; JP 0x202
+    const ROM: [u8; 4] = [0x60, 0x07, 0x12, 0x02];
+
+    fn respond(service: &mut Service, line: &str) -> Value {
+        serde_json::from_str(&handle(service, line).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn single() {
+        let mut service = Service::new(Config::default(), &ROM);
+        assert_eq!(
+            json!({"jsonrpc": "2.0", "result": null, "id": 1}),
+            respond(
+                &mut service,
+                r#"{"jsonrpc": "2.0", "method": "reset", "id": 1}"#
+            )
+        );
+        assert_eq!(
+            json!(-32601),
+            respond(
+                &mut service,
+                r#"{"jsonrpc": "2.0", "method": "jump", "id": 2}"#
+            )["error"]["code"]
+        );
+        // Notifications are run without response
+        assert_eq!(
+            None,
+            handle(
+                &mut service,
+                r#"{"jsonrpc": "2.0", "method": "press", "params": {"key": 1}}"#
+            )
+        );
+        assert_eq!(0x0002, service.oito().keys());
+    }
+
+    #[test]
+    fn batch() {
+        let mut service = Service::new(Config::default(), &ROM);
+        let responses = respond(
+            &mut service,
+            r#"[
+                {"jsonrpc": "2.0", "method": "run", "params": {"frames": 1}},
+                {"jsonrpc": "2.0", "method": "registers", "id": "regs"},
+                {"jsonrpc": "2.0", "method": "status", "id": 3}
+            ]"#,
+        );
+        assert_eq!(2, responses.as_array().unwrap().len());
+        assert_eq!(json!("regs"), responses[0]["id"]);
+        assert_eq!(json!(7), responses[0]["result"]["v"][0]);
+        assert_eq!(json!(3), responses[1]["id"]);
+
+        assert_eq!(
+            None,
+            handle(&mut service, r#"[{"jsonrpc": "2.0", "method": "reset"}]"#)
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let mut service = Service::new(Config::default(), &ROM);
+        let parse = respond(&mut service, "{");
+        assert_eq!(json!(-32700), parse["error"]["code"]);
+        assert_eq!(Value::Null, parse["id"]);
+        assert_eq!(json!(-32600), respond(&mut service, "[]")["error"]["code"]);
+        let version = respond(
+            &mut service,
+            r#"{"jsonrpc": "1.0", "method": "reset", "id": 4}"#,
+        );
+        assert_eq!(json!(-32600), version["error"]["code"]);
+        assert_eq!(json!(4), version["id"]);
+        assert_eq!(
+            json!(-32600),
+            respond(&mut service, r#"{"jsonrpc": "2.0", "id": 5}"#)["error"]["code"]
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let mut service = Service::new(Config::default(), &ROM);
+        let input = r#"{"jsonrpc": "2.0", "method": "reset", "id": 1}
+
+{"jsonrpc": "2.0", "method": "status", "id": 2}
+"#;
+        let mut output = Vec::new();
+        serve(&mut service, input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(2, lines.len());
+        assert!(lines[1].contains(r#""id":2"#));
+    }
+}
@@ -0,0 +1,504 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use oito_core::{
+    config::Config,
+    core::{OitoCore, Register, State, Status},
+    exception::Exception,
+    key::Key,
+    Address, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+
+use crate::protocol::{Error, EMULATOR_ERROR, METHOD_NOT_FOUND};
+
+/// Instructions run per frame by default
+const TICKS_PER_FRAME: usize = 10;
+
+/// Emulator exposed through the methods of the JSON-RPC server
+#[derive(Debug)]
+pub struct Service {
+    oito: OitoCore,
+    /// Settings used to rebuild the emulator in the loads and resets
+    config: Config,
+    /// ROM being run
+    rom: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoadParams {
+    rom: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyParams {
+    key: KeyParam,
+}
+
+/// Key of the keypad, by value or by hexadecimal digit
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyParam {
+    Value(u8),
+    Digit(String),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RunParams {
+    #[serde(default = "one")]
+    frames: usize,
+    #[serde(default = "ticks_per_frame")]
+    ticks: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScreenParams {
+    #[serde(default)]
+    format: ScreenFormat,
+}
+
+/// Encodings of the screen
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ScreenFormat {
+    /// Packed rows, eight bytes per row with the leftmost pixel as the most significant bit, in base64
+    #[default]
+    Base64,
+    /// One hexadecimal string per row with the leftmost pixel as the most significant bit
+    Bitmask,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReadMemoryParams {
+    address: Address,
+    length: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WriteMemoryParams {
+    address: Address,
+    data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetRegisterParams {
+    register: String,
+    value: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StateParams {
+    state: String,
+}
+
+fn one() -> usize {
+    1
+}
+
+fn ticks_per_frame() -> usize {
+    TICKS_PER_FRAME
+}
+
+impl Service {
+    /// Returns a new service running the ROM in an emulator built with the settings
+    pub fn new(config: Config, rom: &[u8]) -> Self {
+        let mut oito = OitoCore::with_config(config.clone());
+        oito.load(rom);
+        Self {
+            oito,
+            config,
+            rom: rom.to_vec(),
+        }
+    }
+
+    /// Returns the emulator
+    pub fn oito(&self) -> &OitoCore {
+        &self.oito
+    }
+
+    /// Runs the method with the parameters and returns its result
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value, Error> {
+        match method {
+            "load" => {
+                let params: LoadParams = parse(params)?;
+                let rom = match (params.rom, params.path) {
+                    (Some(rom), None) => decode(&rom)?,
+                    (None, Some(path)) => std::fs::read(&path)
+                        .map_err(|e| Error::invalid_params(format!("{path}: {e}")))?,
+                    _ => return Err(Error::invalid_params("expected either rom or path")),
+                };
+                self.rom = rom;
+                self.reset();
+                Ok(Value::Null)
+            }
+            "reset" => {
+                parse::<Empty>(params)?;
+                self.reset();
+                Ok(Value::Null)
+            }
+            "press" => {
+                self.oito
+                    .key_press(parse::<KeyParams>(params)?.key.try_into()?);
+                Ok(Value::Null)
+            }
+            "release" => {
+                self.oito
+                    .key_release(parse::<KeyParams>(params)?.key.try_into()?);
+                Ok(Value::Null)
+            }
+            "run" => {
+                let params: RunParams = parse(params)?;
+                let frames = self.run(params.frames, params.ticks).map_err(emulator)?;
+                Ok(json!({
+                    "frames": frames,
+                    "instructions": self.oito.instructions(),
+                    "status": self.oito.status().to_string(),
+                }))
+            }
+            "screen" => {
+                let params: ScreenParams = parse(params)?;
+                let rows = self.oito.frame_rows();
+                let data = match params.format {
+                    ScreenFormat::Base64 => {
+                        let bytes: Vec<u8> =
+                            rows.iter().flat_map(|row| row.to_be_bytes()).collect();
+                        json!(STANDARD.encode(bytes))
+                    }
+                    ScreenFormat::Bitmask => {
+                        json!(rows
+                            .iter()
+                            .map(|row| format!("{row:016x}"))
+                            .collect::<Vec<_>>())
+                    }
+                };
+                Ok(json!({ "width": SCREEN_WIDTH, "height": SCREEN_HEIGHT, "data": data }))
+            }
+            "read_memory" => {
+                let params: ReadMemoryParams = parse(params)?;
+                let content = self
+                    .oito
+                    .memory(params.address, params.length)
+                    .map_err(invalid)?;
+                Ok(json!({ "data": STANDARD.encode(content) }))
+            }
+            "write_memory" => {
+                let params: WriteMemoryParams = parse(params)?;
+                self.oito
+                    .poke(params.address, &decode(&params.data)?)
+                    .map_err(invalid)?;
+                Ok(Value::Null)
+            }
+            "registers" => {
+                parse::<Empty>(params)?;
+                let registers = self.oito.registers();
+                Ok(json!({
+                    "v": registers.v,
+                    "i": registers.i,
+                    "pc": registers.pc,
+                    "sp": registers.sp,
+                    "dt": registers.dt,
+                    "st": registers.st,
+                }))
+            }
+            "set_register" => {
+                let params: SetRegisterParams = parse(params)?;
+                let register: Register = params.register.parse().map_err(Error::invalid_params)?;
+                self.oito.set_register(register, params.value);
+                Ok(Value::Null)
+            }
+            "save_state" => {
+                parse::<Empty>(params)?;
+                Ok(json!({ "state": STANDARD.encode(self.oito.save_state().to_bytes()) }))
+            }
+            "load_state" => {
+                let params: StateParams = parse(params)?;
+                let state = State::try_from(decode(&params.state)?.as_slice()).map_err(invalid)?;
+                self.oito.load_state(&state);
+                Ok(Value::Null)
+            }
+            "status" => {
+                parse::<Empty>(params)?;
+                Ok(json!({
+                    "status": self.oito.status().to_string(),
+                    "instructions": self.oito.instructions(),
+                    "sound": self.oito.sound(),
+                }))
+            }
+            _ => Err(Error::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        }
+    }
+
+    /// Restarts the ROM in a new emulator built with the same settings
+    fn reset(&mut self) {
+        self.oito = OitoCore::with_config(self.config.clone());
+        self.oito.load(&self.rom);
+    }
+
+    /// Runs the frames, stopping before if the emulator stops. Returns the number of frames run.
+    fn run(&mut self, frames: usize, ticks: usize) -> Result<usize, Exception> {
+        for frame in 0..frames {
+            for _ in 0..ticks {
+                self.oito.tick()?;
+                if self.stopped() {
+                    return Ok(frame);
+                }
+            }
+            self.oito.frame_tick();
+        }
+        Ok(frames)
+    }
+
+    /// Returns true if the emulator won't keep running without intervention
+    fn stopped(&self) -> bool {
+        let status = self.oito.status();
+        status.is_stopped() || matches!(status, Status::Break(_))
+    }
+}
+
+/// Parameters of the methods without parameters
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Empty {}
+
+impl TryFrom<KeyParam> for Key {
+    type Error = Error;
+
+    fn try_from(key: KeyParam) -> Result<Self, Self::Error> {
+        let value = match key {
+            KeyParam::Value(value) => value,
+            KeyParam::Digit(digit) => u8::from_str_radix(&digit, 16)
+                .map_err(|_| Error::invalid_params(format!("invalid key: {digit}")))?,
+        };
+        Key::try_from(value).map_err(Error::invalid_params)
+    }
+}
+
+/// Returns the parameters of the method, taking the missing ones as an empty object
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, Error> {
+    let params = match params {
+        Value::Null => json!({}),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|e| Error::invalid_params(e.to_string()))
+}
+
+/// Returns the bytes encoded in base64
+fn decode(data: &str) -> Result<Vec<u8>, Error> {
+    STANDARD
+        .decode(data)
+        .map_err(|e| Error::invalid_params(format!("invalid base64: {e}")))
+}
+
+/// Returns the exception of the parameters as an error
+fn invalid(exception: Exception) -> Error {
+    Error::invalid_params(exception.to_string())
+}
+
+/// Returns the exception of the execution as an error
+fn emulator(exception: Exception) -> Error {
+    Error::new(EMULATOR_ERROR, exception.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde_json::{json, Value};
+
+    use oito_core::config::Config;
+
+    use crate::protocol::{EMULATOR_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND};
+
+    use super::Service;
+
+    /// LD V0, 0x07; LD I, 0x300; LD [I], V0; LD F, V0; DRW V0, V0, 5; JP 0x20A
+    const ROM: [u8; 12] = [
+        0x60, 0x07, 0xA3, 0x00, 0xF0, 0x55, 0xF0, 0x29, 0xD0, 0x05, 0x12, 0x0A,
+    ];
+
+    fn call(service: &mut Service, method: &str, params: Value) -> Value {
+        service.call(method, params).unwrap()
+    }
+
+    fn error(service: &mut Service, method: &str, params: Value) -> i64 {
+        service.call(method, params).unwrap_err().code
+    }
+
+    #[test]
+    fn run() {
+        let mut service = Service::new(Config::default(), &ROM);
+        let result = call(&mut service, "run", json!({"frames": 1, "ticks": 3}));
+        assert_eq!(json!(1), result["frames"]);
+        assert_eq!(json!(3), result["instructions"]);
+
+        // Stops once the ROM halts
+        let result = call(&mut service, "run", json!({"frames": 100}));
+        assert_eq!(json!(0), result["frames"]);
+        assert_eq!(json!("halted jumping to itself"), result["status"]);
+        assert_eq!(
+            json!({"status": "halted jumping to itself", "instructions": 6, "sound": false}),
+            call(&mut service, "status", Value::Null)
+        );
+
+        call(&mut service, "reset", Value::Null);
+        assert_eq!(
+            json!(0),
+            call(&mut service, "status", json!({}))["instructions"]
+        );
+    }
+
+    #[test]
+    fn screen() {
+        let mut service = Service::new(Config::default(), &ROM);
+        call(&mut service, "run", Value::Null);
+
+        let bitmask = call(&mut service, "screen", json!({"format": "bitmask"}));
+        assert_eq!(json!(64), bitmask["width"]);
+        let rows = bitmask["data"].as_array().unwrap();
+        assert_eq!(32, rows.len());
+        // Top of the 7 of the font, drawn at (7, 7)
+        assert_eq!(json!("01e0000000000000"), rows[7]);
+
+        let base64 = call(&mut service, "screen", Value::Null);
+        let bytes = STANDARD.decode(base64["data"].as_str().unwrap()).unwrap();
+        assert_eq!(256, bytes.len());
+        assert_eq!([0x01, 0xE0], bytes[56..58]);
+        assert_eq!(
+            INVALID_PARAMS,
+            error(&mut service, "screen", json!({"format": "png"}))
+        );
+    }
+
+    #[test]
+    fn memory_and_registers() {
+        let mut service = Service::new(Config::default(), &ROM);
+        assert_eq!(
+            json!({"data": "YAejAA=="}),
+            call(
+                &mut service,
+                "read_memory",
+                json!({"address": 0x200, "length": 4})
+            )
+        );
+        call(
+            &mut service,
+            "write_memory",
+            json!({"address": 0x300, "data": "vu8="}),
+        );
+        assert_eq!(
+            json!({"data": "vu8="}),
+            call(
+                &mut service,
+                "read_memory",
+                json!({"address": 0x300, "length": 2})
+            )
+        );
+        assert_eq!(
+            INVALID_PARAMS,
+            error(
+                &mut service,
+                "read_memory",
+                json!({"address": 0xFFF, "length": 2})
+            )
+        );
+        assert_eq!(
+            INVALID_PARAMS,
+            error(
+                &mut service,
+                "write_memory",
+                json!({"address": 0x300, "data": "*"})
+            )
+        );
+
+        call(
+            &mut service,
+            "set_register",
+            json!({"register": "v3", "value": 0x2A}),
+        );
+        call(
+            &mut service,
+            "set_register",
+            json!({"register": "pc", "value": 0x204}),
+        );
+        let registers = call(&mut service, "registers", Value::Null);
+        assert_eq!(json!(0x2A), registers["v"][3]);
+        assert_eq!(json!(0x204), registers["pc"]);
+        assert_eq!(
+            INVALID_PARAMS,
+            error(
+                &mut service,
+                "set_register",
+                json!({"register": "x", "value": 1})
+            )
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let mut service = Service::new(Config::default(), &ROM);
+        call(&mut service, "press", json!({"key": 5}));
+        call(&mut service, "press", json!({"key": "a"}));
+        assert_eq!(0x0420, service.oito().keys());
+        call(&mut service, "release", json!({"key": "5"}));
+        assert_eq!(0x0400, service.oito().keys());
+        assert_eq!(
+            INVALID_PARAMS,
+            error(&mut service, "press", json!({"key": 16}))
+        );
+        assert_eq!(
+            INVALID_PARAMS,
+            error(&mut service, "press", json!({"key": "g"}))
+        );
+        assert_eq!(INVALID_PARAMS, error(&mut service, "press", Value::Null));
+    }
+
+    #[test]
+    fn state() {
+        let mut service = Service::new(Config::default(), &ROM);
+        call(&mut service, "run", json!({"frames": 1, "ticks": 2}));
+        let state = call(&mut service, "save_state", Value::Null);
+
+        call(&mut service, "run", Value::Null);
+        // The font sprite of the 7 is pointed by I
+        assert_eq!(json!(35), call(&mut service, "registers", Value::Null)["i"]);
+        call(&mut service, "load_state", state);
+        assert_eq!(
+            json!(0x300),
+            call(&mut service, "registers", Value::Null)["i"]
+        );
+        assert_eq!(
+            INVALID_PARAMS,
+            error(&mut service, "load_state", json!({"state": "AAAA"}))
+        );
+    }
+
+    #[test]
+    fn load() {
+        let mut service = Service::new(Config::default(), &ROM);
+        // LD V0, 0x05; 0xFFFF
+        call(&mut service, "load", json!({"rom": "YAX//w=="}));
+        assert_eq!(EMULATOR_ERROR, error(&mut service, "run", Value::Null));
+        assert_eq!(
+            json!(5),
+            call(&mut service, "registers", Value::Null)["v"][0]
+        );
+
+        assert_eq!(INVALID_PARAMS, error(&mut service, "load", json!({})));
+        assert_eq!(
+            INVALID_PARAMS,
+            error(&mut service, "load", json!({"path": "missing.ch8"}))
+        );
+        assert_eq!(METHOD_NOT_FOUND, error(&mut service, "jump", Value::Null));
+    }
+}
@@ -0,0 +1,93 @@
+//! Session of a client automating the emulator through the loopback socket,
+//! checking that consecutive connections share the same emulator and outlive the failed ones.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    thread,
+};
+
+use oito_core::config::Config;
+use rpc::{server, service::Service};
+use serde_json::{json, Value};
+
+/// LD V0, 0x07; LD F, V0; DRW V0, V0, 5; JP 0x206
+const ROM: [u8; 8] = [0x60, 0x07, 0xF0, 0x29, 0xD0, 0x05, 0x12, 0x06];
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    id: u64,
+}
+
+impl Client {
+    fn connect(port: u16) -> Self {
+        let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        Self {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+            id: 0,
+        }
+    }
+
+    /// Calls the method and returns its result
+    fn call(&mut self, method: &str, params: Value) -> Value {
+        self.id += 1;
+        let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": self.id});
+        writeln!(self.writer, "{request}").unwrap();
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json!(self.id), response["id"]);
+        assert_eq!(None, response.get("error"), "{method} failed");
+        response["result"].clone()
+    }
+}
+
+/// Starts a server in the background and returns its port
+fn listen() -> u16 {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        let mut service = Service::new(Config::default(), &ROM);
+        server::serve_tcp(&mut service, listener).unwrap();
+    });
+    port
+}
+
+#[test]
+fn session() {
+    let port = listen();
+
+    let mut client = Client::connect(port);
+    let run = client.call("run", json!({"frames": 2}));
+    assert_eq!(json!("halted jumping to itself"), run["status"]);
+    let screen = client.call("screen", json!({"format": "bitmask"}));
+    assert_eq!(json!("01e0000000000000"), screen["data"][7]);
+    let state = client.call("save_state", Value::Null);
+    client.call("reset", Value::Null);
+    drop(client);
+
+    // The next connection continues with the same emulator
+    let mut client = Client::connect(port);
+    assert_eq!(json!(0), client.call("status", Value::Null)["instructions"]);
+    client.call("load_state", state);
+    assert_eq!(json!(4), client.call("status", Value::Null)["instructions"]);
+    assert_eq!(
+        json!({"data": "YAc="}),
+        client.call("read_memory", json!({"address": 0x200, "length": 2}))
+    );
+}
+
+#[test]
+fn failed_connection() {
+    let port = listen();
+
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+    stream.write_all(b"\xFF\xFE\n").unwrap();
+    drop(stream);
+
+    // The server keeps serving the next connections
+    let mut client = Client::connect(port);
+    assert_eq!(json!(0), client.call("status", Value::Null)["instructions"]);
+}
@@ -1,6 +1,8 @@
-use std::str::FromStr;
-
-use sdl2::pixels::Color;
+use oito_core::{
+    config::Protection,
+    fontset::Font,
+    video::{Filter, Rgb},
+};
 use structopt::StructOpt;
 
 /// Customize the emmulator
@@ -10,35 +12,29 @@ pub struct Args {
     pub file: String,
     /// Background color
     #[structopt(long = "bg", short = "b", default_value = "#000000")]
-    pub bg: CliColor,
+    pub bg: Rgb,
     /// Foreground color
     #[structopt(long = "fg", short = "f", default_value = "#FFFFFF")]
-    pub fg: CliColor,
+    pub fg: Rgb,
     /// Scale to apply to the screen
     #[structopt(long = "scale", short = "s", default_value = "20")]
     pub scale: u32,
-}
-
-#[derive(Debug)]
-pub struct CliColor(u8, u8, u8);
-
-impl FromStr for CliColor {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let from = if s.starts_with("#") { 1 } else { 0 };
-		let str = &s[from..];
-
-		let r: u8 = u8::from_str_radix(&str[0..=1], 16).unwrap_or_default();
-        let g: u8 = u8::from_str_radix(&str[2..=3], 16).unwrap_or_default();
-        let b: u8 = u8::from_str_radix(&str[4..=5], 16).unwrap_or_default();
-
-        Ok(CliColor(r, g, b))
-    }
-}
-
-impl Into<Color> for CliColor {
-    fn into(self) -> Color {
-        Color::RGB(self.0, self.1, self.2)
-    }
+    /// Filter to reduce the flickering: none, phosphor[:decay] or blend[:frames]
+    #[structopt(long = "filter", default_value = "none")]
+    pub filter: Filter,
+    /// Memory protected against the writes of the ROM as REGION[:ACTION], like font or rom:ignore. Can be repeated.
+    #[structopt(long = "protect")]
+    pub protected: Vec<Protection>,
+    /// Built-in font: modern, vip, dream6800, eti660, fishnchips or schip
+    #[structopt(long = "font", default_value = "modern")]
+    pub font: Font,
+    /// File with a custom font: the 80 bytes of the small font, optionally followed by the 160 of the big one
+    #[structopt(long = "font-file")]
+    pub font_file: Option<String>,
+    /// Fx0A waits for the key to be released, like the COSMAC VIP
+    #[structopt(long = "wait-release")]
+    pub wait_release: bool,
+    /// File binding the arrows and space to the keys used by each ROM to move and fire
+    #[structopt(long = "actions")]
+    pub actions: Option<String>,
 }
@@ -1,27 +1,61 @@
-use oito_core::key::Key;
-use sdl2::keyboard::Scancode;
+use oito_frontend::{
+	io::{Input, InputSource},
+	keymap::{Keymap, PhysicalKey},
+};
+use sdl2::{event::Event, keyboard::Scancode, EventPump};
 
-pub fn map_key(scancode: Option<Scancode>) -> Option<Key> {
-	if scancode.is_none() {
-		return None;
+/// Keyboard of the SDL2 window, translated through the keymap
+pub struct Keyboard {
+	pub pump: EventPump,
+	pub keymap: Keymap,
+}
+
+impl InputSource for Keyboard {
+	fn poll(&mut self) -> Vec<Input> {
+		let keymap = self.keymap;
+		self.pump
+			.poll_iter()
+			.filter_map(|event| match event {
+				Event::Quit { .. } => Some(Input::Quit),
+				Event::KeyDown { scancode, repeat: false, .. } => {
+					keymap.input(map_scancode(scancode?)?, true)
+				}
+				Event::KeyUp { scancode, .. } => keymap.input(map_scancode(scancode?)?, false),
+				_ => None,
+			})
+			.collect()
 	}
-	match scancode.unwrap() {
-		Scancode::Num1 => Some(Key::One),
-		Scancode::Num2 => Some(Key::Two),
-		Scancode::Num3 => Some(Key::Three),
-		Scancode::Num4 => Some(Key::C),
-		Scancode::Q => Some(Key::Four),
-		Scancode::W => Some(Key::Five),
-		Scancode::E => Some(Key::Six),
-		Scancode::R => Some(Key::D),
-		Scancode::A => Some(Key::Seven),
-		Scancode::S => Some(Key::Eight),
-		Scancode::D => Some(Key::Nine),
-		Scancode::F => Some(Key::E),
-		Scancode::Z => Some(Key::A),
-		Scancode::X => Some(Key::Zero),
-		Scancode::C => Some(Key::B),
-		Scancode::V => Some(Key::F),
+}
+
+/// Returns the host key of the scancode
+fn map_scancode(scancode: Scancode) -> Option<PhysicalKey> {
+	match scancode {
+		Scancode::Num1 => Some(PhysicalKey::Digit1),
+		Scancode::Num2 => Some(PhysicalKey::Digit2),
+		Scancode::Num3 => Some(PhysicalKey::Digit3),
+		Scancode::Num4 => Some(PhysicalKey::Digit4),
+		Scancode::Q => Some(PhysicalKey::Q),
+		Scancode::W => Some(PhysicalKey::W),
+		Scancode::E => Some(PhysicalKey::E),
+		Scancode::R => Some(PhysicalKey::R),
+		Scancode::A => Some(PhysicalKey::A),
+		Scancode::S => Some(PhysicalKey::S),
+		Scancode::D => Some(PhysicalKey::D),
+		Scancode::F => Some(PhysicalKey::F),
+		Scancode::Z => Some(PhysicalKey::Z),
+		Scancode::X => Some(PhysicalKey::X),
+		Scancode::C => Some(PhysicalKey::C),
+		Scancode::V => Some(PhysicalKey::V),
+		Scancode::Up => Some(PhysicalKey::Up),
+		Scancode::Down => Some(PhysicalKey::Down),
+		Scancode::Left => Some(PhysicalKey::Left),
+		Scancode::Right => Some(PhysicalKey::Right),
+		Scancode::Space => Some(PhysicalKey::Space),
+		Scancode::Return => Some(PhysicalKey::Enter),
+		Scancode::Escape => Some(PhysicalKey::Escape),
+		Scancode::F5 => Some(PhysicalKey::F5),
+		Scancode::F6 => Some(PhysicalKey::F6),
+		Scancode::F7 => Some(PhysicalKey::F7),
 		_ => None
 	}
-}
\ No newline at end of file
+}
@@ -1,24 +1,28 @@
-use input::map_key;
-use oito_core::core::OitoCore;
-use render::Renderer;
+use input::Keyboard;
+use oito_core::{
+    action::ActionProfiles,
+    config::{Config, Quirks},
+    fontset::Font,
+};
+use oito_frontend::{keymap::Keymap, runner::Runner};
+use render::{Renderer, Screen};
 use rom_loader::{desktop::FilePathLoader, RomLoader};
-use sdl2::{event::Event, keyboard::Scancode};
-use sound::{SOUND_SPEC, Beep};
+use sound::{SOUND_SPEC, Beep, Speaker};
 use structopt::StructOpt;
-use std::error::Error;
+use std::{error::Error, fs, path::Path};
 
 mod args;
 mod input;
 mod render;
 mod sound;
 
-const TICKS_PER_FRAME: usize = 10;
 const SOUND_PATH: &str = "./res/beep.wav";
 
 fn main() -> Result<(), Box<dyn Error>> {
 	let args = args::Args::from_args();
+	simple_logger::init_with_level(log::Level::Warn)?;
 
-    let renderer = Renderer::new(args.scale, args.bg.into(), args.fg.into());
+    let renderer = Renderer::new(args.scale, args.bg, args.fg, args.filter);
 
     let sdl = sdl2::init().expect("error during SDL2 initialization");
     let video = sdl.video().expect("error during video setup");
@@ -44,51 +48,45 @@ fn main() -> Result<(), Box<dyn Error>> {
     canvas.clear();
     canvas.present();
 
-    let mut event_pump = sdl
+    let texture_creator = canvas.texture_creator();
+    let texture = renderer.texture(&texture_creator);
+    let mut screen = Screen { renderer, canvas, texture };
+
+    let pump = sdl
         .event_pump()
         .expect("error obtaining the event SDL2 event pump");
 
 	let beep = Beep::new(SOUND_PATH.to_string());
-	let audio = audio.open_playback(None, &SOUND_SPEC, move |_| {
+	let mut speaker = Speaker(audio.open_playback(None, &SOUND_SPEC, move |_| {
 		beep
-	}).expect("error during audio device setup");
+	}).expect("error during audio device setup"));
 
-    let mut oito = OitoCore::new();
+    let font = match &args.font_file {
+        Some(path) => Font::from_bytes(&fs::read(path)?).ok_or("invalid font file size")?,
+        None => args.font,
+    };
+    let config = Config {
+        protected: args.protected,
+        font,
+        quirks: Quirks {
+            wait_release: args.wait_release,
+        },
+        ..Default::default()
+    };
     let loader = FilePathLoader::new(&args.file);
-    oito.load(loader.rom());
-    
-
-    'gameloop: loop {
-        for e in event_pump.poll_iter() {
-            match e {
-                Event::Quit { .. } | Event::KeyDown{scancode: Some(Scancode::Escape), ..} => {
-                    break 'gameloop;
-                }
-				Event::KeyDown { scancode, .. } => {
-					if let Some(key) = map_key(scancode) {
-						oito.key_press(key);
-					}
-				}
-				Event::KeyUp { scancode, .. } => {
-					if let Some(key) = map_key(scancode) {
-						oito.key_release(key);
-					}
-				}
-                _ => {}
-            }
-        }
-
-		for _ in 0..TICKS_PER_FRAME {
-			oito.tick()?;
-		}
-		oito.frame_tick();
+    let mut runner = Runner::new(config, loader.rom());
 
-		if oito.sound() {
-			audio.resume();
-		}
+    let profiles: ActionProfiles = match &args.actions {
+        Some(path) => fs::read_to_string(path)?.parse()?,
+        None => ActionProfiles::default(),
+    };
+    let rom_name = Path::new(&args.file).file_stem().and_then(|name| name.to_str());
+    let mut keyboard = Keyboard {
+        pump,
+        keymap: Keymap::new(profiles.map(rom_name.unwrap_or_default())),
+    };
 
-        renderer.draw_frame(&oito, &mut canvas);
-    }
+    runner.run(&mut screen, &mut speaker, &mut keyboard)?;
 
     Ok(())
 }
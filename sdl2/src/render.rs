@@ -1,15 +1,41 @@
-use oito_core::{core::OitoCore, SCREEN_HEIGHT, SCREEN_WIDTH};
-use sdl2::{pixels::Color, rect::Rect, render::Canvas, video::Window};
+use oito_core::{
+    core::OitoCore,
+    damage::Damage,
+    video::{Filter, FrameConverter, FrameFilter, Palette, PixelFormat, Rgb, Upscaler},
+    SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+use oito_frontend::io::VideoSink;
+use sdl2::{
+    pixels::PixelFormatEnum,
+    rect::Rect,
+    render::{Canvas, Texture, TextureCreator},
+    video::{Window, WindowContext},
+};
 
 pub struct Renderer {
     scale: u32,
-    bg: Color,
-    fg: Color,
+    filter: FrameFilter,
+    /// Converter of the frames, or of their filtered intensities, into the texture content
+    converter: FrameConverter,
+    /// Last converted frame
+    image: Vec<u8>,
 }
 
 impl Renderer {
-    pub fn new(scale: u32, bg: Color, fg: Color) -> Self {
-        Self { scale, bg, fg }
+    pub fn new(scale: u32, bg: Rgb, fg: Rgb, filter: Filter) -> Self {
+        let palette = match filter {
+            Filter::None => Palette::mono(bg, fg),
+            _ => Palette::gradient(bg, fg),
+        };
+        let converter = FrameConverter::new(palette, PixelFormat::Rgba8888, Upscaler::default());
+        let mut image = vec![0; converter.buffer_size()];
+        converter.convert(&[0; SCREEN_HEIGHT], &mut image);
+        Self {
+            scale,
+            filter: FrameFilter::new(filter),
+            converter,
+            image,
+        }
     }
 
     pub fn scaled_width(&self) -> u32 {
@@ -20,22 +46,76 @@ impl Renderer {
         self.scale * SCREEN_HEIGHT as u32
     }
 
-    /// Draws the current Oito frame into the SDL2 Canvas
-    pub fn draw_frame(&self, oito: &OitoCore, canvas: &mut Canvas<Window>) {
-        canvas.set_draw_color(self.bg);
-        canvas.clear();
-
-        canvas.set_draw_color(self.fg);
-        for (i, pixel) in oito.frame_buffer().iter().enumerate() {
-            if *pixel {
-                let x = (i % SCREEN_WIDTH) as i32 * self.scale as i32;
-                let y = (i / SCREEN_WIDTH) as i32 * self.scale as i32;
-                let rect = Rect::new(x, y, self.scale, self.scale);
+    /// Builds the texture holding the Oito frame, filled with the background color
+    pub fn texture<'a>(&self, creator: &'a TextureCreator<WindowContext>) -> Texture<'a> {
+        let mut texture = creator
+            .create_texture_streaming(
+                PixelFormatEnum::RGBA32,
+                SCREEN_WIDTH as u32,
+                SCREEN_HEIGHT as u32,
+            )
+            .expect("error building the frame texture");
+        texture
+            .update(None, &self.image, self.converter.pitch())
+            .expect("error clearing the frame texture");
+        texture
+    }
 
-                canvas.fill_rect(rect).expect("error drawing point");
+    /// Draws the current Oito frame into the SDL2 Canvas.
+    /// Without filter only the region changed since the last frame is uploaded to the texture,
+    /// with it the whole frame is uploaded each time the filtered intensity changes.
+    pub fn draw_frame(
+        &mut self,
+        oito: &mut OitoCore,
+        canvas: &mut Canvas<Window>,
+        texture: &mut Texture,
+    ) {
+        let damage = oito.take_damage();
+        let area = if self.filter.filter() == Filter::None {
+            let area = damage.bounds();
+            if area.is_some() {
+                self.converter.convert(oito.frame_rows(), &mut self.image);
             }
+            area
+        } else if self.filter.apply(oito.frame_rows()) {
+            self.converter
+                .convert_filtered(&self.filter, &mut self.image);
+            Damage::FULL.bounds()
+        } else {
+            None
+        };
+
+        if let Some(area) = area {
+            let pitch = self.converter.pitch();
+            let start = area.y * pitch + area.x * PixelFormat::Rgba8888.bytes_per_pixel();
+            let rect = Rect::new(
+                area.x as i32,
+                area.y as i32,
+                area.width as u32,
+                area.height as u32,
+            );
+            texture
+                .update(rect, &self.image[start..], pitch)
+                .expect("error updating the frame texture");
         }
 
+        canvas
+            .copy(texture, None, None)
+            .expect("error drawing the frame");
         canvas.present();
     }
-}
\ No newline at end of file
+}
+
+/// Window where the frames are drawn
+pub struct Screen<'a> {
+    pub renderer: Renderer,
+    pub canvas: Canvas<Window>,
+    pub texture: Texture<'a>,
+}
+
+impl VideoSink for Screen<'_> {
+    fn draw(&mut self, oito: &mut OitoCore) {
+        self.renderer
+            .draw_frame(oito, &mut self.canvas, &mut self.texture);
+    }
+}
@@ -1,4 +1,5 @@
-use sdl2::audio::{AudioSpecDesired, AudioSpecWAV, AudioCallback};
+use oito_frontend::io::AudioSink;
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired, AudioSpecWAV};
 
 pub const SOUND_SPEC: AudioSpecDesired = AudioSpecDesired {
     freq: Some(44100),
@@ -35,4 +36,17 @@ impl AudioCallback for Beep {
             *dst = *src;
         }
     }
-}
\ No newline at end of file
+}
+
+/// Audio device playing the beep while the sound timer is on
+pub struct Speaker(pub AudioDevice<Beep>);
+
+impl AudioSink for Speaker {
+    fn beep(&mut self, on: bool) {
+        if on {
+            self.0.resume();
+        } else {
+            self.0.pause();
+        }
+    }
+}
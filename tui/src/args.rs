@@ -0,0 +1,27 @@
+use structopt::StructOpt;
+use tui::render::Glyphs;
+
+/// Run a Chip-8 ROM in the terminal
+#[derive(Debug, StructOpt)]
+pub struct Args {
+    /// The ROM file to read
+    pub file: String,
+    /// Characters drawing the pixels: halfblock or braille
+    #[structopt(long = "glyphs", short = "g", default_value = "halfblock")]
+    pub glyphs: Glyphs,
+    /// File binding the characters of the terminal to the keys of the keypad
+    #[structopt(long = "keys", short = "k")]
+    pub keys: Option<String>,
+    /// File binding the actions (arrows and space) to the keys of each ROM
+    #[structopt(long = "actions")]
+    pub actions: Option<String>,
+    /// Milliseconds a key is held after its last press, when the terminal doesn't report the releases
+    #[structopt(long = "hold", default_value = "200")]
+    pub hold: u64,
+    /// Rings the terminal bell with the beeps
+    #[structopt(long = "bell")]
+    pub bell: bool,
+    /// Fx0A waits for the key to be released, like the COSMAC VIP
+    #[structopt(long = "wait-release")]
+    pub wait_release: bool,
+}
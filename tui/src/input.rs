@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use oito_core::key::Key;
+use oito_frontend::{
+    io::{Input, InputSource},
+    keymap::{Keymap, PhysicalKey},
+};
+
+/// Characters of the keypad in a QWERTY layout, by the value of the key in their position
+const QWERTY: [char; Key::SIZE] = [
+    'x', '1', '2', '3', 'q', 'w', 'e', 'a', 's', 'd', 'z', 'c', '4', 'r', 'f', 'v',
+];
+
+/// Keys of the keypad pressed by the characters typed in the terminal, read from a file like:
+///
+/// ```text
+/// # AZERTY layout
+/// a = 4
+/// z = 5
+/// q = 7
+/// w = A
+/// ```
+///
+/// The bindings in the file are applied over the QWERTY layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bindings {
+    keys: HashMap<char, Key>,
+}
+
+impl Bindings {
+    /// Returns the key bound to the character, ignoring the case
+    pub fn key(&self, c: char) -> Option<Key> {
+        self.keys.get(&c.to_ascii_lowercase()).copied()
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            keys: Key::iter()
+                .map(|key| (QWERTY[u8::from(key) as usize], key))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for Bindings {
+    type Err = String;
+
+    /// Parses the `CHAR = KEY` lines, with the key in hexadecimal.
+    /// Empty lines and the ones starting with `#` are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bindings = Self::default();
+        for (number, line) in s.lines().enumerate() {
+            let invalid = || format!("invalid key binding at line {}: {line}", number + 1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (c, key) = line.split_once('=').ok_or_else(invalid)?;
+            let mut chars = c.trim().chars();
+            let c = chars
+                .next()
+                .filter(|_| chars.next().is_none())
+                .ok_or_else(invalid)?;
+            let key = u8::from_str_radix(key.trim(), 16)
+                .ok()
+                .and_then(|key| Key::try_from(key).ok())
+                .ok_or_else(invalid)?;
+            bindings.keys.retain(|_, bound| *bound != key);
+            bindings.keys.insert(c.to_ascii_lowercase(), key);
+        }
+        Ok(bindings)
+    }
+}
+
+/// Keys held by the terminal presses.
+/// Most terminals only report the presses, repeating them while the key is held,
+/// so the keys are released when no press is received for a while.
+#[derive(Debug)]
+pub struct Holds {
+    /// Time a key is held after its last press, none if the terminal reports the releases
+    hold: Option<Duration>,
+    /// Moment when each held key will be released, if it times out
+    deadlines: HashMap<Key, Option<Instant>>,
+}
+
+impl Holds {
+    pub fn new(hold: Option<Duration>) -> Self {
+        Self {
+            hold,
+            deadlines: HashMap::new(),
+        }
+    }
+
+    /// Holds the key until the timeout, or extends the hold if it was already held.
+    /// Returns the press only if the key was not held.
+    pub fn press(&mut self, key: Key, now: Instant) -> Option<Input> {
+        let deadline = self.hold.map(|hold| now + hold);
+        let held = self.deadlines.insert(key, deadline).is_some();
+        (!held).then_some(Input::Press(key))
+    }
+
+    /// Stops holding the key, returning the release if it was held
+    pub fn release(&mut self, key: Key) -> Option<Input> {
+        self.deadlines.remove(&key).map(|_| Input::Release(key))
+    }
+
+    /// Returns the releases of the keys whose hold ran out
+    pub fn expired(&mut self, now: Instant) -> Vec<Input> {
+        let mut expired: Vec<Key> = self
+            .deadlines
+            .iter()
+            .filter(|(_, deadline)| deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(key, _)| *key)
+            .collect();
+        expired.sort_by_key(|key| u8::from(*key));
+        expired
+            .into_iter()
+            .filter_map(|key| self.release(key))
+            .collect()
+    }
+}
+
+/// Keyboard of the terminal.
+/// Characters are mapped with the bindings, the arrows, space, enter and the controls with the keymap.
+pub struct Keyboard {
+    bindings: Bindings,
+    keymap: Keymap,
+    holds: Holds,
+}
+
+impl Keyboard {
+    pub fn new(bindings: Bindings, keymap: Keymap, hold: Option<Duration>) -> Self {
+        Self {
+            bindings,
+            keymap,
+            holds: Holds::new(hold),
+        }
+    }
+
+    /// Returns the input of the key event, holding the pressed keys until they are released or time out
+    fn input(&mut self, event: KeyEvent, now: Instant) -> Option<Input> {
+        if event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL) {
+            return Some(Input::Quit);
+        }
+        let pressed = event.kind != KeyEventKind::Release;
+        let input = match event.code {
+            KeyCode::Char(c) if c != ' ' => self.bindings.key(c).map(|key| {
+                if pressed {
+                    Input::Press(key)
+                } else {
+                    Input::Release(key)
+                }
+            }),
+            code => self.keymap.input(physical_key(code)?, pressed),
+        };
+        match input? {
+            Input::Press(key) => self.holds.press(key, now),
+            Input::Release(key) => self.holds.release(key),
+            control => Some(control).filter(|_| event.kind == KeyEventKind::Press),
+        }
+    }
+}
+
+impl InputSource for Keyboard {
+    fn poll(&mut self) -> Vec<Input> {
+        let mut inputs = Vec::new();
+        while event::poll(Duration::ZERO).expect("error reading the terminal") {
+            let now = Instant::now();
+            if let Event::Key(event) = event::read().expect("error reading the terminal") {
+                inputs.extend(self.input(event, now));
+            }
+        }
+        inputs.extend(self.holds.expired(Instant::now()));
+        inputs
+    }
+}
+
+/// Returns the host key of the terminal key that's not a character
+fn physical_key(code: KeyCode) -> Option<PhysicalKey> {
+    match code {
+        KeyCode::Up => Some(PhysicalKey::Up),
+        KeyCode::Down => Some(PhysicalKey::Down),
+        KeyCode::Left => Some(PhysicalKey::Left),
+        KeyCode::Right => Some(PhysicalKey::Right),
+        KeyCode::Char(' ') => Some(PhysicalKey::Space),
+        KeyCode::Enter => Some(PhysicalKey::Enter),
+        KeyCode::Esc => Some(PhysicalKey::Escape),
+        KeyCode::F(5) => Some(PhysicalKey::F5),
+        KeyCode::F(6) => Some(PhysicalKey::F6),
+        KeyCode::F(7) => Some(PhysicalKey::F7),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use oito_core::key::Key;
+    use oito_frontend::{io::Input, keymap::Keymap};
+
+    use super::{Bindings, Holds, Keyboard};
+
+    const HOLD: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn bindings() {
+        let bindings = Bindings::default();
+        assert_eq!(Some(Key::Four), bindings.key('q'));
+        assert_eq!(Some(Key::Four), bindings.key('Q'));
+        assert_eq!(Some(Key::Zero), bindings.key('x'));
+        assert_eq!(None, bindings.key('p'));
+    }
+
+    #[test]
+    fn bindings_from_str() {
+        let bindings: Bindings = "# AZERTY\na = 4\nz = 5".parse().unwrap();
+        assert_eq!(Some(Key::Four), bindings.key('a'));
+        assert_eq!(Some(Key::Five), bindings.key('z'));
+        assert_eq!(None, bindings.key('q'));
+        assert_eq!(Some(Key::Six), bindings.key('e'));
+
+        assert!("ab = 4".parse::<Bindings>().is_err());
+        assert!("a = 10".parse::<Bindings>().is_err());
+        assert!("a".parse::<Bindings>().is_err());
+    }
+
+    #[test]
+    fn holds() {
+        let mut holds = Holds::new(Some(HOLD));
+        let start = Instant::now();
+
+        assert_eq!(Some(Input::Press(Key::A)), holds.press(Key::A, start));
+        // Repeated presses extend the hold
+        assert_eq!(None, holds.press(Key::A, start + HOLD / 2));
+        assert!(holds.expired(start + HOLD).is_empty());
+        assert_eq!(
+            vec![Input::Release(Key::A)],
+            holds.expired(start + HOLD * 2)
+        );
+        assert!(holds.expired(start + HOLD * 3).is_empty());
+    }
+
+    #[test]
+    fn release() {
+        let mut holds = Holds::new(None);
+        let start = Instant::now();
+
+        holds.press(Key::One, start);
+        assert!(holds.expired(start + HOLD * 100).is_empty());
+        assert_eq!(Some(Input::Release(Key::One)), holds.release(Key::One));
+        assert_eq!(None, holds.release(Key::One));
+        assert!(holds.expired(start + HOLD).is_empty());
+    }
+
+    #[test]
+    fn keyboard() {
+        let mut keyboard = Keyboard::new(Bindings::default(), Keymap::default(), Some(HOLD));
+        let now = Instant::now();
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert_eq!(
+            Some(Input::Press(Key::Five)),
+            keyboard.input(key(KeyCode::Char('w')), now)
+        );
+        assert_eq!(
+            Some(Input::Press(Key::Two)),
+            keyboard.input(key(KeyCode::Up), now)
+        );
+        let mut release = key(KeyCode::Char('w'));
+        release.kind = KeyEventKind::Release;
+        assert_eq!(
+            Some(Input::Release(Key::Five)),
+            keyboard.input(release, now)
+        );
+        assert_eq!(
+            Some(Input::Press(Key::Five)),
+            keyboard.input(key(KeyCode::Char(' ')), now)
+        );
+        assert_eq!(Some(Input::Quit), keyboard.input(key(KeyCode::Esc), now));
+        assert_eq!(
+            Some(Input::Quit),
+            keyboard.input(
+                KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                now
+            )
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use oito_core::{
+    action::ActionProfiles,
+    config::{Config, Quirks},
+};
+use oito_frontend::{keymap::Keymap, runner::Runner};
+use rom_loader::{desktop::FilePathLoader, RomLoader};
+use std::{
+    error::Error,
+    fs,
+    io::{self, stdout},
+    path::Path,
+    time::Duration,
+};
+use structopt::StructOpt;
+use tui::{
+    input::{Bindings, Keyboard},
+    render::Screen,
+    sound::Bell,
+};
+
+mod args;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = args::Args::from_args();
+
+    let bindings: Bindings = match &args.keys {
+        Some(path) => fs::read_to_string(path)?.parse()?,
+        None => Bindings::default(),
+    };
+    let profiles: ActionProfiles = match &args.actions {
+        Some(path) => fs::read_to_string(path)?.parse()?,
+        None => ActionProfiles::default(),
+    };
+    let rom_name = Path::new(&args.file)
+        .file_stem()
+        .and_then(|name| name.to_str());
+    let keymap = Keymap::new(profiles.map(rom_name.unwrap_or_default()));
+
+    let config = Config {
+        quirks: Quirks {
+            wait_release: args.wait_release,
+        },
+        ..Default::default()
+    };
+    let loader = FilePathLoader::new(&args.file);
+    let mut runner = Runner::new(config, loader.rom());
+
+    let terminal = Terminal::setup()?;
+    let hold = (!terminal.releases).then(|| Duration::from_millis(args.hold));
+    let mut keyboard = Keyboard::new(bindings, keymap, hold);
+    let mut screen = Screen::new(stdout(), args.glyphs);
+    let mut bell = Bell::new(stdout(), args.bell);
+
+    let result = runner.run(&mut screen, &mut bell, &mut keyboard);
+    drop(terminal);
+    Ok(result?)
+}
+
+/// Terminal in raw mode, restored when dropped
+struct Terminal {
+    /// True if the terminal reports the key releases
+    releases: bool,
+}
+
+impl Terminal {
+    fn setup() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        let releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if releases {
+            execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+        }
+        Ok(Self { releases })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if self.releases {
+            let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+        }
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
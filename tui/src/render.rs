@@ -0,0 +1,151 @@
+use std::{io::Write, str::FromStr};
+
+use crossterm::{cursor::MoveTo, queue, style::Print};
+use oito_core::{core::OitoCore, Row, SCREEN_HEIGHT, SCREEN_WIDTH};
+use oito_frontend::io::VideoSink;
+
+/// Characters used to draw the pixels in the terminal cells
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Glyphs {
+    /// Two pixels per cell, one above the other, drawing the frame in 64x16 cells
+    #[default]
+    HalfBlock,
+    /// Eight pixels per cell, in two columns of four, drawing the frame in 32x8 cells
+    Braille,
+}
+
+impl Glyphs {
+    /// Returns the number of pixels drawn by each cell, in width and height
+    pub fn cell(&self) -> (usize, usize) {
+        match self {
+            Glyphs::HalfBlock => (1, 2),
+            Glyphs::Braille => (2, 4),
+        }
+    }
+
+    /// Returns the character drawing the pixels of the cell, indexed by their column and row inside it
+    fn glyph(&self, lit: impl Fn(usize, usize) -> bool) -> char {
+        match self {
+            Glyphs::HalfBlock => match (lit(0, 0), lit(0, 1)) {
+                (false, false) => ' ',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (true, true) => '█',
+            },
+            Glyphs::Braille => {
+                const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+                let mut bits = 0;
+                for (x, column) in DOTS.iter().enumerate() {
+                    for (y, dot) in column.iter().enumerate() {
+                        if lit(x, y) {
+                            bits |= dot;
+                        }
+                    }
+                }
+                char::from_u32(0x2800 + bits).unwrap_or(' ')
+            }
+        }
+    }
+}
+
+impl FromStr for Glyphs {
+    type Err = String;
+
+    /// Parses the glyphs: `halfblock` or `braille`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "halfblock" => Ok(Glyphs::HalfBlock),
+            "braille" => Ok(Glyphs::Braille),
+            _ => Err(format!("invalid glyphs: {s}")),
+        }
+    }
+}
+
+/// Returns the lines of text drawing the frame, packed in rows of bits, with the specified glyphs
+pub fn lines(rows: &[Row], glyphs: Glyphs) -> Vec<String> {
+    let (width, height) = glyphs.cell();
+    let lit = |x: usize, y: usize| y < rows.len() && rows[y] >> (SCREEN_WIDTH - 1 - x) & 1 == 1;
+    (0..SCREEN_HEIGHT / height)
+        .map(|line| {
+            (0..SCREEN_WIDTH / width)
+                .map(|cell| glyphs.glyph(|x, y| lit(cell * width + x, line * height + y)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Terminal where the frames are drawn, from its top left corner
+pub struct Screen<W: Write> {
+    out: W,
+    glyphs: Glyphs,
+    /// Forces the next draw to paint the frame even if it didn't change
+    redraw: bool,
+}
+
+impl<W: Write> Screen<W> {
+    pub fn new(out: W, glyphs: Glyphs) -> Self {
+        Self {
+            out,
+            glyphs,
+            redraw: true,
+        }
+    }
+}
+
+impl<W: Write> VideoSink for Screen<W> {
+    /// Draws the whole frame when any part of it changed since the last draw
+    fn draw(&mut self, oito: &mut OitoCore) {
+        let changed = oito.take_damage().bounds().is_some();
+        if !std::mem::take(&mut self.redraw) && !changed {
+            return;
+        }
+        for (y, line) in lines(oito.frame_rows(), self.glyphs).iter().enumerate() {
+            queue!(self.out, MoveTo(0, y as u16), Print(line)).expect("error drawing the frame");
+        }
+        self.out.flush().expect("error drawing the frame");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_core::SCREEN_HEIGHT;
+
+    use super::{lines, Glyphs};
+
+    /// Frame with the top left pixel, the second pixel of the second row and the bottom right pixel lit
+    fn rows() -> [u64; SCREEN_HEIGHT] {
+        let mut rows = [0; SCREEN_HEIGHT];
+        rows[0] = 1 << 63;
+        rows[1] = 1 << 62;
+        rows[SCREEN_HEIGHT - 1] = 1;
+        rows
+    }
+
+    #[test]
+    fn half_blocks() {
+        let lines = lines(&rows(), Glyphs::HalfBlock);
+
+        assert_eq!(16, lines.len());
+        assert!(lines.iter().all(|line| line.chars().count() == 64));
+        assert!(lines[0].starts_with("▀▄ "));
+        assert!(lines[15].ends_with(" ▄"));
+        assert!(lines[8].chars().all(|c| c == ' '));
+    }
+
+    #[test]
+    fn braille() {
+        let lines = lines(&rows(), Glyphs::Braille);
+
+        assert_eq!(8, lines.len());
+        assert!(lines.iter().all(|line| line.chars().count() == 32));
+        assert!(lines[0].starts_with("⠑⠀"));
+        assert!(lines[7].ends_with('⢀'));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Ok(Glyphs::Braille), "braille".parse());
+        assert_eq!(Ok(Glyphs::HalfBlock), "halfblock".parse());
+        assert!("ascii".parse::<Glyphs>().is_err());
+    }
+}
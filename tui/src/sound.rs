@@ -0,0 +1,54 @@
+use std::io::Write;
+
+use oito_frontend::io::AudioSink;
+
+/// Terminal bell, rung at the start of each beep. Silent if it's not enabled.
+pub struct Bell<W: Write> {
+    out: W,
+    enabled: bool,
+    /// True while the beep is on
+    ringing: bool,
+}
+
+impl<W: Write> Bell<W> {
+    pub fn new(out: W, enabled: bool) -> Self {
+        Self {
+            out,
+            enabled,
+            ringing: false,
+        }
+    }
+}
+
+impl<W: Write> AudioSink for Bell<W> {
+    fn beep(&mut self, on: bool) {
+        if self.enabled && on && !self.ringing {
+            self.out
+                .write_all(b"\x07")
+                .and_then(|_| self.out.flush())
+                .expect("error ringing the bell");
+        }
+        self.ringing = on;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oito_frontend::io::AudioSink;
+
+    use super::Bell;
+
+    #[test]
+    fn beep() {
+        let mut bell = Bell::new(Vec::new(), true);
+        bell.beep(true);
+        bell.beep(true);
+        bell.beep(false);
+        bell.beep(true);
+        assert_eq!(b"\x07\x07", bell.out.as_slice());
+
+        let mut bell = Bell::new(Vec::new(), false);
+        bell.beep(true);
+        assert!(bell.out.is_empty());
+    }
+}
@@ -1,13 +1,29 @@
-use oito_core::{core::OitoCore, SCREEN_WIDTH, SCREEN_HEIGHT};
+use oito_core::{
+    action::ActionProfiles,
+    config::Config,
+    video::{Filter, FrameFilter, Rgb},
+};
+use oito_frontend::{
+    keymap::{Keymap, PhysicalKey},
+    runner::Runner,
+};
+use screen::{Beeper, Screen};
 use wasm_bindgen::{prelude::*, JsCast};
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, KeyboardEvent};
 
-mod input;
+mod screen;
 
 #[wasm_bindgen]
 pub struct OitoWasm {
-    oito: OitoCore,
-    ctx: CanvasRenderingContext2d,
+    runner: Runner,
+    screen: Screen,
+    beeper: Beeper,
+    /// Bindings of the actions of each ROM
+    profiles: ActionProfiles,
+    /// Translation of the keyboard, with the actions of the current ROM
+    keymap: Keymap,
+    /// Name of the current ROM without extension
+    rom: String,
 }
 
 #[wasm_bindgen]
@@ -29,66 +45,106 @@ impl OitoWasm {
             .unwrap();
 
         Self {
-            oito: OitoCore::new(),
-            ctx,
+            runner: Runner::new(Config::default(), &[]),
+            screen: Screen::new(ctx),
+            beeper: Beeper::default(),
+            profiles: ActionProfiles::default(),
+            keymap: Keymap::default(),
+            rom: String::new(),
         }
     }
 
+    /// Runs a frame of the emulator and draws it with the specified scale and colors
     #[wasm_bindgen]
-    pub fn tick(&mut self) {
-        self.oito.tick().unwrap();
+    pub fn frame(&mut self, scale: usize, background: &str, foreground: &str) {
+        self.screen.scale = scale;
+        self.screen.bg = background.parse().unwrap_or(Rgb(0x00, 0x00, 0x00));
+        self.screen.fg = foreground.parse().unwrap_or(Rgb(0xFF, 0xFF, 0xFF));
+        self.runner.frame(&mut self.screen, &mut self.beeper).unwrap();
     }
 
+    /// Loads the ROM, binding the actions to the keys of its profile, found by the file name
     #[wasm_bindgen]
-    pub fn frame_tick(&mut self) {
-        self.oito.frame_tick();
+    pub fn load(&mut self, data: js_sys::Uint8Array, name: &str) {
+        self.runner.load(&data.to_vec());
+        let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        self.rom = stem.to_string();
+        self.keymap.set_actions(self.profiles.map(&self.rom));
     }
 
+    /// Sets the bindings of the actions of each ROM, with the format of the action files.
+    /// Returns false if the bindings are not valid.
     #[wasm_bindgen]
-    pub fn load(&mut self, data: js_sys::Uint8Array) {
-        self.oito.load(&data.to_vec());
+    pub fn set_actions(&mut self, bindings: &str) -> bool {
+        match bindings.parse::<ActionProfiles>() {
+            Ok(profiles) => {
+                self.profiles = profiles;
+                self.keymap.set_actions(self.profiles.map(&self.rom));
+                true
+            }
+            Err(_) => false,
+        }
     }
 
     /// Emmulates the pressing of the desired key
     pub fn key_press(&mut self, key: KeyboardEvent) {
-		let code = key.code();
-		if let Some(key) = input::map_key(&code) {
-			self.oito.key_press(key);
-		}
+        if !key.repeat() {
+            self.key(&key.code(), true);
+        }
     }
 
     /// Emmulates the release of the desired key
     pub fn key_release(&mut self, key: KeyboardEvent) {
-        let code = key.code();
-		if let Some(key) = input::map_key(&code) {
-			self.oito.key_release(key);
-		}
+        self.key(&key.code(), false);
     }
 
+    /// Sets the filter to reduce the flickering: none, phosphor[:decay] or blend[:frames].
+    /// Returns false if the filter is not valid.
     #[wasm_bindgen]
-    pub fn draw(&mut self, scale: usize) {
-        let disp = self.oito.frame_buffer();
-        for i in 0..(SCREEN_WIDTH * SCREEN_HEIGHT) {
-            if disp[i] {
-                let x = i % SCREEN_WIDTH;
-                let y = i / SCREEN_WIDTH;
-                self.ctx.fill_rect(
-                    (x * scale) as f64,
-                    (y * scale) as f64,
-                    scale as f64,
-                    scale as f64,
-                );
+    pub fn set_filter(&mut self, filter: &str) -> bool {
+        match filter.parse::<Filter>() {
+            Ok(filter) => {
+                self.screen.filter = FrameFilter::new(filter);
+                self.screen.redraw = true;
+                true
             }
+            Err(_) => false,
         }
     }
 
+    /// Forces the next draw to paint the whole frame, needed when the colors or scale change
+    #[wasm_bindgen]
+    pub fn invalidate(&mut self) {
+        self.screen.redraw = true;
+    }
+
 	#[wasm_bindgen]
 	pub fn sound(&self) -> bool {
-		self.oito.sound()
+		self.beeper.on
 	}
 
     #[wasm_bindgen]
     pub fn reset(&mut self) {
-        self.oito = OitoCore::new();
+        self.runner.reset();
+    }
+}
+
+impl OitoWasm {
+    /// Sends the input of the key with the specified code to the runner.
+    /// Quitting is left to the page.
+    fn key(&mut self, code: &str, pressed: bool) {
+        let input = code
+            .parse::<PhysicalKey>()
+            .ok()
+            .and_then(|key| self.keymap.input(key, pressed));
+        if let Some(input) = input {
+            self.runner.handle(input).unwrap();
+        }
+    }
+}
+
+impl Default for OitoWasm {
+    fn default() -> Self {
+        Self::new()
     }
 }
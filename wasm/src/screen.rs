@@ -0,0 +1,100 @@
+use oito_core::{
+    core::OitoCore,
+    damage::Damage,
+    video::{Filter, FrameConverter, FrameFilter, Palette, PixelFormat, Rgb, Upscaler},
+    SCREEN_WIDTH,
+};
+use oito_frontend::io::{AudioSink, VideoSink};
+use wasm_bindgen::Clamped;
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+/// Canvas of the page where the frames are drawn
+pub struct Screen {
+    pub ctx: CanvasRenderingContext2d,
+    /// Forces the next draw to paint the whole frame
+    pub redraw: bool,
+    /// Filter applied to reduce the flickering
+    pub filter: FrameFilter,
+    /// Last frame converted into RGBA pixels
+    image: Vec<u8>,
+    pub scale: usize,
+    pub bg: Rgb,
+    pub fg: Rgb,
+}
+
+impl Screen {
+    pub fn new(ctx: CanvasRenderingContext2d) -> Self {
+        Self {
+            ctx,
+            redraw: true,
+            filter: FrameFilter::default(),
+            image: Vec::new(),
+            scale: 12,
+            bg: Rgb(0x00, 0x00, 0x00),
+            fg: Rgb(0xFF, 0xFF, 0xFF),
+        }
+    }
+}
+
+impl VideoSink for Screen {
+    /// Draws the region of the frame changed since the last draw,
+    /// or the whole filtered frame if a filter is set and its intensity changed
+    fn draw(&mut self, oito: &mut OitoCore) {
+        let (scale, bg, fg) = (self.scale, self.bg, self.fg);
+        let upscaler = Upscaler::Nearest(scale);
+        let damage = oito.take_damage();
+        let redraw = std::mem::take(&mut self.redraw);
+
+        let area = if self.filter.filter() == Filter::None {
+            let area = if redraw {
+                Damage::FULL.bounds()
+            } else {
+                damage.bounds()
+            };
+            if area.is_some() {
+                let converter =
+                    FrameConverter::new(Palette::mono(bg, fg), PixelFormat::Rgba8888, upscaler);
+                self.image.resize(converter.buffer_size(), 0);
+                converter.convert(oito.frame_rows(), &mut self.image);
+            }
+            area
+        } else if self.filter.apply(oito.frame_rows()) || redraw {
+            let converter =
+                FrameConverter::new(Palette::gradient(bg, fg), PixelFormat::Rgba8888, upscaler);
+            self.image.resize(converter.buffer_size(), 0);
+            converter.convert_filtered(&self.filter, &mut self.image);
+            Damage::FULL.bounds()
+        } else {
+            None
+        };
+
+        if let Some(area) = area {
+            let width = upscaler.factor() * SCREEN_WIDTH;
+            let image =
+                ImageData::new_with_u8_clamped_array(Clamped(&self.image), width as u32).unwrap();
+            self.ctx
+                .put_image_data_with_dirty_x_and_dirty_y_and_dirty_width_and_dirty_height(
+                    &image,
+                    0.0,
+                    0.0,
+                    (area.x * scale) as f64,
+                    (area.y * scale) as f64,
+                    (area.width * scale) as f64,
+                    (area.height * scale) as f64,
+                )
+                .unwrap();
+        }
+    }
+}
+
+/// State of the beep, played by the page
+#[derive(Default)]
+pub struct Beeper {
+    pub on: bool,
+}
+
+impl AudioSink for Beeper {
+    fn beep(&mut self, on: bool) {
+        self.on = on;
+    }
+}
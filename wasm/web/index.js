@@ -2,7 +2,6 @@ import init, * as wasm from "./wasm.js";
 
 const WIDTH = 64;
 const HEIGHT = 32;
-const TICKS_PER_FRAME = 10;
 
 let current_frame = 0;
 let background = "#000000";
@@ -23,6 +22,8 @@ const input = document.getElementById("rom_input");
 const bg_picker = document.getElementById("bg_picker");
 const fg_picker = document.getElementById("fg_picker");
 const scale_picker = document.getElementById("scale_picker");
+const filter_picker = document.getElementById("filter_picker");
+const actions_input = document.getElementById("actions_input");
 
 const run = async () => {
 	await init();
@@ -41,6 +42,7 @@ const run = async () => {
 		"change",
 		(e) => {
 			background = e.target.value;
+			oito.invalidate();
 		},
 		false
 	);
@@ -49,6 +51,7 @@ const run = async () => {
 		"change",
 		(e) => {
 			foreground = e.target.value;
+			oito.invalidate();
 		},
 		false
 	);
@@ -59,10 +62,35 @@ const run = async () => {
 			scale = e.target.value;
 			canvas.width = WIDTH * scale;
 			canvas.height = HEIGHT * scale;
+			oito.invalidate();
 		},
 		false
 	)
 
+	filter_picker.addEventListener(
+		"change",
+		(e) => {
+			oito.set_filter(e.target.value);
+		},
+		false
+	);
+
+	actions_input.addEventListener(
+		"change",
+		(e) => {
+			let file = e.target.files[0];
+			if (!file) {
+				return;
+			}
+			file.text().then((bindings) => {
+				if (!oito.set_actions(bindings)) {
+					alert("Invalid action bindings");
+				}
+			});
+		},
+		false
+	);
+
 	input.addEventListener(
 		"change",
 		(e) => {
@@ -81,7 +109,7 @@ const run = async () => {
 				let buffer = fr.result;
 				const rom = new Uint8Array(buffer);
 				oito.reset();
-				oito.load(rom);
+				oito.load(rom, file.name);
 				gameloop(oito);
 			};
 			fr.readAsArrayBuffer(file);
@@ -91,16 +119,7 @@ const run = async () => {
 };
 
 const gameloop = (oito) => {
-	for (let i = 0; i < TICKS_PER_FRAME; i++) {
-		oito.tick();
-	}
-	oito.frame_tick();
-
-	ctx.fillStyle = background;
-	ctx.fillRect(0, 0, WIDTH * scale, HEIGHT * scale);
-
-	ctx.fillStyle = foreground;
-	oito.draw(scale);
+	oito.frame(scale, background, foreground);
 
 	current_frame = window.requestAnimationFrame(() => {
 		gameloop(oito);